@@ -4,12 +4,13 @@
 //! Run with `cargo run --example line_fitting`.
 
 
-#![allow(clippy::many_single_char_names)]
+#![allow(clippy::many_single_char_names, clippy::upper_case_acronyms)]
 
 
 extern crate env_logger;
 extern crate rand;
 extern crate rand_distr;
+extern crate rand_pcg;
 
 extern crate optimization;
 
@@ -17,6 +18,7 @@ extern crate optimization;
 use std::f64::consts::PI;
 use rand::prelude::*;
 use rand_distr::StandardNormal;
+use rand_pcg::Pcg64Mcg;
 
 use optimization::*;
 
@@ -30,9 +32,12 @@ fn main() {
     println!("Trying to approximate the true linear regression coefficients {:?} using SGD \
         given 100 noisy samples", true_coefficients);
 
+    // a seeded random number generator makes the results re-producable
+    let mut rng = Pcg64Mcg::seed_from_u64(42);
+
     let noisy_observations = (0..100).map(|_| {
-        let x = random::<[f64; 2]>();
-        let noise: f64 = thread_rng().sample(StandardNormal);
+        let x = rng.gen::<[f64; 2]>();
+        let noise: f64 = rng.sample(StandardNormal);
         let y = linear_regression(true_coefficients, &x) + noise;
 
         (x.to_vec(), y)
@@ -46,6 +51,7 @@ fn main() {
     };
 
     let solution = StochasticGradientDescent::new()
+        .seed(42)
         .max_iterations(Some(1000))
         .minimize(&sse, vec![1.0; true_coefficients.len()]);
 
@@ -76,7 +82,7 @@ impl Summation1 for SSE {
 
         let e = y - linear_regression(w, x);
 
-        let mut gradient = vec![e * -1.0];
+        let mut gradient = vec![-e];
 
         for x in x {
             gradient.push(e * -x);
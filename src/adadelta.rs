@@ -0,0 +1,191 @@
+use rand::{SeedableRng, random};
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64Mcg;
+
+use types::{Minimizer, Solution, Summation1, SummationAccuracy, ConfigError, check_range,
+    check_count};
+
+
+/// Small constant preventing divisions by zero and bootstrapping the very first steps.
+const EPSILON: f64 = 1.0e-6;
+
+
+/// Provides the _Adadelta_ optimizer, a stochastic Gradient Descent variant that adapts the
+/// step width per coordinate and therefore requires no step width at all.
+///
+/// Adadelta maintains decaying averages of the squared gradients `E[g²]` as well as of the
+/// squared updates `E[Δx²]` and applies the update `Δx = -√(E[Δx²] + ε) / √(E[g²] + ε) · g`.
+#[derive(Debug, Clone)]
+pub struct Adadelta {
+    rng: Pcg64Mcg,
+    max_iterations: Option<u64>,
+    mini_batch: usize,
+    summation_accuracy: SummationAccuracy,
+    decay: f64
+}
+
+impl Adadelta {
+    /// Creates a new `Adadelta` optimizer using the following defaults:
+    ///
+    /// - **`decay`** = `0.95`
+    /// - **`mini_batch`** = `1`
+    /// - **`summation_accuracy`** = `SummationAccuracy::Sequential`
+    /// - **`max_iterations`** = `1000`
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new() -> Adadelta {
+        Adadelta {
+            rng: Pcg64Mcg::new(random()),
+            max_iterations: Some(1000),
+            mini_batch: 1,
+            summation_accuracy: SummationAccuracy::Sequential,
+            decay: 0.95
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(&mut self, max_iterations: Option<u64>) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Adjusts the mini batch size, i.e., how many terms are considered in one step at most.
+    pub fn mini_batch(&mut self, mini_batch: usize) -> &mut Self {
+        self.mini_batch = mini_batch;
+        self
+    }
+
+    /// Adjusts how the terms are summed up when evaluating the objective function, see
+    /// `SummationAccuracy`.
+    pub fn summation_accuracy(&mut self, summation_accuracy: SummationAccuracy) -> &mut Self {
+        self.summation_accuracy = summation_accuracy;
+        self
+    }
+
+    /// Adjusts the decay `ρ` ∈ (0, 1) of the running averages of the squared gradients
+    /// and the squared updates.
+    pub fn decay(&mut self, decay: f64) -> &mut Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        check_count("mini_batch", self.mini_batch as u64)?;
+        check_range("decay", self.decay, "(0, 1)", self.decay > 0.0 && self.decay < 1.0)
+    }
+}
+
+impl Default for Adadelta {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Summation1> Minimizer<F> for Adadelta {
+    type Solution = Solution;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "Adadelta",
+            max_iterations = ?self.max_iterations, mini_batch = self.mini_batch,
+            summation_accuracy = ?self.summation_accuracy, decay = self.decay).entered();
+
+        let mut position = initial_position;
+        let mut value = function.value_with(&position, self.summation_accuracy);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut terms: Vec<_> = (0..function.terms()).collect();
+        let mut rng = self.rng.clone();
+
+        let mut squared_gradients = vec![0.0; position.len()];
+        let mut squared_updates = vec![0.0; position.len()];
+
+        loop {
+            // ensure that we don't run into cycles
+            terms.shuffle(&mut rng);
+
+            for batch in terms.chunks(self.mini_batch) {
+                let gradient = function.partial_gradient(&position, batch);
+
+                for (((x, g), eg), edx) in position.iter_mut().zip(gradient)
+                    .zip(squared_gradients.iter_mut()).zip(squared_updates.iter_mut())
+                {
+                    *eg = self.decay * *eg + (1.0 - self.decay) * g * g;
+
+                    let dx = -(*edx + EPSILON).sqrt() / (*eg + EPSILON).sqrt() * g;
+
+                    *edx = self.decay * *edx + (1.0 - self.decay) * dx * dx;
+                    *x += dx;
+                }
+            }
+
+            value = function.value_with(&position, self.summation_accuracy);
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+                return Solution::new(position, value);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::LinearRegression;
+    use types::{Minimizer, ConfigError};
+
+    use super::Adadelta;
+
+    #[test]
+    fn test_linear_regression() {
+        let coefficients = [13.37, -4.2, 2.5];
+        let problem = LinearRegression::new(&coefficients, 100, 42);
+
+        let solution = Adadelta::new()
+            .seed(42)
+            .max_iterations(Some(1000))
+            .minimize(&problem, vec![1.0; 3]);
+
+        for (w, c) in solution.position.iter().zip(&coefficients) {
+            assert!((w - c).abs() < 0.5, "{:?} differs from {:?}", solution.position, coefficients);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Adadelta::new().validate(), Ok(()));
+        assert_eq!(Adadelta::new().decay(1.0).validate(),
+            Err(ConfigError::OutOfRange { name: "decay", value: 1.0, range: "(0, 1)" }));
+    }
+}
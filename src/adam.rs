@@ -0,0 +1,319 @@
+use rand::{SeedableRng, random};
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64Mcg;
+
+use types::{Minimizer, Solution, Summation1, SummationAccuracy, ConfigError, check_range,
+    check_step_width, check_count};
+
+
+/// Provides the _Adam_ optimizer, a stochastic Gradient Descent variant that adapts the
+/// step width per coordinate using estimates of the first and second moments of the
+/// gradients.
+///
+/// Optionally, Nesterov momentum is applied to the first moment, which is also known as
+/// _Nadam_ and often converges slightly faster, see `Adam::nadam()`.
+#[derive(Debug, Clone)]
+pub struct Adam {
+    rng: Pcg64Mcg,
+    max_iterations: Option<u64>,
+    mini_batch: usize,
+    summation_accuracy: SummationAccuracy,
+    learning_rate: f64,
+    beta1: f64,
+    beta2: f64,
+    epsilon: f64,
+    nesterov: bool,
+    amsgrad: bool
+}
+
+impl Adam {
+    /// Creates a new `Adam` optimizer using the following defaults:
+    ///
+    /// - **`learning_rate`** = `0.001`
+    /// - **`beta1`** = `0.9`
+    /// - **`beta2`** = `0.999`
+    /// - **`epsilon`** = `1e-8`
+    /// - **`nesterov`** = `false`
+    /// - **`amsgrad`** = `false`
+    /// - **`mini_batch`** = `1`
+    /// - **`summation_accuracy`** = `SummationAccuracy::Sequential`
+    /// - **`max_iterations`** = `1000`
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new() -> Adam {
+        Adam {
+            rng: Pcg64Mcg::new(random()),
+            max_iterations: Some(1000),
+            mini_batch: 1,
+            summation_accuracy: SummationAccuracy::Sequential,
+            learning_rate: 0.001,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1.0e-8,
+            nesterov: false,
+            amsgrad: false
+        }
+    }
+
+    /// Creates a new _Nadam_ optimizer, i.e., an `Adam` optimizer using the defaults of `new()`
+    /// except for enabling Nesterov momentum, see `nesterov()`.
+    pub fn nadam() -> Adam {
+        Adam {
+            nesterov: true,
+            ..Adam::new()
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(&mut self, max_iterations: Option<u64>) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Adjusts the mini batch size, i.e., how many terms are considered in one step at most.
+    pub fn mini_batch(&mut self, mini_batch: usize) -> &mut Self {
+        self.mini_batch = mini_batch;
+        self
+    }
+
+    /// Adjusts how the terms are summed up when evaluating the objective function, see
+    /// `SummationAccuracy`.
+    pub fn summation_accuracy(&mut self, summation_accuracy: SummationAccuracy) -> &mut Self {
+        self.summation_accuracy = summation_accuracy;
+        self
+    }
+
+    /// Adjusts the learning rate `α`, i.e., the step width applied to the normalized moments.
+    pub fn learning_rate(&mut self, learning_rate: f64) -> &mut Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Adjusts the decay `β₁` ∈ [0, 1) of the first moment estimate.
+    pub fn beta1(&mut self, beta1: f64) -> &mut Self {
+        self.beta1 = beta1;
+        self
+    }
+
+    /// Adjusts the decay `β₂` ∈ [0, 1) of the second moment estimate.
+    pub fn beta2(&mut self, beta2: f64) -> &mut Self {
+        self.beta2 = beta2;
+        self
+    }
+
+    /// Adjusts the small constant `ε` preventing divisions by zero.
+    pub fn epsilon(&mut self, epsilon: f64) -> &mut Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Enables Nesterov momentum, i.e., the _Nadam_ variant, which looks ahead by using
+    /// the first moment estimate of the next step.
+    pub fn nesterov(&mut self, nesterov: bool) -> &mut Self {
+        self.nesterov = nesterov;
+        self
+    }
+
+    /// Enables the _AMSGrad_ variant, which uses the maximum of all past second moment
+    /// estimates. This fixes the convergence issues of Adam on some convex problems.
+    pub fn amsgrad(&mut self, amsgrad: bool) -> &mut Self {
+        self.amsgrad = amsgrad;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        check_count("mini_batch", self.mini_batch as u64)?;
+        check_step_width("learning_rate", self.learning_rate)?;
+        check_range("beta1", self.beta1, "[0, 1)", (0.0..1.0).contains(&self.beta1))?;
+        check_range("beta2", self.beta2, "[0, 1)", (0.0..1.0).contains(&self.beta2))?;
+        check_range("epsilon", self.epsilon, "(0, ∞)",
+            self.epsilon > 0.0 && self.epsilon.is_finite())
+    }
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Summation1> Minimizer<F> for Adam {
+    type Solution = Solution;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "Adam",
+            max_iterations = ?self.max_iterations, mini_batch = self.mini_batch,
+            summation_accuracy = ?self.summation_accuracy,
+            learning_rate = self.learning_rate, beta1 = self.beta1, beta2 = self.beta2,
+            epsilon = self.epsilon, nesterov = self.nesterov, amsgrad = self.amsgrad).entered();
+
+        let mut position = initial_position;
+        let mut value = function.value_with(&position, self.summation_accuracy);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut terms: Vec<_> = (0..function.terms()).collect();
+        let mut rng = self.rng.clone();
+
+        let mut first_moments = vec![0.0; position.len()];
+        let mut second_moments = vec![0.0; position.len()];
+        let mut max_second_moments = vec![0.0; position.len()];
+        let mut step = 0;
+
+        loop {
+            // ensure that we don't run into cycles
+            terms.shuffle(&mut rng);
+
+            for batch in terms.chunks(self.mini_batch) {
+                let gradient = function.partial_gradient(&position, batch);
+
+                step += 1;
+
+                // bias corrections of the moment estimates
+                let c1 = 1.0 - self.beta1.powi(step);
+                let c2 = 1.0 - self.beta2.powi(step);
+
+                for ((((x, g), m), v), v_max) in position.iter_mut().zip(gradient)
+                    .zip(first_moments.iter_mut()).zip(second_moments.iter_mut())
+                    .zip(max_second_moments.iter_mut())
+                {
+                    *m = self.beta1 * *m + (1.0 - self.beta1) * g;
+                    *v = self.beta2 * *v + (1.0 - self.beta2) * g * g;
+
+                    let m_hat = if self.nesterov {
+                        self.beta1 * *m / (1.0 - self.beta1.powi(step + 1)) +
+                            (1.0 - self.beta1) * g / c1
+                    } else {
+                        *m / c1
+                    };
+                    let v_hat = if self.amsgrad {
+                        *v_max = f64::max(*v_max, *v);
+                        *v_max / c2
+                    } else {
+                        *v / c2
+                    };
+
+                    *x -= self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+                }
+            }
+
+            value = function.value_with(&position, self.summation_accuracy);
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+                return Solution::new(position, value);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::LinearRegression;
+    use types::{Minimizer, Summation, Summation1, ConfigError};
+
+    use super::Adam;
+
+    // number of iterations required to reach the `target` value
+    fn iterations_to_target(adam: &mut Adam, problem: &LinearRegression, target: f64) -> u64 {
+        (1..1000).find(|&iterations| {
+            adam.max_iterations(Some(iterations)).minimize(problem, vec![1.0; 3]).value <= target
+        }).expect("target was not reached")
+    }
+
+    #[test]
+    fn test_nadam_is_faster() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let mut adam = Adam::new();
+        adam.seed(42).learning_rate(0.05);
+
+        let mut nadam = Adam::nadam();
+        nadam.seed(42).learning_rate(0.05);
+
+        let adam_iterations = iterations_to_target(&mut adam, &problem, 5.0);
+        let nadam_iterations = iterations_to_target(&mut nadam, &problem, 5.0);
+
+        assert!(nadam_iterations <= adam_iterations,
+            "{} > {}", nadam_iterations, adam_iterations);
+    }
+
+    // convex summation of a rare large and frequent small linear terms, slightly regularized,
+    // for which Adam is known to head into the wrong direction (Reddi et al., 2018)
+    struct Counterexample;
+
+    impl Summation for Counterexample {
+        fn terms(&self) -> usize {
+            3
+        }
+
+        fn term_value(&self, x: &[f64], term: usize) -> f64 {
+            let slope = if term == 0 { 10.0 } else { -1.0 };
+
+            slope * x[0] + 0.5 * x[0].powi(2)
+        }
+    }
+
+    impl Summation1 for Counterexample {
+        fn term_gradient(&self, x: &[f64], term: usize) -> Vec<f64> {
+            let slope = if term == 0 { 10.0 } else { -1.0 };
+
+            vec![slope + x[0]]
+        }
+    }
+
+    #[test]
+    fn test_amsgrad_converges() {
+        // minimum of 8 x + 1.5 x²
+        let minimum = -8.0 / 3.0;
+
+        let mut adam = Adam::new();
+        adam.seed(42).learning_rate(0.1).beta1(0.0).beta2(1.0 / 101.0);
+
+        let plain = adam.minimize(&Counterexample, vec![0.0]);
+        let amsgrad = adam.amsgrad(true).minimize(&Counterexample, vec![0.0]);
+
+        assert!((plain.position[0] - minimum).abs() > 1.0);
+        assert!((amsgrad.position[0] - minimum).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Adam::new().validate(), Ok(()));
+        assert_eq!(Adam::new().beta2(1.0).validate(),
+            Err(ConfigError::OutOfRange { name: "beta2", value: 1.0, range: "[0, 1)" }));
+        assert_eq!(Adam::new().learning_rate(f64::INFINITY).validate(),
+            Err(ConfigError::NonPositiveStepWidth { name: "learning_rate", value: f64::INFINITY }));
+    }
+}
@@ -0,0 +1,1262 @@
+use std::borrow::Borrow;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Add;
+use rand::{Rng, SeedableRng, random};
+use rand_distr::StandardNormal;
+use rand_pcg::Pcg64Mcg;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use types::{Function, Function1, Function2, HessianProduct, VectorFunction, VectorFunction1,
+    Summation, Summation1};
+use utils::solve_linear_system;
+
+
+/// Scales and offsets a function `f`, i.e., `g(x) = a · f(x) + b`.
+///
+/// For a positive `a` the position of the minimum remains unchanged, which is useful, e.g.,
+/// to test the invariances of a `Minimizer`.
+#[derive(Debug, Copy, Clone)]
+pub struct Affine<F> {
+    function: F,
+    scale: f64,
+    offset: f64
+}
+
+impl<F: Function> Affine<F> {
+    /// Creates a new `Affine` function given the wrapped `function`, its `scale` `a`
+    /// and its `offset` `b`.
+    pub fn new(function: F, scale: f64, offset: f64) -> Self {
+        assert!(scale.is_finite(), "scale must be finite");
+        assert!(offset.is_finite(), "offset must be finite");
+
+        Affine {
+            function,
+            scale,
+            offset
+        }
+    }
+}
+
+impl<F: Function> Function for Affine<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.scale * self.function.value(position) + self.offset
+    }
+
+    // a negative scale turns a convex function into a concave one
+    fn is_convex(&self) -> Option<bool> {
+        if self.scale >= 0.0 {
+            self.function.is_convex()
+        } else {
+            None
+        }
+    }
+}
+
+impl<F: Function1> Function1 for Affine<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.function.gradient(position).into_iter().map(|g| self.scale * g).collect()
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        (self.scale * value + self.offset, gradient.into_iter().map(|g| self.scale * g).collect())
+    }
+}
+
+impl_add!{[F,] Affine<F>}
+
+
+/// Sum of two functions `f` and `g`, i.e., `h(x) = f(x) + g(x)`.
+///
+/// Usually created by simply adding two functions, e.g., `Sphere::default() + Sphere::default()`,
+/// which is supported by the problems, `Func`, `Func1` and all adapters of this crate on the
+/// left-hand side, and by any `Function` on the right-hand side. Otherwise, use `Sum::new()`.
+#[derive(Debug, Copy, Clone)]
+pub struct Sum<A, B> {
+    first: A,
+    second: B
+}
+
+impl<A, B> Sum<A, B> {
+    /// Creates the sum of the `first` and the `second` function.
+    pub fn new(first: A, second: B) -> Self {
+        Sum {
+            first,
+            second
+        }
+    }
+}
+
+impl<A: Function, B: Function> Function for Sum<A, B> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.first.value(position) + self.second.value(position)
+    }
+
+    // the sum of a convex and a non-convex function might still be convex
+    fn is_convex(&self) -> Option<bool> {
+        match (self.first.is_convex(), self.second.is_convex()) {
+            (Some(true), Some(true)) => Some(true),
+            _ => None
+        }
+    }
+}
+
+impl<A: Function1, B: Function1> Function1 for Sum<A, B> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.first.gradient(position).into_iter().zip(self.second.gradient(position))
+            .map(|(a, b)| a + b)
+            .collect()
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (first_value, first_gradient) = self.first.value_and_gradient(position);
+        let (second_value, second_gradient) = self.second.value_and_gradient(position);
+
+        (first_value + second_value,
+         first_gradient.into_iter().zip(second_gradient).map(|(a, b)| a + b).collect())
+    }
+}
+
+impl_add!{[A, B,] Sum<A, B>}
+
+
+/// Concatenation of two summations `f` and `g` over the same parameters, i.e., the terms of
+/// `f` followed by the terms of `g`, e.g., to grow a training set incrementally.
+///
+/// The `i`-th term refers to the `i`-th term of the `first` summation for `i < n`, where
+/// `n` is its number of terms, and to the `(i - n)`-th term of the `second` one otherwise.
+#[derive(Debug, Copy, Clone)]
+pub struct Concat<A, B> {
+    first: A,
+    second: B
+}
+
+impl<A, B> Concat<A, B> {
+    /// Creates the concatenation of the `first` and the `second` summation.
+    pub fn new(first: A, second: B) -> Self {
+        Concat {
+            first,
+            second
+        }
+    }
+}
+
+impl<A: Summation, B: Summation> Summation for Concat<A, B> {
+    fn terms(&self) -> usize {
+        self.first.terms() + self.second.terms()
+    }
+
+    fn term_value(&self, position: &[f64], term: usize) -> f64 {
+        let offset = self.first.terms();
+
+        if term < offset {
+            self.first.term_value(position, term)
+        } else {
+            self.second.term_value(position, term - offset)
+        }
+    }
+
+    // the sum of a convex and a non-convex summation might still be convex
+    fn is_convex_sum(&self) -> Option<bool> {
+        match (self.first.is_convex_sum(), self.second.is_convex_sum()) {
+            (Some(true), Some(true)) => Some(true),
+            _ => None
+        }
+    }
+}
+
+impl<A: Summation1, B: Summation1> Summation1 for Concat<A, B> {
+    fn term_gradient(&self, position: &[f64], term: usize) -> Vec<f64> {
+        let offset = self.first.terms();
+
+        if term < offset {
+            self.first.term_gradient(position, term)
+        } else {
+            self.second.term_gradient(position, term - offset)
+        }
+    }
+
+    fn term_gradient_sparse(&self, position: &[f64], term: usize) -> Vec<(usize, f64)> {
+        let offset = self.first.terms();
+
+        if term < offset {
+            self.first.term_gradient_sparse(position, term)
+        } else {
+            self.second.term_gradient_sparse(position, term - offset)
+        }
+    }
+
+    fn has_sparse_gradients(&self) -> bool {
+        self.first.has_sparse_gradients() || self.second.has_sparse_gradients()
+    }
+
+    // forwards the terms of each summation at once, e.g., to keep its own partial gradient
+    fn partial_gradient<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, position: &[f64],
+                                                                terms: T) -> Vec<f64>
+    {
+        let offset = self.first.terms();
+
+        let (first, second): (Vec<_>, Vec<_>) = terms.into_iter()
+            .map(|term| *term.borrow())
+            .partition(|&term| term < offset);
+
+        let second: Vec<_> = second.into_iter().map(|term| term - offset).collect();
+
+        match (first.is_empty(), second.is_empty()) {
+            (_, true) => self.first.partial_gradient(position, first),
+            (true, false) => self.second.partial_gradient(position, second),
+            (false, false) => self.first.partial_gradient(position, first).into_iter()
+                .zip(self.second.partial_gradient(position, second))
+                .map(|(a, b)| a + b)
+                .collect()
+        }
+    }
+}
+
+impl_add!{[A, B,] Concat<A, B>}
+
+/// Evaluates the term gradients of a mini batch of a summation concurrently, which pays off
+/// for expensive terms, requires the `rayon` feature.
+///
+/// Whenever a partial gradient is requested, e.g., by `Adam`, `Adadelta`, `Svrg` or
+/// `StochasticGradientDescent` for each mini batch, the gradients of its terms are computed on
+/// the worker threads of rayon, while the calling thread accumulates them in the order of the
+/// batch. Since the sum is formed in exactly the same order as the sequential default, the
+/// results are bitwise identical to the ones of the wrapped summation, i.e., deterministic for
+/// a fixed seed.
+///
+/// Note that only the terms of a single mini batch are evaluated concurrently, since the next
+/// mini batch depends on the position after the current step. Sparse term gradients, see
+/// `Summation1::has_sparse_gradients()`, are evaluated one by one.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Copy, Clone)]
+pub struct ParallelTerms<S> {
+    summation: S
+}
+
+#[cfg(feature = "rayon")]
+impl<S: Summation1 + Sync> ParallelTerms<S> {
+    /// Creates a new `ParallelTerms` summation wrapping the given one.
+    pub fn new(summation: S) -> Self {
+        ParallelTerms {
+            summation
+        }
+    }
+
+    /// Returns the wrapped summation.
+    pub fn inner(&self) -> &S {
+        &self.summation
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<S: Summation> Summation for ParallelTerms<S> {
+    fn terms(&self) -> usize {
+        self.summation.terms()
+    }
+
+    fn term_value(&self, position: &[f64], term: usize) -> f64 {
+        self.summation.term_value(position, term)
+    }
+
+    fn is_convex_sum(&self) -> Option<bool> {
+        self.summation.is_convex_sum()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<S: Summation1 + Sync> Summation1 for ParallelTerms<S> {
+    fn term_gradient(&self, position: &[f64], term: usize) -> Vec<f64> {
+        self.summation.term_gradient(position, term)
+    }
+
+    fn term_gradient_sparse(&self, position: &[f64], term: usize) -> Vec<(usize, f64)> {
+        self.summation.term_gradient_sparse(position, term)
+    }
+
+    fn has_sparse_gradients(&self) -> bool {
+        self.summation.has_sparse_gradients()
+    }
+
+    fn partial_gradient<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, position: &[f64],
+                                                                terms: T) -> Vec<f64>
+    {
+        let terms: Vec<_> = terms.into_iter().map(|term| *term.borrow()).collect();
+
+        // the parallel iterator retains the order of the terms
+        let gradients: Vec<_> = terms.par_iter()
+            .map(|&term| self.summation.term_gradient(position, term))
+            .collect();
+
+        let mut gradient = vec![0.0; position.len()];
+
+        for term_gradient in gradients {
+            for (g, gi) in gradient.iter_mut().zip(term_gradient) {
+                *g += gi;
+            }
+        }
+
+        gradient
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl_add!{[S,] ParallelTerms<S>}
+
+/// Composition of an outer function `f: Rᵐ → R` and an inner vector-valued function
+/// `g: Rⁿ → Rᵐ`, i.e., `h(x) = f(g(x))`.
+///
+/// The gradient is computed using the chain rule, i.e., `∇h(x) = J(x)ᵀ ∇f(g(x))`, where
+/// `J` is the Jacobian of the inner function.
+#[derive(Debug, Copy, Clone)]
+pub struct Compose<F, G> {
+    outer: F,
+    inner: G
+}
+
+impl<F: Function, G: VectorFunction> Compose<F, G> {
+    /// Creates the composition of the `outer` and the `inner` function.
+    pub fn new(outer: F, inner: G) -> Self {
+        Compose {
+            outer,
+            inner
+        }
+    }
+}
+
+impl<F: Function, G: VectorFunction> Function for Compose<F, G> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.outer.value(&self.inner.value(position))
+    }
+}
+
+impl<F: Function1, G: VectorFunction1> Function1 for Compose<F, G> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        let n = position.len();
+
+        let outer_gradient = self.outer.gradient(&self.inner.value(position));
+        let jacobian = self.inner.jacobian(position);
+
+        assert_eq!(jacobian.len(), outer_gradient.len() * n);
+
+        let mut gradient = vec![0.0; n];
+
+        for (row, g) in jacobian.chunks(n).zip(outer_gradient) {
+            for (x, j) in gradient.iter_mut().zip(row) {
+                *x += g * j;
+            }
+        }
+
+        gradient
+    }
+}
+
+impl_add!{[F, G,] Compose<F, G>}
+
+
+/// Standardizes the input of a function `f` per coordinate, i.e., `g(x) = f((x - μ) / σ)`
+/// given the `means` `μ` and the standard deviations `stds` `σ`.
+///
+/// The gradient is scaled accordingly by the chain rule, i.e., `∇g(x) = ∇f((x - μ) / σ) / σ`.
+/// Since the curvature along each coordinate is divided by `σ²`, choosing `σᵢ ∝ √Hᵢᵢ` for
+/// the diagonal of the Hessian `H` of `f` results in a much better conditioned function `g`,
+/// which is minimized faster. A minimum `x` of `g` corresponds to the minimum
+/// `standardize(x)` of `f`.
+#[derive(Debug, Clone)]
+pub struct Standardized<F> {
+    function: F,
+    means: Vec<f64>,
+    stds: Vec<f64>
+}
+
+impl<F: Function> Standardized<F> {
+    /// Creates a new `Standardized` function given the wrapped `function` as well as the
+    /// `means` and the positive `stds` of each coordinate.
+    pub fn new(function: F, means: Vec<f64>, stds: Vec<f64>) -> Self {
+        assert_eq!(means.len(), stds.len(), "means and stds must have the same length");
+        assert!(means.iter().all(|mean| mean.is_finite()), "means must be finite");
+        assert!(stds.iter().all(|&std| std > 0.0 && std.is_finite()),
+            "stds must be greater than 0 and finite");
+
+        Standardized {
+            function,
+            means,
+            stds
+        }
+    }
+
+    /// Standardizes the `position`, i.e., maps it onto the input `(x - μ) / σ` of the
+    /// wrapped function.
+    pub fn standardize(&self, position: &[f64]) -> Vec<f64> {
+        assert_eq!(position.len(), self.means.len());
+
+        position.iter().zip(&self.means).zip(&self.stds)
+            .map(|((x, mean), std)| (x - mean) / std)
+            .collect()
+    }
+
+    /// Inverts `standardize()`, i.e., maps an input `z` of the wrapped function onto the
+    /// position `μ + σ z`, e.g., to start a minimization at a given position of `f`.
+    pub fn destandardize(&self, input: &[f64]) -> Vec<f64> {
+        assert_eq!(input.len(), self.means.len());
+
+        input.iter().zip(&self.means).zip(&self.stds)
+            .map(|((z, mean), std)| mean + std * z)
+            .collect()
+    }
+}
+
+impl<F: Function> Function for Standardized<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(&self.standardize(position))
+    }
+
+    fn is_convex(&self) -> Option<bool> {
+        self.function.is_convex()
+    }
+}
+
+impl<F: Function1> Function1 for Standardized<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        let gradient = self.function.gradient(&self.standardize(position));
+
+        gradient.into_iter().zip(&self.stds).map(|(g, std)| g / std).collect()
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(&self.standardize(position));
+
+        (value, gradient.into_iter().zip(&self.stds).map(|(g, std)| g / std).collect())
+    }
+}
+
+impl_add!{[F,] Standardized<F>}
+
+
+/// Restricts a function `f` to the affine subspace satisfying the linear equality
+/// constraints `A x = b`.
+///
+/// The gradient is projected onto the null space of `A`, i.e., `P ∇f(x)` with the
+/// projection `P = I - Aᵀ (A Aᵀ)⁻¹ A`, which is computed once upfront. Consequently, a
+/// gradient based minimizer like `GradientDescent` started at a feasible position, e.g.,
+/// obtained using `project()`, satisfies the constraints at every iterate.
+#[derive(Debug, Clone)]
+pub struct NullSpaceProjected<F> {
+    function: F,
+    constraints: Vec<f64>,
+    rhs: Vec<f64>,
+    correction: Vec<f64>,
+    projection: Vec<f64>
+}
+
+impl<F: Function1> NullSpaceProjected<F> {
+    /// Creates a new `NullSpaceProjected` function given the wrapped `function`, the
+    /// `m × n` matrix `A` of the `constraints` stored row by row and the right-hand side `b`
+    /// of length `m`. The rows of `A` have to be linearly independent.
+    pub fn new(function: F, constraints: Vec<f64>, rhs: Vec<f64>) -> Self {
+        let m = rhs.len();
+
+        assert!(m > 0, "at least one constraint is required");
+        assert!(!constraints.is_empty() && constraints.len() % m == 0,
+            "constraints must be a matrix with one row per right-hand side");
+
+        let n = constraints.len() / m;
+
+        // A Aᵀ, which is inverted column by column
+        let gram: Vec<_> = (0..m * m).map(|k| {
+            let (i, j) = (k / m, k % m);
+            constraints[i * n..(i + 1) * n].iter().zip(&constraints[j * n..(j + 1) * n])
+                .map(|(a, b)| a * b)
+                .fold(0.0, Add::add)
+        }).collect();
+
+        let mut inverse = vec![0.0; m * m];
+
+        for j in 0..m {
+            let unit: Vec<_> = (0..m).map(|i| if i == j { 1.0 } else { 0.0 }).collect();
+            let column = solve_linear_system(&gram, &unit)
+                .expect("constraints must be linearly independent");
+
+            for (i, c) in column.into_iter().enumerate() {
+                inverse[i * m + j] = c;
+            }
+        }
+
+        // Aᵀ (A Aᵀ)⁻¹, an n × m matrix
+        let correction: Vec<_> = (0..n * m).map(|k| {
+            let (i, j) = (k / m, k % m);
+            (0..m).map(|l| constraints[l * n + i] * inverse[l * m + j]).fold(0.0, Add::add)
+        }).collect();
+
+        // I - Aᵀ (A Aᵀ)⁻¹ A, an n × n matrix
+        let projection: Vec<_> = (0..n * n).map(|k| {
+            let (i, j) = (k / n, k % n);
+            let identity = if i == j { 1.0 } else { 0.0 };
+            identity - (0..m).map(|l| correction[i * m + l] * constraints[l * n + j])
+                .fold(0.0, Add::add)
+        }).collect();
+
+        NullSpaceProjected {
+            function,
+            constraints,
+            rhs,
+            correction,
+            projection
+        }
+    }
+
+    /// Returns the feasible position closest to the supplied `position`, e.g., to obtain a
+    /// feasible initial position.
+    pub fn project(&self, position: &[f64]) -> Vec<f64> {
+        let residual = self.residual(position);
+
+        position.iter().zip(self.correction.chunks(self.rhs.len()))
+            .map(|(x, row)| x - row.iter().zip(&residual).map(|(c, r)| c * r).fold(0.0, Add::add))
+            .collect()
+    }
+
+    /// Tests whether the supplied `position` satisfies all constraints up to the absolute
+    /// `tolerance`.
+    pub fn is_feasible(&self, position: &[f64], tolerance: f64) -> bool {
+        self.residual(position).iter().all(|r| r.abs() <= tolerance)
+    }
+
+    // computes A x - b
+    fn residual(&self, position: &[f64]) -> Vec<f64> {
+        self.constraints.chunks(position.len()).zip(&self.rhs)
+            .map(|(row, b)| row.iter().zip(position).map(|(a, x)| a * x).fold(0.0, Add::add) - b)
+            .collect()
+    }
+
+    fn project_gradient(&self, gradient: Vec<f64>) -> Vec<f64> {
+        self.projection.chunks(gradient.len())
+            .map(|row| row.iter().zip(&gradient).map(|(p, g)| p * g).fold(0.0, Add::add))
+            .collect()
+    }
+}
+
+impl<F: Function> Function for NullSpaceProjected<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(position)
+    }
+}
+
+impl<F: Function1> Function1 for NullSpaceProjected<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.project_gradient(self.function.gradient(position))
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        (value, self.project_gradient(gradient))
+    }
+}
+
+impl_add!{[F,] NullSpaceProjected<F>}
+
+
+/// Counts the evaluations of a function `f`, e.g., to compare the efficiency of minimizers.
+///
+/// Fused evaluations using `value_and_gradient()` are counted on their own.
+#[derive(Debug, Clone)]
+pub struct Counting<F> {
+    function: F,
+    values: Cell<u64>,
+    gradients: Cell<u64>,
+    values_and_gradients: Cell<u64>
+}
+
+impl<F> Counting<F> {
+    /// Creates a new `Counting` function wrapping the supplied `function`.
+    pub fn new(function: F) -> Self {
+        Counting {
+            function,
+            values: Cell::new(0),
+            gradients: Cell::new(0),
+            values_and_gradients: Cell::new(0)
+        }
+    }
+
+    /// Returns the number of evaluations of `value()`.
+    pub fn values(&self) -> u64 {
+        self.values.get()
+    }
+
+    /// Returns the number of evaluations of `gradient()`.
+    pub fn gradients(&self) -> u64 {
+        self.gradients.get()
+    }
+
+    /// Returns the number of evaluations of `value_and_gradient()`.
+    pub fn values_and_gradients(&self) -> u64 {
+        self.values_and_gradients.get()
+    }
+}
+
+impl<F: Function> Function for Counting<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.values.set(self.values.get() + 1);
+
+        self.function.value(position)
+    }
+
+    fn is_convex(&self) -> Option<bool> {
+        self.function.is_convex()
+    }
+}
+
+impl<F: Function1> Function1 for Counting<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.gradients.set(self.gradients.get() + 1);
+
+        self.function.gradient(position)
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        self.values_and_gradients.set(self.values_and_gradients.get() + 1);
+
+        self.function.value_and_gradient(position)
+    }
+}
+
+impl_add!{[F,] Counting<F>}
+
+
+/// Caches the most recent values of a function `f`, e.g., to avoid the re-evaluation of the
+/// initial position by a line search.
+///
+/// The cache is a small LRU of `(x, f(x))` pairs keyed by the hash of the bit patterns of
+/// `x`, i.e., only bit-exact positions hit the cache, such that the returned values are
+/// identical to a re-evaluation of a deterministic function. Positions containing NaN and
+/// NaN values are never cached. Gradients are not cached, but `value_and_gradient()` takes
+/// a cached value into account.
+#[derive(Debug)]
+pub struct Memoized<F> {
+    function: F,
+    capacity: usize,
+    cache: RefCell<VecDeque<(u64, Vec<u64>, f64)>>
+}
+
+impl<F> Memoized<F> {
+    /// Creates a new `Memoized` function wrapping the supplied `function`, caching the
+    /// values of at most `capacity` recent positions.
+    pub fn new(function: F, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than 0");
+
+        Memoized {
+            function,
+            capacity,
+            cache: RefCell::new(VecDeque::with_capacity(capacity))
+        }
+    }
+
+    /// Returns the wrapped function.
+    pub fn inner(&self) -> &F {
+        &self.function
+    }
+
+    // returns the cached value of the position, which becomes the most recent entry
+    fn lookup(&self, key: &(u64, Vec<u64>)) -> Option<f64> {
+        let mut cache = self.cache.borrow_mut();
+
+        let index = cache.iter().position(|entry| entry.0 == key.0 && entry.1 == key.1)?;
+        let entry = cache.remove(index).unwrap();
+        let value = entry.2;
+
+        cache.push_back(entry);
+
+        Some(value)
+    }
+
+    fn store(&self, key: (u64, Vec<u64>), value: f64) {
+        if value.is_nan() {
+            return;
+        }
+
+        let mut cache = self.cache.borrow_mut();
+
+        if cache.len() == self.capacity {
+            cache.pop_front();
+        }
+
+        cache.push_back((key.0, key.1, value));
+    }
+}
+
+// the bit patterns of the position along with their hash, unless it contains NaN
+fn memo_key(position: &[f64]) -> Option<(u64, Vec<u64>)> {
+    if position.iter().any(|x| x.is_nan()) {
+        return None;
+    }
+
+    let bits: Vec<_> = position.iter().map(|x| x.to_bits()).collect();
+
+    let mut hasher = DefaultHasher::new();
+    bits.hash(&mut hasher);
+
+    Some((hasher.finish(), bits))
+}
+
+impl<F: Function> Function for Memoized<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        let key = match memo_key(position) {
+            Some(key) => key,
+            None => return self.function.value(position)
+        };
+
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+
+        let value = self.function.value(position);
+
+        self.store(key, value);
+
+        value
+    }
+}
+
+impl<F: Function1> Function1 for Memoized<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.function.gradient(position)
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let key = match memo_key(position) {
+            Some(key) => key,
+            None => return self.function.value_and_gradient(position)
+        };
+
+        if let Some(value) = self.lookup(&key) {
+            return (value, self.function.gradient(position));
+        }
+
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        self.store(key, value);
+
+        (value, gradient)
+    }
+}
+
+impl_add!{[F,] Memoized<F>}
+
+/// Tracks the position with the lowest value `f(x)` that has been evaluated so far, e.g., to
+/// recover the progress of an aborted minimization.
+#[derive(Debug)]
+pub struct Tracking<'a, F: 'a + ?Sized> {
+    function: &'a F,
+    best: RefCell<Option<(Vec<f64>, f64)>>
+}
+
+impl<'a, F: ?Sized> Tracking<'a, F> {
+    /// Creates a new `Tracking` function wrapping the supplied `function`.
+    pub fn new(function: &'a F) -> Self {
+        Tracking {
+            function,
+            best: RefCell::new(None)
+        }
+    }
+
+    /// Returns the position with the lowest value as well as the value, if any position has
+    /// been evaluated yet.
+    pub fn best(&self) -> Option<(Vec<f64>, f64)> {
+        self.best.borrow().clone()
+    }
+
+    fn track(&self, position: &[f64], value: f64) {
+        let mut best = self.best.borrow_mut();
+
+        if best.as_ref().is_none_or(|&(_, best_value)| value < best_value) {
+            *best = Some((position.to_vec(), value));
+        }
+    }
+}
+
+impl<'a, F: Function + ?Sized> Function for Tracking<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        let value = self.function.value(position);
+
+        self.track(position, value);
+
+        value
+    }
+}
+
+impl<'a, F: Function1 + ?Sized> Function1 for Tracking<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.function.gradient(position)
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        self.track(position, value);
+
+        (value, gradient)
+    }
+}
+
+impl_add!{['a, F: 'a + ?Sized,] Tracking<'a, F>}
+
+
+/// Negates a function `f`, i.e., `g(x) = -f(x)`, such that minimizing `g` maximizes `f`.
+///
+/// The value as well as the available derivatives, i.e., the gradient and the Hessian, are
+/// negated, e.g., to maximize a function, see `Minimizer::maximize()`, or to alternate between
+/// minimizing and maximizing an objective.
+#[derive(Debug)]
+pub struct Negated<'a, F: 'a + ?Sized> {
+    function: &'a F
+}
+
+impl<'a, F: ?Sized> Negated<'a, F> {
+    /// Creates a new `Negated` function wrapping the supplied `function`.
+    pub fn new(function: &'a F) -> Self {
+        Negated {
+            function
+        }
+    }
+}
+
+impl<'a, F: Function + ?Sized> Function for Negated<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        -self.function.value(position)
+    }
+}
+
+impl<'a, F: Function1 + ?Sized> Function1 for Negated<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.function.gradient(position).into_iter().map(|g| -g).collect()
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        (-value, gradient.into_iter().map(|g| -g).collect())
+    }
+}
+
+impl<'a, F: Function2 + ?Sized> Function2 for Negated<'a, F> {
+    fn hessian(&self, position: &[f64]) -> Vec<f64> {
+        self.function.hessian(position).into_iter().map(|h| -h).collect()
+    }
+}
+
+impl<'a, F: HessianProduct + ?Sized> HessianProduct for Negated<'a, F> {
+    fn hessian_product(&self, position: &[f64], direction: &[f64]) -> Vec<f64> {
+        self.function.hessian_product(position, direction).into_iter().map(|h| -h).collect()
+    }
+}
+
+impl_add!{['a, F: 'a + ?Sized,] Negated<'a, F>}
+
+
+/// Adds zero-mean Gaussian noise to each evaluation of a function `f`, e.g., to test the
+/// robustness of a `Minimizer` against noisy objectives.
+///
+/// By default, only the value is perturbed while the gradient remains exact, see
+/// `noisy_gradient()`.
+#[derive(Debug)]
+pub struct Noisy<F> {
+    function: F,
+    stddev: f64,
+    noisy_gradient: bool,
+    rng: RefCell<Pcg64Mcg>
+}
+
+impl<F: Function> Noisy<F> {
+    /// Creates a new `Noisy` function given the wrapped `function` and the standard
+    /// deviation `stddev` of the noise. The used random number generator is randomly seeded.
+    pub fn new(function: F, stddev: f64) -> Self {
+        assert!(stddev >= 0.0 && stddev.is_finite(), "stddev must be finite and non-negative");
+
+        Noisy {
+            function,
+            stddev,
+            noisy_gradient: false,
+            rng: RefCell::new(Pcg64Mcg::new(random()))
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(self, seed: u64) -> Self {
+        *self.rng.borrow_mut() = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Specifies whether each gradient component is perturbed by independent noise of the
+    /// same standard deviation as well.
+    pub fn noisy_gradient(mut self, noisy_gradient: bool) -> Self {
+        self.noisy_gradient = noisy_gradient;
+        self
+    }
+
+    fn noise(&self) -> f64 {
+        self.stddev * self.rng.borrow_mut().sample::<f64, _>(StandardNormal)
+    }
+
+    fn perturb(&self, gradient: Vec<f64>) -> Vec<f64> {
+        if self.noisy_gradient {
+            gradient.into_iter().map(|g| g + self.noise()).collect()
+        } else {
+            gradient
+        }
+    }
+}
+
+impl<F: Function> Function for Noisy<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(position) + self.noise()
+    }
+}
+
+impl<F: Function1> Function1 for Noisy<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.perturb(self.function.gradient(position))
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        (value + self.noise(), self.perturb(gradient))
+    }
+}
+
+impl_add!{[F,] Noisy<F>}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::{Sphere, LinearRegression};
+    use types::{Function, Function1, Func, Func1, HessianProduct, Minimizer, VectorFunction,
+        VectorFunction1, Summation, Summation1};
+    use gd::GradientDescent;
+
+    use super::{Affine, Concat, Compose, Standardized, NullSpaceProjected, Counting, Memoized,
+        Negated, Noisy};
+    #[cfg(feature = "rayon")]
+    use adam::Adam;
+    #[cfg(feature = "rayon")]
+    use svrg::Svrg;
+    #[cfg(feature = "rayon")]
+    use sgd::StochasticGradientDescent;
+    #[cfg(feature = "rayon")]
+    use super::ParallelTerms;
+
+    #[test]
+    fn test_affine_invariance() {
+        let minimizer = GradientDescent::new();
+
+        let plain = minimizer.minimize(&Sphere::default(), vec![1.0, -2.0]);
+        let affine = minimizer.minimize(&Affine::new(Sphere::default(), 3.0, 5.0),
+            vec![1.0, -2.0]);
+
+        for (a, b) in plain.position.iter().zip(&affine.position) {
+            assert!((a - b).abs() < 1.0e-3);
+        }
+
+        assert!((affine.value - 5.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_sum() {
+        let sum = Sphere::default() + Sphere::default();
+
+        assert_eq!(sum.gradient(&[1.0, -2.5]), vec![4.0, -10.0]);
+
+        let solution = GradientDescent::new().minimize(&sum, vec![3.0, -4.0]);
+
+        assert!(solution.position.iter().all(|x| x.abs() < 1.0e-4));
+    }
+
+    #[test]
+    fn test_add_adapters() {
+        let sum = Counting::new(Sphere::default()) + Func(|x: &[f64]| x[0]);
+
+        assert_eq!(sum.value(&[1.0, -2.0]), 6.0);
+
+        let sum = Memoized::new(Affine::new(Sphere::default(), 2.0, 1.0), 1)
+            + Func1(|x: &[f64]| x[0], |_: &[f64]| vec![1.0, 0.0]);
+
+        assert_eq!(sum.value_and_gradient(&[1.0, -2.0]), (12.0, vec![5.0, -8.0]));
+
+        let negated = Negated::new(&sum) + Sphere::default();
+
+        assert_eq!(negated.value(&[1.0, -2.0]), -12.0 + 5.0);
+    }
+
+    // linear map g(x) = A x with a fixed 2 × 2 matrix A
+    struct Linear([f64; 4]);
+
+    impl VectorFunction for Linear {
+        fn value(&self, x: &[f64]) -> Vec<f64> {
+            vec![self.0[0] * x[0] + self.0[1] * x[1], self.0[2] * x[0] + self.0[3] * x[1]]
+        }
+    }
+
+    impl VectorFunction1 for Linear {
+        fn jacobian(&self, _x: &[f64]) -> Vec<f64> {
+            self.0.to_vec()
+        }
+    }
+
+    // sum squared error of a line `y = w₀ + w₁ x` fitted to the observations `(x, y)`
+    struct LineFitting(Vec<(f64, f64)>);
+
+    impl Summation for LineFitting {
+        fn terms(&self) -> usize {
+            self.0.len()
+        }
+
+        fn term_value(&self, w: &[f64], term: usize) -> f64 {
+            let (x, y) = self.0[term];
+
+            0.5 * (y - w[0] - w[1] * x).powi(2)
+        }
+
+        fn is_convex_sum(&self) -> Option<bool> {
+            Some(true)
+        }
+    }
+
+    impl Summation1 for LineFitting {
+        fn term_gradient(&self, w: &[f64], term: usize) -> Vec<f64> {
+            let (x, y) = self.0[term];
+            let e = y - w[0] - w[1] * x;
+
+            vec![-e, -e * x]
+        }
+    }
+
+    #[test]
+    fn test_concat() {
+        let observations: Vec<_> = (0..10)
+            .map(|i| (i as f64, 2.0 * i as f64 - 1.0 + (i % 3) as f64))
+            .collect();
+
+        let combined = LineFitting(observations.clone());
+        let concat = Concat::new(LineFitting(observations[..4].to_vec()),
+            LineFitting(observations[4..].to_vec()));
+
+        assert_eq!(concat.terms(), combined.terms());
+
+        for w in &[[0.0, 0.0], [1.5, -2.0], [-1.0, 2.0]] {
+            assert_eq!(concat.value(w), combined.value(w));
+            assert_eq!(concat.gradient(w), combined.gradient(w));
+
+            for term in 0..combined.terms() {
+                assert_eq!(concat.term_gradient(w, term), combined.term_gradient(w, term));
+            }
+
+            // each summation computes the partial gradient over its own terms
+            let partial = concat.partial_gradient(w, [1, 7, 3, 8]);
+            let expected = combined.partial_gradient(w, [1, 3, 7, 8]);
+
+            for (p, e) in partial.iter().zip(&expected) {
+                assert!((p - e).abs() < 1.0e-12, "{:?} vs. {:?}", partial, expected);
+            }
+
+            assert_eq!(concat.partial_gradient(w, [5, 9]), combined.partial_gradient(w, [5, 9]));
+        }
+
+        assert_eq!(concat.is_convex(), Some(true));
+        // the convexity of the linear regression is unknown
+        let regression = LinearRegression::new(&[1.0, 2.0], 10, 42);
+
+        assert_eq!(Concat::new(LineFitting(observations), regression).is_convex(), None);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_terms() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+        let parallel = ParallelTerms::new(LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42));
+
+        let mut adam = Adam::new();
+        adam.seed(42).max_iterations(Some(20)).mini_batch(8);
+
+        let plain = adam.minimize(&problem, vec![1.0; 3]);
+        let concurrent = adam.minimize(&parallel, vec![1.0; 3]);
+
+        assert_eq!(plain.position, concurrent.position);
+        assert_eq!(plain.value, concurrent.value);
+
+        let mut svrg = Svrg::new();
+        svrg.seed(42).max_iterations(Some(10)).mini_batch(8);
+
+        let plain = svrg.minimize(&problem, vec![1.0; 3]);
+        let concurrent = svrg.minimize(&parallel, vec![1.0; 3]);
+
+        assert_eq!(plain.position, concurrent.position);
+        assert_eq!(plain.value, concurrent.value);
+
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.seed(42).max_iterations(Some(5)).mini_batch(8);
+
+        let plain = sgd.minimize(&problem, vec![1.0; 3]);
+        let concurrent = sgd.minimize(&parallel, vec![1.0; 3]);
+
+        assert_eq!(plain.position, concurrent.position);
+        assert_eq!(plain.value, concurrent.value);
+    }
+
+    #[test]
+    fn test_compose() {
+        let a = [1.0, 2.0, 3.0, -4.0];
+        let composition = Compose::new(Sphere::default(), Linear(a));
+
+        for x in &[[0.0, 0.0], [1.0, -2.0], [-0.5, 3.0]] {
+            // ∇ ||A x||² = 2 Aᵀ A x
+            let ax = Linear(a).value(x);
+            let expected = vec![2.0 * (a[0] * ax[0] + a[2] * ax[1]),
+                                2.0 * (a[1] * ax[0] + a[3] * ax[1])];
+
+            assert_eq!(composition.gradient(x), expected);
+        }
+    }
+
+    #[test]
+    fn test_standardized() {
+        // anisotropic quadratic f(z) = 100 z₀² + z₁² with its minimum at the origin
+        let quadratic = || Func1(|z: &[f64]| 100.0 * z[0].powi(2) + z[1].powi(2),
+                                 |z: &[f64]| vec![200.0 * z[0], 2.0 * z[1]]);
+
+        let standardized = Standardized::new(quadratic(), vec![1.0, -2.0], vec![10.0, 1.0]);
+
+        let start = [1.0, 1.0];
+
+        let raw = GradientDescent::new().minimize(&quadratic(), start.to_vec());
+        let report = GradientDescent::new().minimize(&standardized,
+            standardized.destandardize(&start));
+
+        assert!(report.iterations < raw.iterations, "{} vs. {}", report.iterations,
+            raw.iterations);
+
+        for z in standardized.standardize(&report.position) {
+            assert!(z.abs() < 1.0e-4, "{:?} is not close to the minimum", report.position);
+        }
+
+        // the gradient follows the chain rule
+        let x = [3.0, 0.5];
+        let z = standardized.standardize(&x);
+
+        assert_eq!(standardized.value(&x), quadratic().value(&z));
+        assert_eq!(standardized.gradient(&x), vec![200.0 * z[0] / 10.0, 2.0 * z[1]]);
+        assert_eq!(standardized.value_and_gradient(&x).1, standardized.gradient(&x));
+    }
+
+    #[test]
+    fn test_null_space_projected() {
+        // ∑ xᵢ = 5, i.e., the minimum of the sphere is located at xᵢ = 5 / 4
+        let problem = NullSpaceProjected::new(Sphere::new(4), vec![1.0; 4], vec![5.0]);
+
+        let start = problem.project(&[3.0, -1.0, 0.5, 2.0]);
+
+        assert!(problem.is_feasible(&start, 1.0e-12));
+
+        let report = GradientDescent::new()
+            .record_trajectory(true)
+            .minimize(&problem, start);
+
+        for (position, _) in report.trajectory.as_ref().unwrap() {
+            assert!(problem.is_feasible(position, 1.0e-9), "{:?} is infeasible", position);
+        }
+
+        for x in &report.position {
+            assert!((x - 1.25).abs() < 1.0e-3, "{:?} is not optimal", report.position);
+        }
+    }
+
+    #[test]
+    fn test_memoized() {
+        let memoized = Memoized::new(Counting::new(Sphere::default()), 2);
+
+        assert_eq!(memoized.value(&[1.0, 2.0]), 5.0);
+        assert_eq!(memoized.value(&[1.0, 2.0]), 5.0);
+        assert_eq!(memoized.value_and_gradient(&[1.0, 2.0]).0, 5.0);
+
+        assert_eq!(memoized.inner().values(), 1);
+        assert_eq!(memoized.inner().values_and_gradients(), 0);
+
+        // positions differing in a single bit are distinct, e.g., the signed zeros
+        memoized.value(&[0.0, 2.0]);
+        memoized.value(&[-0.0, 2.0]);
+
+        assert_eq!(memoized.inner().values(), 3);
+
+        // the least recently used position has been evicted
+        memoized.value(&[-0.0, 2.0]);
+        memoized.value(&[1.0, 2.0]);
+
+        assert_eq!(memoized.inner().values(), 4);
+    }
+
+    #[test]
+    fn test_memoized_nan() {
+        let counting = Counting::new(Func(|x: &[f64]| if x[0] < 0.0 { f64::NAN } else { x[0] }));
+        let memoized = Memoized::new(counting, 4);
+
+        assert!(memoized.value(&[f64::NAN]).is_nan());
+        assert!(memoized.value(&[f64::NAN]).is_nan());
+        assert!(memoized.value(&[-1.0]).is_nan());
+        assert!(memoized.value(&[-1.0]).is_nan());
+
+        assert_eq!(memoized.inner().values(), 4);
+    }
+
+    #[test]
+    fn test_negated() {
+        let sphere = Sphere::default();
+        let negated = Negated::new(&sphere);
+
+        let position = [1.5, -0.25];
+
+        assert_eq!(negated.value(&position), -sphere.value(&position));
+        assert_eq!(negated.gradient(&position), vec![-3.0, 0.5]);
+        assert_eq!(negated.value_and_gradient(&position), (-2.3125, vec![-3.0, 0.5]));
+        assert_eq!(negated.hessian_product(&position, &[1.0, 2.0]), vec![-2.0, -4.0]);
+    }
+
+    #[test]
+    fn test_noisy() {
+        let noisy = Noisy::new(Sphere::default(), 0.1).seed(42);
+
+        // the noise has zero mean and the requested standard deviation
+        let values: Vec<_> = (0..10000).map(|_| noisy.value(&[1.0, 1.0]) - 2.0).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let stddev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() /
+            values.len() as f64).sqrt();
+
+        assert!(mean.abs() < 1.0e-2);
+        assert!((stddev - 0.1).abs() < 1.0e-2);
+        assert_eq!(noisy.gradient(&[1.0, 1.0]), vec![2.0, 2.0]);
+
+        let noisy = noisy.noisy_gradient(true);
+
+        assert_ne!(noisy.gradient(&[1.0, 1.0]), vec![2.0, 2.0]);
+
+        // both are seeded identically
+        assert_eq!(Noisy::new(Sphere::default(), 0.1).seed(7).value(&[1.0, 1.0]),
+                   Noisy::new(Sphere::default(), 0.1).seed(7).value(&[1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_noisy_minimization() {
+        let noisy = Noisy::new(Sphere::default(), 1.0e-2).seed(42);
+
+        let solution = GradientDescent::new()
+            .max_iterations(Some(100))
+            .minimize(&noisy, vec![3.0, -4.0]);
+
+        assert!(solution.position.iter().all(|x| x.abs() < 0.1), "{:?}", solution.position);
+    }
+}
@@ -0,0 +1,246 @@
+use std::iter::Sum;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+use types::{Function, Function1};
+
+
+/// A dual number `a + b ε` with `ε² = 0`, which carries the value `a` of an expression along
+/// with its derivative `b` with respect to a single variable.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Dual {
+    /// The value of the expression.
+    pub value: f64,
+    /// The derivative of the expression.
+    pub derivative: f64
+}
+
+impl Dual {
+    /// Creates a new `Dual` number given its `value` and `derivative`.
+    pub fn new(value: f64, derivative: f64) -> Dual {
+        Dual {
+            value,
+            derivative
+        }
+    }
+
+    /// Creates a constant, i.e., a dual number with a vanishing derivative.
+    pub fn constant(value: f64) -> Dual {
+        Dual::new(value, 0.0)
+    }
+
+    /// Creates the variable to differentiate with respect to, i.e., a dual number with a
+    /// derivative of one.
+    pub fn variable(value: f64) -> Dual {
+        Dual::new(value, 1.0)
+    }
+
+    /// Raises the number to an integer power.
+    pub fn powi(self, n: i32) -> Dual {
+        Dual::new(self.value.powi(n), f64::from(n) * self.value.powi(n - 1) * self.derivative)
+    }
+
+    /// Raises the number to a floating point power.
+    pub fn powf(self, n: f64) -> Dual {
+        Dual::new(self.value.powf(n), n * self.value.powf(n - 1.0) * self.derivative)
+    }
+
+    /// Takes the square root of the number.
+    pub fn sqrt(self) -> Dual {
+        let sqrt = self.value.sqrt();
+
+        Dual::new(sqrt, self.derivative / (2.0 * sqrt))
+    }
+
+    /// Returns `e` raised to the power of the number.
+    pub fn exp(self) -> Dual {
+        let exp = self.value.exp();
+
+        Dual::new(exp, exp * self.derivative)
+    }
+
+    /// Returns the natural logarithm of the number.
+    pub fn ln(self) -> Dual {
+        Dual::new(self.value.ln(), self.derivative / self.value)
+    }
+
+    /// Computes the sine of the number.
+    pub fn sin(self) -> Dual {
+        Dual::new(self.value.sin(), self.value.cos() * self.derivative)
+    }
+
+    /// Computes the cosine of the number.
+    pub fn cos(self) -> Dual {
+        Dual::new(self.value.cos(), -self.value.sin() * self.derivative)
+    }
+
+    /// Computes the absolute value of the number, whose derivative at zero is zero.
+    pub fn abs(self) -> Dual {
+        if self.value > 0.0 {
+            self
+        } else if self.value < 0.0 {
+            -self
+        } else {
+            Dual::constant(0.0)
+        }
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, other: Dual) -> Dual {
+        Dual::new(self.value + other.value, self.derivative + other.derivative)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, other: Dual) -> Dual {
+        Dual::new(self.value - other.value, self.derivative - other.derivative)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, other: Dual) -> Dual {
+        Dual::new(self.value * other.value,
+            self.derivative * other.value + self.value * other.derivative)
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+
+    fn div(self, other: Dual) -> Dual {
+        Dual::new(self.value / other.value,
+            (self.derivative * other.value - self.value * other.derivative) / other.value.powi(2))
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Dual {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+macro_rules! impl_constant_op {
+    ( $($op:ident: $method:ident),* ) => {
+        $(
+            impl $op<f64> for Dual {
+                type Output = Dual;
+
+                fn $method(self, other: f64) -> Dual {
+                    self.$method(Dual::constant(other))
+                }
+            }
+
+            impl $op<Dual> for f64 {
+                type Output = Dual;
+
+                fn $method(self, other: Dual) -> Dual {
+                    Dual::constant(self).$method(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_constant_op!{Add: add, Sub: sub, Mul: mul, Div: div}
+
+impl Sum for Dual {
+    fn sum<I: Iterator<Item=Dual>>(iter: I) -> Dual {
+        iter.fold(Dual::constant(0.0), Add::add)
+    }
+}
+
+
+/// Wraps a function for which to provide exact derivatives by forward-mode automatic
+/// differentiation, i.e., the function is evaluated on dual numbers.
+///
+/// The gradient requires one evaluation of the function per dimension, but in contrast to
+/// `NumericalDifferentiation` it does not suffer from any truncation error.
+///
+/// # Examples
+///
+/// ```
+/// # use self::optimization::*;
+/// let square = AutoDiff::new(|x: &[Dual]| x[0] * x[0]);
+///
+/// assert_eq!(square.gradient(&[3.0]), vec![6.0]);
+/// ```
+pub struct AutoDiff<F: Fn(&[Dual]) -> Dual> {
+    function: F
+}
+
+impl<F: Fn(&[Dual]) -> Dual> AutoDiff<F> {
+    /// Creates a new differentiable function by evaluating the supplied `function` on dual
+    /// numbers to find the derivatives.
+    pub fn new(function: F) -> Self {
+        AutoDiff {
+            function
+        }
+    }
+}
+
+impl<F: Fn(&[Dual]) -> Dual> Function for AutoDiff<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        let x: Vec<_> = position.iter().cloned().map(Dual::constant).collect();
+
+        (self.function)(&x).value
+    }
+}
+
+impl<F: Fn(&[Dual]) -> Dual> Function1 for AutoDiff<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        let mut x: Vec<_> = position.iter().cloned().map(Dual::constant).collect();
+
+        (0..position.len()).map(|i| {
+            x[i] = Dual::variable(position[i]);
+
+            let d_i = (self.function)(&x).derivative;
+
+            x[i] = Dual::constant(position[i]);
+
+            d_i
+        }).collect()
+    }
+}
+
+impl_add!{[F: Fn(&[Dual]) -> Dual,] AutoDiff<F>}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    use problems::{Problem, Rosenbrock};
+    use types::{Function, Function1};
+    use utils::are_close;
+
+    use super::{AutoDiff, Dual};
+
+    #[test]
+    fn test_rosenbrock() {
+        let problem = Rosenbrock::default();
+        let autodiff = AutoDiff::new(|x: &[Dual]| {
+            (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0].powi(2)).powi(2)
+        });
+
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        for _ in 0..1000 {
+            let position = problem.random_start_seeded(&mut rng);
+
+            assert_eq!(autodiff.value(&position), problem.value(&position));
+
+            for (a, d) in problem.gradient(&position).into_iter().zip(autodiff.gradient(&position)) {
+                assert!(are_close(a, d, 1.0e-12), "{} differs from {}", d, a);
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Utilities to compare the performance of minimizers.
+
+use std::cell::Cell;
+use rand::{SeedableRng, random};
+use rand_pcg::Pcg64Mcg;
+
+use types::{Function, Function1, Minimizer};
+use combinators::EvalBudget;
+use problems::Problem;
+
+
+/// Outcome of a single minimization within an evaluation budget.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Run {
+    /// The lowest value `f(x)` that has been evaluated within the budget.
+    pub value: f64,
+    /// The number of evaluations required to reach the lowest value.
+    pub evaluations: u64
+}
+
+
+/// Result of racing two minimizers against each other, see `race()`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Comparison {
+    /// Outcome of the first minimizer.
+    pub first: Run,
+    /// Outcome of the second minimizer.
+    pub second: Run
+}
+
+impl Comparison {
+    /// Tests whether the first minimizer reached a lower value than the second one, or the
+    /// same value using fewer evaluations.
+    pub fn first_wins(&self) -> bool {
+        beats(&self.first, &self.second)
+    }
+
+    /// Tests whether the second minimizer reached a lower value than the first one, or the
+    /// same value using fewer evaluations.
+    pub fn second_wins(&self) -> bool {
+        beats(&self.second, &self.first)
+    }
+}
+
+fn beats(a: &Run, b: &Run) -> bool {
+    a.value < b.value || (a.value == b.value && a.evaluations < b.evaluations)
+}
+
+
+/// Wraps a function such that a minimization is aborted once the evaluation budget is
+/// exhausted, while keeping track of the best evaluation within the budget.
+///
+/// Each call of `value()`, `gradient()` or `value_and_gradient()` counts as one evaluation.
+pub struct Budgeted<F> {
+    function: F,
+    budget: EvalBudget,
+    best: Cell<Run>
+}
+
+impl<F> Budgeted<F> {
+    fn new(function: F, budget: u64) -> Self {
+        Budgeted {
+            function,
+            budget: EvalBudget::new(budget),
+            best: Cell::new(Run {
+                value: f64::INFINITY,
+                evaluations: 0
+            })
+        }
+    }
+
+    fn track(&self, value: f64) {
+        if value < self.best.get().value {
+            self.best.set(Run {
+                value,
+                evaluations: self.budget.evaluations()
+            });
+        }
+    }
+}
+
+impl<F: Function> Function for Budgeted<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.budget.spend();
+
+        let value = self.function.value(position);
+
+        self.track(value);
+
+        value
+    }
+}
+
+impl<F: Function1> Function1 for Budgeted<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.budget.spend();
+
+        self.function.gradient(position)
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        self.budget.spend();
+
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        self.track(value);
+
+        (value, gradient)
+    }
+}
+
+
+/// Races two minimizers against each other on the same `function`, starting from the same
+/// `initial_position` and using the same evaluation `budget`.
+///
+/// Each minimizer is stopped as soon as it exhausted the budget, or runs until its own
+/// stopping criteria are met. A panic of a minimizer or the function is propagated.
+pub fn race<F, A, B>(function: &F, first: &A, second: &B, initial_position: Vec<f64>,
+    budget: u64) -> Comparison
+    where F: Function + Clone, A: Minimizer<Budgeted<F>>, B: Minimizer<Budgeted<F>>
+{
+    assert!(budget > 0, "budget must be greater than 0");
+
+    Comparison {
+        first: run(function, first, initial_position.clone(), budget),
+        second: run(function, second, initial_position, budget)
+    }
+}
+
+fn run<F, M>(function: &F, minimizer: &M, initial_position: Vec<f64>, budget: u64) -> Run
+    where F: Function + Clone, M: Minimizer<Budgeted<F>>
+{
+    let budgeted = Budgeted::new(function.clone(), budget);
+
+    budgeted.budget.run(|| minimizer.minimize(&budgeted, initial_position));
+
+    budgeted.best.get()
+}
+
+
+/// Result of a hyperparameter search, see `HyperSearch`.
+#[derive(Debug, Clone)]
+pub struct Tuning<P> {
+    /// The hyperparameters with the lowest mean value.
+    pub best: P,
+    /// The mean of the lowest values reached from each start using the best hyperparameters.
+    pub mean_value: f64,
+    /// All evaluated hyperparameters along with their mean value, in order of evaluation.
+    pub candidates: Vec<(P, f64)>
+}
+
+
+/// Searches the hyperparameters of a minimizer that perform best on a problem, either on a
+/// grid or at random.
+///
+/// Each candidate is turned into a minimizer by a factory closure, which is run from the
+/// same random starts using the same evaluation budget per start, see `race()`. The
+/// candidates are ranked by the mean of the lowest values reached within the budget.
+#[derive(Clone)]
+pub struct HyperSearch {
+    rng: Pcg64Mcg,
+    starts: usize,
+    budget: u64
+}
+
+impl HyperSearch {
+    /// Creates a new `HyperSearch` running each candidate from `starts` random positions
+    /// using an evaluation `budget` per start.
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new(starts: usize, budget: u64) -> HyperSearch {
+        assert!(starts > 0, "starts must be greater than 0");
+        assert!(budget > 0, "budget must be greater than 0");
+
+        HyperSearch {
+            rng: Pcg64Mcg::new(random()),
+            starts,
+            budget
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Evaluates each of the given `candidates` and returns the best one.
+    pub fn grid<F, P, M, C>(&self, problem: &F, candidates: Vec<P>, factory: C) -> Tuning<P>
+        where F: Problem + Clone, P: Clone, M: Minimizer<Budgeted<F>>, C: Fn(&P) -> M
+    {
+        let mut rng = self.rng.clone();
+
+        let starts = self.starts(problem, &mut rng);
+
+        self.evaluate(problem, &starts, candidates, factory)
+    }
+
+    /// Evaluates `samples` candidates drawn by the `sample` closure using the seeded random
+    /// number generator and returns the best one.
+    pub fn random<F, P, M, S, C>(&self, problem: &F, samples: usize, mut sample: S, factory: C)
+        -> Tuning<P>
+        where F: Problem + Clone, P: Clone, M: Minimizer<Budgeted<F>>,
+              S: FnMut(&mut Pcg64Mcg) -> P, C: Fn(&P) -> M
+    {
+        let mut rng = self.rng.clone();
+
+        let starts = self.starts(problem, &mut rng);
+        let candidates = (0..samples).map(|_| sample(&mut rng)).collect();
+
+        self.evaluate(problem, &starts, candidates, factory)
+    }
+
+    fn starts<F: Problem>(&self, problem: &F, rng: &mut Pcg64Mcg) -> Vec<Vec<f64>> {
+        (0..self.starts).map(|_| problem.random_start_seeded(rng)).collect()
+    }
+
+    fn evaluate<F, P, M, C>(&self, problem: &F, starts: &[Vec<f64>], candidates: Vec<P>,
+        factory: C) -> Tuning<P>
+        where F: Problem + Clone, P: Clone, M: Minimizer<Budgeted<F>>, C: Fn(&P) -> M
+    {
+        assert!(!candidates.is_empty(), "candidates must not be empty");
+
+        let candidates: Vec<_> = candidates.into_iter().map(|candidate| {
+            let minimizer = factory(&candidate);
+
+            let mean_value = starts.iter()
+                .map(|start| run(problem, &minimizer, start.clone(), self.budget).value)
+                .sum::<f64>() / starts.len() as f64;
+
+            info!("Candidate reached a mean y = {:?}", mean_value);
+
+            (candidate, mean_value)
+        }).collect();
+
+        let (best, mean_value) = candidates.iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .cloned()
+            .unwrap();
+
+        Tuning {
+            best,
+            mean_value,
+            candidates
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use problems::Rosenbrock;
+    use gd::GradientDescent;
+    use line_search::{FixedStepWidth, ArmijoLineSearch};
+
+    use super::{race, HyperSearch};
+
+    #[test]
+    fn test_race() {
+        let armijo = GradientDescent::new();
+        let fixed = GradientDescent::new().line_search(FixedStepWidth::new(1.0e-4));
+
+        let comparison = race(&Rosenbrock::default(), &armijo, &fixed, vec![-1.5, 2.0], 1000);
+
+        assert!(comparison.first_wins(), "{:?}", comparison);
+        assert!(!comparison.second_wins());
+        assert!(comparison.first.evaluations <= 1000);
+        assert!(comparison.second.evaluations <= 1000);
+    }
+
+    #[test]
+    fn test_hyper_search() {
+        let mut search = HyperSearch::new(20, 1000);
+        search.seed(42);
+
+        let factory = |&decay_factor: &f64| GradientDescent::new()
+            .line_search(ArmijoLineSearch::new(0.5, 1.0, decay_factor, 0.0));
+
+        // too aggressive as well as too cautious backtracking performs poorly
+        let tuning = search.grid(&Rosenbrock::default(), vec![0.001, 0.01, 0.1, 0.5, 0.9, 0.99],
+            factory);
+
+        assert!(tuning.best >= 0.1 && tuning.best <= 0.9, "{:?}", tuning);
+        assert_eq!(tuning.candidates.len(), 6);
+        assert!(tuning.candidates.iter().all(|&(_, mean_value)| tuning.mean_value <= mean_value));
+
+        let random = search.random(&Rosenbrock::default(), 10,
+            |rng| rng.gen_range(0.001, 0.999), factory);
+
+        assert!(random.best >= 0.1 && random.best <= 0.9, "{:?}", random);
+
+        // the search is reproducible given the same seed
+        let repeated = search.random(&Rosenbrock::default(), 10,
+            |rng| rng.gen_range(0.001, 0.999), factory);
+
+        assert_eq!(random.best, repeated.best);
+        assert_eq!(random.mean_value, repeated.mean_value);
+    }
+}
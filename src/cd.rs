@@ -0,0 +1,247 @@
+use rand::{Rng, SeedableRng, random};
+use rand_pcg::Pcg64Mcg;
+
+use types::{Function1, Minimizer, Convergence, Report, ConfigError, check_positive, check_count};
+use line_search::{LineSearch, SearchHistory, ArmijoLineSearch};
+use utils::is_saddle_point;
+
+
+/// Specifies how `CoordinateDescent` selects the coordinate to update in each iteration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CoordinateRule {
+    /// Iterates over the coordinates in order.
+    Cyclic,
+    /// Draws the coordinates uniformly at random.
+    Random,
+    /// Selects the coordinate with the largest absolute gradient component, also known as
+    /// the Gauss-Southwell rule.
+    GaussSouthwell
+}
+
+
+/// A Coordinate Descent optimizer, which only moves along a single coordinate per iteration.
+#[derive(Debug, Clone)]
+pub struct CoordinateDescent<T> {
+    line_search: T,
+    rule: CoordinateRule,
+    rng: Pcg64Mcg,
+    gradient_tolerance: f64,
+    max_iterations: Option<u64>
+}
+
+impl CoordinateDescent<ArmijoLineSearch> {
+    /// Creates a new `CoordinateDescent` optimizer using the following defaults:
+    ///
+    /// - **`line_search`** = `ArmijoLineSearch(0.5, 1.0, 0.5, 0.0)`
+    /// - **`rule`** = `CoordinateRule::Cyclic`
+    /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`max_iterations`** = `None`
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new() -> CoordinateDescent<ArmijoLineSearch> {
+        CoordinateDescent {
+            line_search: ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0),
+            rule: CoordinateRule::Cyclic,
+            rng: Pcg64Mcg::new(random()),
+            gradient_tolerance: 1.0e-4,
+            max_iterations: None
+        }
+    }
+}
+
+impl Default for CoordinateDescent<ArmijoLineSearch> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: LineSearch> CoordinateDescent<T> {
+    /// Specifies the line search method to use.
+    pub fn line_search<S: LineSearch>(self, line_search: S) -> CoordinateDescent<S> {
+        CoordinateDescent {
+            line_search,
+            rule: self.rule,
+            rng: self.rng,
+            gradient_tolerance: self.gradient_tolerance,
+            max_iterations: self.max_iterations
+        }
+    }
+
+    /// Specifies the rule used to select the coordinate to update.
+    pub fn rule(mut self, rule: CoordinateRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Seeds the random number generator used by `CoordinateRule::Random`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the gradient tolerance which is used as abort criterion to decide
+    /// whether we reached a plateau.
+    pub fn gradient_tolerance(mut self, gradient_tolerance: f64) -> Self {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations, i.e., coordinate updates. A value of
+    /// `None` instructs the optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))
+    }
+}
+
+impl<F: Function1 + ?Sized, S: LineSearch> Minimizer<F> for CoordinateDescent<S> {
+    type Solution = Report;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Report {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        info!("Starting coordinate descent minimization: rule = {:?}, gradient_tolerance = {:?},
+            max_iterations = {:?}, line_search = {:?}",
+            self.rule, self.gradient_tolerance, self.max_iterations, self.line_search);
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "CoordinateDescent", rule = ?self.rule,
+            gradient_tolerance = self.gradient_tolerance, max_iterations = ?self.max_iterations,
+            line_search = ?self.line_search).entered();
+
+        let mut position = initial_position;
+        let mut value = function.value(&position);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut history = SearchHistory::new();
+        let mut rng = self.rng.clone();
+
+        loop {
+            let gradient = function.gradient(&position);
+
+            if is_saddle_point(&gradient, self.gradient_tolerance) {
+                info!("Gradient to small, stopping optimization");
+
+                return Report::new(position, value, iteration, Convergence::GradientTolerance);
+            }
+
+            let coordinate = match self.rule {
+                CoordinateRule::Cyclic => (iteration % position.len() as u64) as usize,
+                CoordinateRule::Random => rng.gen_range(0, position.len()),
+                CoordinateRule::GaussSouthwell => {
+                    gradient.iter().enumerate()
+                        .fold((0, 0.0), |(i, max), (j, g)| {
+                            if g.abs() > max { (j, g.abs()) } else { (i, max) }
+                        }).0
+                }
+            };
+
+            // a vanishing component is no descent direction, so there is nothing to do
+            if gradient[coordinate].abs() > self.gradient_tolerance {
+                let mut direction = vec![0.0; position.len()];
+                direction[coordinate] = -gradient[coordinate];
+
+                position = self.line_search
+                    .search_in(&mut history, function, &position, &direction, None)
+                    .position;
+                value = function.value(&position);
+            }
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                return Report::new(position, value, iteration, Convergence::MaxIterations);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::Sphere;
+    use types::{Function, Function1, Minimizer, Convergence, ConfigError};
+
+    use super::{CoordinateDescent, CoordinateRule};
+
+    test_minimizer!{CoordinateDescent::new(),
+        sphere => Sphere::default()}
+
+    // anisotropic quadratic f(x) = ∑ᵢ aᵢ xᵢ² + x₀ x₁
+    struct Anisotropic(Vec<f64>);
+
+    impl Function for Anisotropic {
+        fn value(&self, x: &[f64]) -> f64 {
+            self.0.iter().zip(x).map(|(a, x)| a * x * x).sum::<f64>() + x[0] * x[1]
+        }
+    }
+
+    impl Function1 for Anisotropic {
+        fn gradient(&self, x: &[f64]) -> Vec<f64> {
+            let mut gradient: Vec<_> = self.0.iter().zip(x).map(|(a, x)| 2.0 * a * x).collect();
+
+            gradient[0] += x[1];
+            gradient[1] += x[0];
+
+            gradient
+        }
+    }
+
+    #[test]
+    fn test_gauss_southwell() {
+        let function = Anisotropic(vec![1.0, 3.0, 0.1, 7.0, 0.01, 13.0]);
+        let start = vec![1.0, -1.0, 1.0, -1.0, 1.0, -1.0];
+
+        let cyclic = CoordinateDescent::new()
+            .rule(CoordinateRule::Cyclic)
+            .minimize(&function, start.clone());
+        let gauss_southwell = CoordinateDescent::new()
+            .rule(CoordinateRule::GaussSouthwell)
+            .minimize(&function, start.clone());
+        let random = CoordinateDescent::new()
+            .rule(CoordinateRule::Random)
+            .seed(42)
+            .minimize(&function, start);
+
+        for report in &[&cyclic, &gauss_southwell, &random] {
+            assert_eq!(report.convergence, Convergence::GradientTolerance);
+        }
+
+        assert!(gauss_southwell.iterations < cyclic.iterations,
+            "{} >= {}", gauss_southwell.iterations, cyclic.iterations);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(CoordinateDescent::new().validate(), Ok(()));
+        assert_eq!(CoordinateDescent::new().gradient_tolerance(0.0).validate(),
+            Err(ConfigError::OutOfRange {
+                name: "gradient_tolerance", value: 0.0, range: "(0, ∞]"
+            }));
+    }
+}
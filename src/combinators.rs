@@ -0,0 +1,803 @@
+use rand::{SeedableRng, random};
+use rand_pcg::Pcg64Mcg;
+#[cfg(feature = "rayon")]
+use rayon::ThreadPoolBuilder;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::time::{Duration, Instant};
+
+use types::{Function, Function1, Evaluation, Minimizer, Solution, ConfigError, check_count};
+use problems::Problem;
+use adapters::Tracking;
+
+
+/// Chains two minimizers, i.e., runs the `first` one and uses its solution as the initial
+/// position of the `second` one.
+///
+/// A typical use case is a coarse global search followed by a local refinement.
+#[derive(Debug, Copy, Clone)]
+pub struct Then<A, B> {
+    first: A,
+    second: B
+}
+
+impl<A, B> Then<A, B> {
+    /// Creates a new chain running the `first` minimizer followed by the `second` one.
+    pub fn new(first: A, second: B) -> Self {
+        Then {
+            first,
+            second
+        }
+    }
+}
+
+impl<F: ?Sized, A: Minimizer<F>, B: Minimizer<F>> Minimizer<F> for Then<A, B> {
+    type Solution = B::Solution;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> B::Solution {
+        let solution = self.first.minimize(function, initial_position);
+
+        info!("First minimizer found y = {:?}, continuing with the second one", solution.value());
+
+        self.second.minimize(function, solution.position().to_vec())
+    }
+}
+
+
+/// Runs a minimizer in a transformed search space, i.e., the position `y` seen by the
+/// minimizer is mapped coordinate-wise by a bijection `x = g(y)` before evaluating the
+/// function.
+///
+/// The bijection is given by its `inverse` `g⁻¹`, mapping the initial position into the
+/// search space, and its `derivative` `g'`, which is required to apply the chain rule to the
+/// gradient. The solution is mapped back into the original space.
+///
+/// A typical use case is to optimize positive parameters in log space, see `log()`, which
+/// enforces positivity implicitly and balances the scale of small and large values.
+#[derive(Debug, Copy, Clone)]
+pub struct Reparameterized<M> {
+    minimizer: M,
+    map: fn(f64) -> f64,
+    inverse: fn(f64) -> f64,
+    derivative: fn(f64) -> f64
+}
+
+impl<M> Reparameterized<M> {
+    /// Creates a new reparameterization of the `minimizer` given the bijection `map`, its
+    /// `inverse` and its `derivative`.
+    pub fn new(minimizer: M, map: fn(f64) -> f64, inverse: fn(f64) -> f64,
+               derivative: fn(f64) -> f64) -> Self {
+        Reparameterized {
+            minimizer,
+            map,
+            inverse,
+            derivative
+        }
+    }
+
+    /// Creates a new reparameterization of the `minimizer` in log space, i.e., `x = exp(y)`,
+    /// restricting all coordinates to be positive.
+    pub fn log(minimizer: M) -> Self {
+        Reparameterized::new(minimizer, f64::exp, f64::ln, f64::exp)
+    }
+}
+
+impl<F, M> Minimizer<F> for Reparameterized<M>
+    where F: Function1 + ?Sized,
+          M: for<'a> Minimizer<Transformed<'a, F>>
+{
+    type Solution = Solution;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+        let transformed = Transformed {
+            function,
+            map: self.map,
+            derivative: self.derivative
+        };
+
+        let initial_position = initial_position.into_iter().map(self.inverse).collect();
+
+        let solution = self.minimizer.minimize(&transformed, initial_position);
+
+        Solution::new(solution.position().iter().cloned().map(self.map).collect(),
+            solution.value())
+    }
+}
+
+
+/// A function `f` evaluated in a transformed search space, i.e., `h(y) = f(g(y))`, as seen
+/// by the minimizer of `Reparameterized`.
+#[derive(Debug)]
+pub struct Transformed<'a, F: 'a + ?Sized> {
+    function: &'a F,
+    map: fn(f64) -> f64,
+    derivative: fn(f64) -> f64
+}
+
+impl<'a, F: ?Sized> Transformed<'a, F> {
+    fn original(&self, position: &[f64]) -> Vec<f64> {
+        position.iter().cloned().map(self.map).collect()
+    }
+}
+
+impl<'a, F: Function + ?Sized> Function for Transformed<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(&self.original(position))
+    }
+}
+
+impl<'a, F: Function1 + ?Sized> Function1 for Transformed<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.value_and_gradient(position).1
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(&self.original(position));
+
+        // chain rule, i.e., ∂h/∂yᵢ = ∂f/∂xᵢ g'(yᵢ)
+        (value, gradient.into_iter().zip(position).map(|(g, &y)| g * (self.derivative)(y)).collect())
+    }
+}
+
+
+/// Runs a minimizer with a hard cap on the number of function evaluations, regardless of
+/// the algorithm and its own stopping criteria.
+///
+/// Each call of `value()`, `gradient()` or `value_and_gradient()` counts as one evaluation.
+/// Once the `budget` is exhausted, the minimization is aborted by unwinding the stack out
+/// of the minimizer, and the best evaluation so far is returned. Otherwise, the solution of
+/// the minimizer is returned. A panic of the minimizer or the function is propagated.
+#[derive(Debug, Copy, Clone)]
+pub struct WithEvalBudget<M> {
+    minimizer: M,
+    budget: u64
+}
+
+impl<M> WithEvalBudget<M> {
+    /// Creates a new wrapper running the `minimizer` with at most `budget` evaluations.
+    pub fn new(minimizer: M, budget: u64) -> Self {
+        WithEvalBudget::try_new(minimizer, budget).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new wrapper like `new()`, but returns an error instead of panicking if the
+    /// `budget` is `0`.
+    pub fn try_new(minimizer: M, budget: u64) -> Result<Self, ConfigError> {
+        if budget == 0 {
+            return Err(ConfigError::OutOfRange { name: "budget", value: 0.0, range: "[1, ∞)" });
+        }
+
+        Ok(WithEvalBudget {
+            minimizer,
+            budget
+        })
+    }
+}
+
+impl<F, M> Minimizer<F> for WithEvalBudget<M>
+    where F: Function + ?Sized,
+          M: for<'a> Minimizer<EvalBudgeted<'a, F>>
+{
+    type Solution = Solution;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+        let budgeted = EvalBudgeted {
+            tracking: Tracking::new(function),
+            budget: EvalBudget::new(self.budget)
+        };
+
+        match budgeted.budget.run(|| self.minimizer.minimize(&budgeted, initial_position)) {
+            Some(solution) => Solution::new(solution.position().to_vec(), solution.value()),
+            None => {
+                info!("Evaluation budget exhausted, returning best evaluation so far");
+
+                let (position, value) = budgeted.tracking.best()
+                    .expect("budget allows for at least one evaluation");
+
+                Solution::new(position, value)
+            }
+        }
+    }
+}
+
+
+/// A function `f` whose evaluations are limited by a budget, as seen by the minimizer of
+/// `WithEvalBudget`.
+///
+/// Each gradient is evaluated along with the value, such that the best evaluation is known
+/// even if the minimizer never asks for a value.
+#[derive(Debug)]
+pub struct EvalBudgeted<'a, F: 'a + ?Sized> {
+    tracking: Tracking<'a, F>,
+    budget: EvalBudget
+}
+
+impl<'a, F: Function + ?Sized> Function for EvalBudgeted<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.budget.spend();
+
+        self.tracking.value(position)
+    }
+}
+
+impl<'a, F: Function1 + ?Sized> Function1 for EvalBudgeted<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.budget.spend();
+
+        self.tracking.value_and_gradient(position).1
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        self.budget.spend();
+
+        self.tracking.value_and_gradient(position)
+    }
+}
+
+
+/// A budget of function evaluations, which aborts a minimization by unwinding the stack once
+/// it is exhausted, as used by `WithEvalBudget` and `benchmark::race()`.
+#[derive(Debug)]
+pub struct EvalBudget {
+    evaluations: Cell<u64>,
+    budget: u64
+}
+
+// payload used to abort a minimization once the budget is exhausted
+struct EvalBudgetExceeded;
+
+impl EvalBudget {
+    /// Creates a new budget allowing for `budget` evaluations.
+    pub fn new(budget: u64) -> EvalBudget {
+        EvalBudget {
+            evaluations: Cell::new(0),
+            budget
+        }
+    }
+
+    /// Returns the number of evaluations spent so far.
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations.get()
+    }
+
+    /// Spends a single evaluation, or aborts the running minimization if the budget is
+    /// exhausted already.
+    pub fn spend(&self) {
+        if self.evaluations.get() >= self.budget {
+            // does not invoke the panic hook, i.e., nothing is printed
+            resume_unwind(Box::new(EvalBudgetExceeded));
+        }
+
+        self.evaluations.set(self.evaluations.get() + 1);
+    }
+
+    /// Runs the `minimization`, returning `None` if it has been aborted since the budget is
+    /// exhausted. Any other panic is propagated.
+    pub fn run<T, M: FnOnce() -> T>(&self, minimization: M) -> Option<T> {
+        match catch_unwind(AssertUnwindSafe(minimization)) {
+            Ok(result) => Some(result),
+            Err(payload) => {
+                if !payload.is::<EvalBudgetExceeded>() {
+                    resume_unwind(payload);
+                }
+
+                None
+            }
+        }
+    }
+}
+
+
+/// Observes the progress of a minimization, see `WithObserver`, e.g., to collect a record
+/// of each iteration in a user-supplied buffer instead of logging it.
+///
+/// Every closure with the same signature as `observe()` is an observer as well.
+pub trait Observer {
+    /// Observes the `value` at the `position` of the given `iteration`, where the
+    /// `iteration` `0` refers to the initial position.
+    fn observe(&mut self, iteration: u64, position: &[f64], value: f64);
+}
+
+impl<O: FnMut(u64, &[f64], f64)> Observer for O {
+    fn observe(&mut self, iteration: u64, position: &[f64], value: f64) {
+        self(iteration, position, value)
+    }
+}
+
+
+/// Runs a minimizer while an `Observer` is notified about each iteration, regardless of the
+/// algorithm and independently of the logging backend.
+///
+/// Since minimizers do not expose their iterations, an iteration is identified by an
+/// evaluation of the gradient at a new position, i.e., each call of `gradient()` or
+/// `value_and_gradient()` at a position that differs from the previous one. This matches
+/// the accepted positions of minimizers evaluating the gradient once per iteration, e.g.,
+/// `GradientDescent` using its default line search. Note that other minimizers evaluate the
+/// gradient at intermediate positions as well, e.g., the rejected steps of `TrustRegion`.
+#[derive(Debug)]
+pub struct WithObserver<M, O> {
+    minimizer: M,
+    observer: RefCell<O>
+}
+
+impl<M, O: Observer> WithObserver<M, O> {
+    /// Creates a new wrapper running the `minimizer` while notifying the `observer`.
+    pub fn new(minimizer: M, observer: O) -> Self {
+        WithObserver {
+            minimizer,
+            observer: RefCell::new(observer)
+        }
+    }
+
+    /// Returns the observer, e.g., to access the records it collected.
+    pub fn into_observer(self) -> O {
+        self.observer.into_inner()
+    }
+}
+
+impl<F, M, O, S> Minimizer<F> for WithObserver<M, O>
+    where F: Function1 + ?Sized,
+          M: for<'a> Minimizer<Observed<'a, F, O>, Solution=S>,
+          O: Observer,
+          S: Evaluation
+{
+    type Solution = S;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> S {
+        let observed = Observed {
+            function,
+            observer: &self.observer,
+            iteration: Cell::new(0),
+            previous: RefCell::new(None)
+        };
+
+        self.minimizer.minimize(&observed, initial_position)
+    }
+}
+
+
+/// A function `f` whose evaluations of the gradient are reported to an `Observer`, as seen by
+/// the minimizer of `WithObserver`.
+#[derive(Debug)]
+pub struct Observed<'a, F: 'a + ?Sized, O: 'a> {
+    function: &'a F,
+    observer: &'a RefCell<O>,
+    iteration: Cell<u64>,
+    previous: RefCell<Option<Vec<f64>>>
+}
+
+impl<'a, F: ?Sized, O: Observer> Observed<'a, F, O> {
+    // notifies the observer unless the position has been observed most recently
+    fn observe(&self, position: &[f64], value: f64) {
+        let mut previous = self.previous.borrow_mut();
+
+        if previous.as_ref().is_some_and(|previous| &previous[..] == position) {
+            return;
+        }
+
+        if previous.is_some() {
+            self.iteration.set(self.iteration.get() + 1);
+        }
+
+        *previous = Some(position.to_vec());
+
+        self.observer.borrow_mut().observe(self.iteration.get(), position, value);
+    }
+}
+
+impl<'a, F: Function + ?Sized, O> Function for Observed<'a, F, O> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(position)
+    }
+}
+
+impl<'a, F: Function1 + ?Sized, O: Observer> Function1 for Observed<'a, F, O> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.value_and_gradient(position).1
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        let (value, gradient) = self.function.value_and_gradient(position);
+
+        self.observe(position, value);
+
+        (value, gradient)
+    }
+}
+
+
+/// Restarts a local minimizer from random positions of a `Problem` until a wall-clock
+/// `budget` elapses and returns the best solution, i.e., an anytime global optimization.
+///
+/// The first run starts from the initial position, all further ones from
+/// `Problem::random_start_seeded()`. A run is never interrupted, hence the budget is exceeded
+/// by the duration of the last run. Thus, the number of runs and the solution depend on the
+/// speed of the machine, even for a fixed seed, unless the number of runs is limited by
+/// `max_runs()` before the budget elapses.
+#[derive(Debug, Clone)]
+pub struct RandomRestart<M> {
+    minimizer: M,
+    budget: Duration,
+    max_runs: Option<u64>,
+    rng: Pcg64Mcg
+}
+
+impl<M> RandomRestart<M> {
+    /// Creates a new wrapper restarting the `minimizer` until the `budget` elapses.
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new(minimizer: M, budget: Duration) -> Self {
+        RandomRestart {
+            minimizer,
+            budget,
+            max_runs: None,
+            rng: Pcg64Mcg::new(random())
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the number of maximally run local minimizations, including the one from the
+    /// initial position. A value of `None` restarts until the budget elapses.
+    pub fn max_runs(mut self, max_runs: Option<u64>) -> Self {
+        self.max_runs = max_runs;
+        self
+    }
+
+    /// Creates a parallel version of this wrapper that runs the local minimizations on
+    /// `workers` threads, see `ParallelRandomRestart`.
+    #[cfg(feature = "rayon")]
+    pub fn parallel(self, workers: usize) -> ParallelRandomRestart<M> {
+        ParallelRandomRestart {
+            restart: self,
+            workers
+        }
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error. The wrapped minimizer is checked by its own
+    /// minimizations.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_runs", self.max_runs.unwrap_or(1))
+    }
+
+    // whether another run is allowed after `runs` runs, which started at `start`
+    fn proceed(&self, start: Instant, runs: u64) -> bool {
+        start.elapsed() < self.budget && self.max_runs.is_none_or(|max_runs| runs < max_runs)
+    }
+}
+
+impl<P: Problem + ?Sized, M: Minimizer<P>> Minimizer<P> for RandomRestart<M> {
+    type Solution = M::Solution;
+
+    fn minimize(&self, problem: &P, initial_position: Vec<f64>) -> M::Solution {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        let start = Instant::now();
+
+        let mut rng = self.rng.clone();
+        let mut best = self.minimizer.minimize(problem, initial_position);
+        let mut runs = 1;
+
+        while self.proceed(start, runs) {
+            let solution = self.minimizer.minimize(problem, problem.random_start_seeded(&mut rng));
+
+            runs += 1;
+
+            if solution.value() < best.value() {
+                debug!("Run {:6}: improved to y = {:?}", runs, solution.value());
+
+                best = solution;
+            }
+        }
+
+        info!("Stopped after {} runs, returning y = {:?}", runs, best.value());
+
+        best
+    }
+}
+
+
+/// Parallel version of `RandomRestart`, running the independent local minimizations on
+/// several threads concurrently, requires the `rayon` feature.
+///
+/// The runs are performed in rounds of one run per worker, where the budget and the maximal
+/// number of runs are checked before each round. The starts are drawn in the same order as
+/// by the serial version and ties are resolved in favor of the earlier run, hence both
+/// return the same solution given the same seed and the same number of runs.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone)]
+pub struct ParallelRandomRestart<M> {
+    restart: RandomRestart<M>,
+    workers: usize
+}
+
+#[cfg(feature = "rayon")]
+impl<M> ParallelRandomRestart<M> {
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.restart.validate()?;
+        check_count("workers", self.workers as u64)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<P, M> Minimizer<P> for ParallelRandomRestart<M>
+    where P: Problem + Sync + ?Sized, M: Minimizer<P> + Sync, M::Solution: Send
+{
+    type Solution = M::Solution;
+
+    fn minimize(&self, problem: &P, initial_position: Vec<f64>) -> M::Solution {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.workers)
+            .build()
+            .expect("failed to create the worker threads");
+
+        let start = Instant::now();
+
+        let mut rng = self.restart.rng.clone();
+        let mut initial_position = Some(initial_position);
+        let mut best: Option<M::Solution> = None;
+        let mut runs = 0;
+
+        while best.is_none() || self.restart.proceed(start, runs) {
+            let round = self.restart.max_runs
+                .map_or(self.workers as u64, |max_runs| (max_runs - runs).min(self.workers as u64));
+
+            let starts: Vec<_> = (0..round).map(|_| match initial_position.take() {
+                Some(position) => position,
+                None => problem.random_start_seeded(&mut rng)
+            }).collect();
+
+            let solutions: Vec<_> = pool.install(|| starts.into_par_iter()
+                .map(|start| self.restart.minimizer.minimize(problem, start))
+                .collect());
+
+            for solution in solutions {
+                runs += 1;
+
+                if best.as_ref().is_none_or(|best| solution.value() < best.value()) {
+                    debug!("Run {:6}: improved to y = {:?}", runs, solution.value());
+
+                    best = Some(solution);
+                }
+            }
+        }
+
+        let best = best.unwrap();
+
+        info!("Stopped after {} runs, returning y = {:?}", runs, best.value());
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use problems::{Sphere, Rosenbrock, Rastrigin};
+    use types::{Function, Function1, Minimizer, Solution, ConfigError};
+    use adapters::Counting;
+    use gd::GradientDescent;
+    use cd::CoordinateDescent;
+
+    use super::{Then, Reparameterized, WithEvalBudget, Observer, WithObserver, RandomRestart};
+
+    #[test]
+    fn test_then() {
+        let problem = Rosenbrock::default();
+        let start = vec![-2.0, 3.0];
+
+        let crude = CoordinateDescent::new().max_iterations(Some(100));
+        let fine = GradientDescent::new().max_iterations(Some(100));
+
+        let crude_solution = crude.minimize(&problem, start.clone());
+        let fine_solution = fine.minimize(&problem, start.clone());
+        let chained_solution = Then::new(crude, fine).minimize(&problem, start);
+
+        assert!(chained_solution.value < crude_solution.value);
+        assert!(chained_solution.value < fine_solution.value);
+    }
+
+    // f(x) = ∑ xᵢ / cᵢ - ln(xᵢ), defined for positive x only, with its minimum at x = c
+    struct Barrier(Vec<f64>);
+
+    impl Function for Barrier {
+        fn value(&self, x: &[f64]) -> f64 {
+            if x.iter().all(|&x| x > 0.0) {
+                x.iter().zip(&self.0).map(|(x, c)| x / c - x.ln()).sum()
+            } else {
+                f64::INFINITY
+            }
+        }
+    }
+
+    impl Function1 for Barrier {
+        fn gradient(&self, x: &[f64]) -> Vec<f64> {
+            x.iter().zip(&self.0).map(|(x, c)| 1.0 / c - 1.0 / x).collect()
+        }
+    }
+
+    #[test]
+    fn test_reparameterized() {
+        // the minimum is badly scaled and close to the boundary in the first coordinate
+        let minimum = [1.0e-3, 10.0];
+        let problem = Barrier(minimum.to_vec());
+
+        let minimizer = GradientDescent::new().max_iterations(Some(100));
+
+        let direct = minimizer.minimize(&problem, vec![1.0, 1.0]);
+        let log = Reparameterized::log(minimizer).minimize(&problem, vec![1.0, 1.0]);
+
+        let error = |position: &[f64]| position.iter().zip(&minimum)
+            .map(|(x, c)| ((x - c) / c).abs())
+            .fold(0.0, f64::max);
+
+        assert!(log.position.iter().all(|&x| x > 0.0));
+        assert!(error(&log.position) < 1.0e-4, "{:?} is not close to the minimum", log.position);
+        assert!(error(&log.position) < error(&direct.position),
+            "{:?} vs. {:?}", log.position, direct.position);
+    }
+
+    #[test]
+    fn test_with_eval_budget() {
+        let counting = Counting::new(Rosenbrock::default());
+        let start = vec![-1.2, 1.0];
+
+        let solution = WithEvalBudget::new(GradientDescent::new(), 100)
+            .minimize(&counting, start.clone());
+
+        let evaluations = counting.values() + counting.gradients() +
+            counting.values_and_gradients();
+
+        assert_eq!(evaluations, 100);
+        assert!(solution.value < counting.value(&start));
+        assert_eq!(solution.value, counting.value(&solution.position));
+
+        // a sufficient budget does not interfere with the minimizer
+        let unlimited = GradientDescent::new().minimize(&Rosenbrock::default(), start.clone());
+        let budgeted = WithEvalBudget::new(GradientDescent::new(), 1_000_000)
+            .minimize(&Rosenbrock::default(), start);
+
+        assert_eq!(unlimited.position, budgeted.position);
+        assert_eq!(unlimited.value, budgeted.value);
+
+        assert_eq!(WithEvalBudget::try_new(GradientDescent::new(), 0).err().map(|e| e.to_string()),
+            Some("budget = 0 must be in range [1, ∞)".to_string()));
+    }
+
+    // steps along the negative gradient with a fixed step width, never evaluating the value
+    struct GradientSteps;
+
+    impl<F: Function1 + ?Sized> Minimizer<F> for GradientSteps {
+        type Solution = Solution;
+
+        fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+            let mut position = initial_position;
+
+            for _ in 0..1000 {
+                let gradient = function.gradient(&position);
+
+                for (x, g) in position.iter_mut().zip(gradient) {
+                    *x -= 0.1 * g;
+                }
+            }
+
+            Solution::new(position, f64::NAN)
+        }
+    }
+
+    #[test]
+    fn test_with_eval_budget_gradients_only() {
+        let start = vec![3.0, -4.0];
+        let solution = WithEvalBudget::new(GradientSteps, 10)
+            .minimize(&Sphere::default(), start.clone());
+
+        // the last of the 10 gradients is evaluated after 9 steps, each scaling x by 0.8
+        for (x, start) in solution.position.iter().zip(&start) {
+            assert!((x - start * 0.8f64.powi(9)).abs() < 1.0e-12, "{:?}", solution.position);
+        }
+        assert_eq!(solution.value, Sphere::default().value(&solution.position));
+    }
+
+    // collects the observed values
+    struct Values(Vec<f64>);
+
+    impl Observer for Values {
+        fn observe(&mut self, _iteration: u64, _position: &[f64], value: f64) {
+            self.0.push(value);
+        }
+    }
+
+    #[test]
+    fn test_with_observer() {
+        let start = vec![1.0, -2.0, 0.5];
+        let mut records = Vec::new();
+
+        let report = {
+            let observing = WithObserver::new(GradientDescent::new().record_trajectory(true),
+                |iteration, position: &[f64], value| {
+                    records.push((iteration, position.to_vec(), value))
+                });
+
+            observing.minimize(&Sphere::new(3), start.clone())
+        };
+
+        let trajectory = report.trajectory.unwrap();
+
+        // the initial position followed by the trajectory
+        assert_eq!(records.len(), trajectory.len() + 1);
+        assert_eq!(records[0], (0, start.clone(), Sphere::new(3).value(&start)));
+
+        for (i, (record, &(ref position, value))) in records[1..].iter().zip(&trajectory)
+            .enumerate()
+        {
+            assert_eq!(record, &(i as u64 + 1, position.clone(), value));
+        }
+
+        // an observer can be retrieved after the minimization
+        let observing = WithObserver::new(GradientDescent::new(), Values(Vec::new()));
+        let report = observing.minimize(&Sphere::new(3), start);
+
+        let values = observing.into_observer().0;
+
+        assert_eq!(values.len() as u64, report.iterations + 1);
+        assert_eq!(values.last(), Some(&report.value));
+    }
+
+    #[test]
+    fn test_random_restart() {
+        let problem = Rastrigin::new(3);
+        let start = vec![3.2, -2.7, 1.9];
+
+        let single = GradientDescent::new().minimize(&problem, start.clone());
+        // an ample budget, such that the number of runs is limited only
+        let restarted = RandomRestart::new(GradientDescent::new(), Duration::from_secs(3600))
+            .seed(42)
+            .max_runs(Some(10))
+            .minimize(&problem, start);
+
+        // the local minimizer gets trapped in the local minimum next to the start
+        assert!(single.value > 1.0);
+        assert!(restarted.value < single.value, "{} vs. {}", restarted.value, single.value);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_random_restart() {
+        let problem = Rastrigin::new(3);
+        let start = vec![3.2, -2.7, 1.9];
+
+        // an ample budget, such that the number of runs is limited only
+        let restart = RandomRestart::new(GradientDescent::new(), Duration::from_secs(3600))
+            .seed(42)
+            .max_runs(Some(10));
+
+        let serial = restart.clone().minimize(&problem, start.clone());
+
+        for &workers in &[1, 3, 4] {
+            let parallel = restart.clone().parallel(workers).minimize(&problem, start.clone());
+
+            assert_eq!(parallel.position, serial.position);
+            assert_eq!(parallel.value, serial.value);
+        }
+    }
+
+    #[test]
+    fn test_random_restart_validate() {
+        let restart = RandomRestart::new(GradientDescent::new(), Duration::from_secs(1));
+
+        assert_eq!(restart.clone().validate(), Ok(()));
+        assert_eq!(restart.max_runs(Some(0)).validate(),
+            Err(ConfigError::OutOfRange { name: "max_runs", value: 0.0, range: "[1, ∞)" }));
+    }
+}
@@ -0,0 +1,240 @@
+use std::ops::{Add, Sub, Mul, Neg};
+
+use types::{Function, Function1};
+
+
+/// A complex number `re + im i`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Complex {
+    /// The real part.
+    pub re: f64,
+    /// The imaginary part.
+    pub im: f64
+}
+
+impl Complex {
+    /// Creates a new `Complex` number given its real part `re` and imaginary part `im`.
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex {
+            re,
+            im
+        }
+    }
+
+    /// Returns the complex conjugate `re - im i`.
+    pub fn conj(self) -> Complex {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// Returns the squared absolute value `|z|² = re² + im²`.
+    pub fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re)
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: f64) -> Complex {
+        Complex::new(self.re * other, self.im * other)
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+
+/// Defines a real-valued objective function `f` of complex parameters `z`, along with its
+/// Wirtinger gradient.
+///
+/// Such a function is not holomorphic unless it is constant, thus it is differentiated with
+/// respect to `z` and its conjugate `z̄` as if they were independent. The gradient is the
+/// conjugate Wirtinger derivative `∂f/∂z̄ = ½ (∂f/∂x + i ∂f/∂y)` for `z = x + y i`, which
+/// points into the direction of the steepest ascent, e.g., `∂/∂z̄ |z - c|² = z - c`.
+pub trait ComplexFunction {
+    /// Computes the objective function at a given complex `position` `z`.
+    fn value(&self, position: &[Complex]) -> f64;
+
+    /// Computes the conjugate Wirtinger derivative `∂f/∂z̄ᵢ` for each coordinate at a given
+    /// complex `position` `z`.
+    fn gradient(&self, position: &[Complex]) -> Vec<Complex>;
+}
+
+
+/// Maps a `ComplexFunction` of `n` complex parameters onto a real `Function1` of `2n`
+/// parameters, i.e., the real parts followed by the imaginary parts, such that any
+/// minimizer is applicable.
+///
+/// The real gradient follows from the Wirtinger gradient by `∂f/∂x = 2 Re ∂f/∂z̄` and
+/// `∂f/∂y = 2 Im ∂f/∂z̄`.
+///
+/// # Examples
+///
+/// ```
+/// # use self::optimization::*;
+/// struct Distance(Complex);
+///
+/// impl ComplexFunction for Distance {
+///     fn value(&self, z: &[Complex]) -> f64 {
+///         (z[0] - self.0).norm_sqr()
+///     }
+///
+///     fn gradient(&self, z: &[Complex]) -> Vec<Complex> {
+///         vec![z[0] - self.0]
+///     }
+/// }
+///
+/// let function = Realified::new(Distance(Complex::new(1.0, -2.0)));
+///
+/// assert_eq!(function.gradient(&[0.0, 0.0]), vec![-2.0, 4.0]);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Realified<F> {
+    function: F
+}
+
+impl<F: ComplexFunction> Realified<F> {
+    /// Creates a new real function wrapping the complex `function`.
+    pub fn new(function: F) -> Self {
+        Realified {
+            function
+        }
+    }
+
+    /// Stacks the real and the imaginary parts of the complex `position`, e.g., to start a
+    /// minimization.
+    pub fn to_real(position: &[Complex]) -> Vec<f64> {
+        position.iter().map(|z| z.re).chain(position.iter().map(|z| z.im)).collect()
+    }
+
+    /// Inverts `to_real()`, e.g., to recover the complex parameters of a solution.
+    pub fn to_complex(position: &[f64]) -> Vec<Complex> {
+        assert!(position.len() % 2 == 0,
+            "position must consist of real and imaginary parts");
+
+        let (re, im) = position.split_at(position.len() / 2);
+
+        re.iter().zip(im).map(|(&re, &im)| Complex::new(re, im)).collect()
+    }
+}
+
+impl<F: ComplexFunction> Function for Realified<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(&Self::to_complex(position))
+    }
+}
+
+impl<F: ComplexFunction> Function1 for Realified<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        let gradient: Vec<_> = self.function.gradient(&Self::to_complex(position)).into_iter()
+            .map(|g| g * 2.0)
+            .collect();
+
+        Self::to_real(&gradient)
+    }
+}
+
+impl_add!{[F,] Realified<F>}
+
+
+#[cfg(test)]
+mod tests {
+    use numeric::NumericalDifferentiation;
+    use types::{Function1, Minimizer};
+    use gd::GradientDescent;
+    use utils::are_close;
+
+    use super::{Complex, ComplexFunction, Realified};
+
+    // ∑ᵢ |zᵢ - cᵢ|² + |z₀ z₁|²
+    struct Coupled(Vec<Complex>);
+
+    impl ComplexFunction for Coupled {
+        fn value(&self, z: &[Complex]) -> f64 {
+            z.iter().zip(&self.0).map(|(&z, &c)| (z - c).norm_sqr()).sum::<f64>() +
+                (z[0] * z[1]).norm_sqr()
+        }
+
+        fn gradient(&self, z: &[Complex]) -> Vec<Complex> {
+            // ∂/∂z̄₀ |z₀ z₁|² = z₀ |z₁|²
+            let coupling = [z[0] * z[1].norm_sqr(), z[1] * z[0].norm_sqr()];
+
+            z.iter().zip(&self.0).zip(&coupling).map(|((&z, &c), &g)| z - c + g).collect()
+        }
+    }
+
+    // ∑ᵢ |zᵢ - cᵢ|²
+    struct Distance(Vec<Complex>);
+
+    impl ComplexFunction for Distance {
+        fn value(&self, z: &[Complex]) -> f64 {
+            z.iter().zip(&self.0).map(|(&z, &c)| (z - c).norm_sqr()).sum()
+        }
+
+        fn gradient(&self, z: &[Complex]) -> Vec<Complex> {
+            z.iter().zip(&self.0).map(|(&z, &c)| z - c).collect()
+        }
+    }
+
+    #[test]
+    fn test_gradient() {
+        let target = vec![Complex::new(1.0, -2.0), Complex::new(0.5, 0.25)];
+
+        let function = Realified::new(Coupled(target.clone()));
+        let numerical = NumericalDifferentiation::new(Realified::new(Coupled(target)));
+
+        let position = [0.3, -1.2, 0.7, 2.0];
+
+        for (a, n) in function.gradient(&position).into_iter().zip(numerical.gradient(&position)) {
+            assert!(are_close(a, n, 1.0e-5), "{} differs from {}", a, n);
+        }
+    }
+
+    #[test]
+    fn test_minimize() {
+        let target = vec![Complex::new(1.0, -2.0), Complex::new(-0.5, 3.0)];
+
+        let function = Realified::new(Distance(target.clone()));
+        let start = Realified::<Distance>::to_real(&[Complex::new(0.0, 0.0); 2]);
+
+        assert_eq!(Realified::<Distance>::to_complex(&Realified::<Distance>::to_real(&target)),
+            target);
+
+        let report = GradientDescent::new().minimize(&function, start);
+        let solution = Realified::<Distance>::to_complex(&report.position);
+
+        for (z, c) in solution.iter().zip(&target) {
+            assert!((*z - *c).norm_sqr().sqrt() < 1.0e-4, "{:?} is not {:?}", solution, target);
+        }
+    }
+}
@@ -1,29 +1,96 @@
-use log::Level::Trace;
+use std::cell::RefCell;
+use std::mem;
+use std::ops::Add;
 
-use types::{Function1, Minimizer, Solution};
-use line_search::{LineSearch, ArmijoLineSearch};
-use utils::is_saddle_point;
+use types::{Function, Function1, MutFunction1, Minimizer, Convergence, Report, ConfigError,
+    check_range, check_positive, check_count};
+use line_search::{LineSearch, SearchHistory, Step, ArmijoLineSearch};
+use utils::{is_saddle_point, dot};
+use numeric::forward_difference;
+
+
+/// Number of consecutive small steps after which the minimization is stopped.
+const SMALL_STEP_ITERATIONS: u64 = 3;
+
+/// Relative error up to which the gradient and its finite difference approximation agree.
+const GRADIENT_CHECK_TOLERANCE: f64 = 1.0e-3;
+
+/// Relative increase of the value per step tolerated in strict mode, e.g., due to rounding.
+const STRICT_TOLERANCE: f64 = 1.0e-12;
+
+
+/// Specifies how the search direction of `GradientDescent` is derived from the gradients.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Variant {
+    /// Steepest descent along the negative gradient `-∇f(x)`.
+    #[default]
+    Vanilla,
+    /// Heavy ball momentum with the factor `β` ∈ [0, 1), i.e., the direction
+    /// `dₖ = -∇f(xₖ) + β dₖ₋₁` accumulates the previous directions. Whenever `dₖ` is not a
+    /// descent direction, the momentum is discarded.
+    Momentum(f64),
+    /// Nesterov's accelerated gradient with the momentum factor `β` ∈ [0, 1), i.e., the line
+    /// search starts from the extrapolation `yₖ = xₖ + β (xₖ - xₖ₋₁)` along `-∇f(yₖ)`. Whenever
+    /// `f(yₖ) > f(xₖ)`, the momentum is discarded, also known as adaptive restart.
+    Nesterov(f64)
+}
 
 
 /// A simple Gradient Descent optimizer.
-#[derive(Default)]
+#[derive(Debug, Clone, Default)]
 pub struct GradientDescent<T> {
     line_search: T,
     gradient_tolerance: f64,
-    max_iterations: Option<u64>
+    relative_gradient_tolerance: Option<f64>,
+    max_iterations: Option<u64>,
+    target_value: Option<f64>,
+    min_step: f64,
+    position_tolerance: Option<f64>,
+    max_step_norm: Option<f64>,
+    record_trajectory: bool,
+    record_step_widths: bool,
+    verify_gradient: bool,
+    step_width_hint: bool,
+    return_best: bool,
+    strict: bool,
+    variant: Variant
 }
 
 impl GradientDescent<ArmijoLineSearch> {
     /// Creates a new `GradientDescent` optimizer using the following defaults:
     ///
-    /// - **`line_search`** = `ArmijoLineSearch(0.5, 1.0, 0.5)`
+    /// - **`line_search`** = `ArmijoLineSearch(0.5, 1.0, 0.5, 0.0)`
     /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`relative_gradient_tolerance`** = `None`
     /// - **`max_iterations`** = `None`
+    /// - **`target_value`** = `None`
+    /// - **`min_step`** = `0.0`
+    /// - **`position_tolerance`** = `None`
+    /// - **`max_step_norm`** = `None`
+    /// - **`record_trajectory`** = `false`
+    /// - **`record_step_widths`** = `false`
+    /// - **`verify_gradient`** = `false`
+    /// - **`step_width_hint`** = `false`
+    /// - **`return_best`** = `false`
+    /// - **`strict`** = `false`
+    /// - **`variant`** = `Variant::Vanilla`
     pub fn new() -> GradientDescent<ArmijoLineSearch> {
         GradientDescent {
-            line_search: ArmijoLineSearch::new(0.5, 1.0, 0.5),
+            line_search: ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0),
             gradient_tolerance: 1.0e-4,
-            max_iterations: None
+            relative_gradient_tolerance: None,
+            max_iterations: None,
+            target_value: None,
+            min_step: 0.0,
+            position_tolerance: None,
+            max_step_norm: None,
+            record_trajectory: false,
+            record_step_widths: false,
+            verify_gradient: false,
+            step_width_hint: false,
+            return_best: false,
+            strict: false,
+            variant: Variant::Vanilla
         }
     }
 }
@@ -34,93 +101,1106 @@ impl<T: LineSearch> GradientDescent<T> {
         GradientDescent {
             line_search,
             gradient_tolerance: self.gradient_tolerance,
-            max_iterations: self.max_iterations
+            relative_gradient_tolerance: self.relative_gradient_tolerance,
+            max_iterations: self.max_iterations,
+            target_value: self.target_value,
+            min_step: self.min_step,
+            position_tolerance: self.position_tolerance,
+            max_step_norm: self.max_step_norm,
+            record_trajectory: self.record_trajectory,
+            record_step_widths: self.record_step_widths,
+            verify_gradient: self.verify_gradient,
+            step_width_hint: self.step_width_hint,
+            return_best: self.return_best,
+            strict: self.strict,
+            variant: self.variant
         }
     }
 
     /// Adjusts the gradient tolerance which is used as abort criterion to decide
     /// whether we reached a plateau.
     pub fn gradient_tolerance(mut self, gradient_tolerance: f64) -> Self {
-        assert!(gradient_tolerance > 0.0);
-
         self.gradient_tolerance = gradient_tolerance;
         self
     }
 
+    /// Adjusts the relative gradient tolerance, i.e., the optimization is also stopped as
+    /// soon as each absolute gradient component drops to or below the relative tolerance
+    /// times the largest absolute component of the initial gradient. In contrast to the
+    /// `gradient_tolerance`, this criterion is invariant to the scale of the function.
+    pub fn relative_gradient_tolerance(mut self, relative_gradient_tolerance: f64) -> Self {
+        self.relative_gradient_tolerance = Some(relative_gradient_tolerance);
+        self
+    }
+
     /// Adjusts the number of maximally run iterations. A value of `None` instructs the
     /// optimizer to ignore the nubmer of iterations.
     pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
-        assert!(max_iterations.map_or(true, |max_iterations| max_iterations > 0));
-
         self.max_iterations = max_iterations;
         self
     }
-}
 
-impl<F: Function1, S: LineSearch> Minimizer<F> for GradientDescent<S>
-{
-    type Solution = Solution;
+    /// Adjusts the target value, i.e., the optimization is stopped as soon as the value drops
+    /// to or below it. A value of `None` disables this criterion.
+    pub fn target_value(mut self, target_value: Option<f64>) -> Self {
+        self.target_value = target_value;
+        self
+    }
+
+    /// Adjusts the minimal step, i.e., the euclidean distance between two consecutive
+    /// positions, below which a step is considered as stalled. If several consecutive steps
+    /// stall, the optimization is stopped. A value of `0.0` disables this criterion.
+    pub fn min_step(mut self, min_step: f64) -> Self {
+        self.min_step = min_step;
+        self
+    }
+
+    /// Adjusts the position tolerance, i.e., the optimization is stopped as soon as a step
+    /// `‖xₖ₊₁ - xₖ‖` drops below the tolerance relative to the norm `‖xₖ‖ + ε`. In contrast to
+    /// the gradient tolerance, this criterion triggers in flat regions around a minimum as
+    /// well, where the gradient vanishes slowly.
+    pub fn position_tolerance(mut self, position_tolerance: f64) -> Self {
+        self.position_tolerance = Some(position_tolerance);
+        self
+    }
+
+    /// Adjusts the maximal step, i.e., the euclidean distance between two consecutive
+    /// positions. Longer search directions as well as accepted steps are rescaled to this
+    /// length, which keeps the line search from overshooting wildly and backtracking many
+    /// times, e.g., on steep functions. A value of `None` disables this limit.
+    pub fn max_step_norm(mut self, max_step_norm: Option<f64>) -> Self {
+        self.max_step_norm = max_step_norm;
+        self
+    }
+
+    /// Specifies whether the accepted iterates of each iteration are recorded and returned
+    /// as part of the `Report`, e.g., to visualize the path of the descent.
+    pub fn record_trajectory(mut self, record_trajectory: bool) -> Self {
+        self.record_trajectory = record_trajectory;
+        self
+    }
+
+    /// Specifies whether the step widths chosen by the line search in each iteration are
+    /// recorded and returned as part of the `Report`, e.g., to debug the convergence.
+    pub fn record_step_widths(mut self, record_step_widths: bool) -> Self {
+        self.record_step_widths = record_step_widths;
+        self
+    }
+
+    /// Specifies whether the supplied gradient is compared against a finite difference
+    /// approximation at the initial position, panicking on a large disagreement. This helps
+    /// to catch errors in hand-written gradients, but costs an additional function
+    /// evaluation per dimension.
+    pub fn verify_gradient(mut self, verify_gradient: bool) -> Self {
+        self.verify_gradient = verify_gradient;
+        self
+    }
+
+    /// Specifies whether the line search is started from a hint derived from the previous
+    /// iteration instead of from scratch, see `LineSearch::search_with_hint()`.
+    ///
+    /// The hint is the previously accepted step width, scaled such that the first-order
+    /// change of the value is the same as in the previous iteration. This pays off in
+    /// particular if the line search backtracks in many small decrements.
+    pub fn step_width_hint(mut self, step_width_hint: bool) -> Self {
+        self.step_width_hint = step_width_hint;
+        self
+    }
+
+    /// Specifies whether the position with the lowest value visited during the optimization
+    /// is returned instead of the last iterate. This is useful on nonconvex functions, where
+    /// the descent might overshoot and end up worse than an earlier iterate, e.g., after
+    /// reaching `max_iterations`.
+    pub fn return_best(mut self, return_best: bool) -> Self {
+        self.return_best = return_best;
+        self
+    }
+
+    /// Specifies whether each step is checked to not increase the value, panicking with a
+    /// diagnostic otherwise, e.g., to debug a custom line search or a wrong gradient. Note
+    /// that line searches might accept increasing steps by design, e.g., the
+    /// `NonmonotoneLineSearch` or `ArmijoLineSearch` with a minimal step width.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Specifies how the search direction is derived from the gradients, see `Variant`.
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        if let Some(relative_gradient_tolerance) = self.relative_gradient_tolerance {
+            check_positive("relative_gradient_tolerance", relative_gradient_tolerance)?;
+        }
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        check_range("min_step", self.min_step, "[0, ∞)",
+            self.min_step >= 0.0 && self.min_step.is_finite())?;
+        if let Some(position_tolerance) = self.position_tolerance {
+            check_range("position_tolerance", position_tolerance, "(0, ∞)",
+                position_tolerance > 0.0 && position_tolerance.is_finite())?;
+        }
+        if let Some(max_step_norm) = self.max_step_norm {
+            check_positive("max_step_norm", max_step_norm)?;
+        }
+        match self.variant {
+            Variant::Vanilla => Ok(()),
+            Variant::Momentum(beta) | Variant::Nesterov(beta) => {
+                check_range("beta", beta, "[0, 1)", (0.0..1.0).contains(&beta))
+            }
+        }
+    }
+
+    /// Performs the actual minimization like `minimize()`, but of a stateful `function`
+    /// that requires exclusive access for each evaluation.
+    pub fn minimize_mut<F: MutFunction1 + ?Sized>(&self, function: &mut F,
+                                                 initial_position: Vec<f64>) -> Report
+    {
+        self.minimize(&Exclusive(RefCell::new(function)), initial_position)
+    }
+
+    /// Performs the actual minimization like `minimize()`, but restricted to the box given by
+    /// the `bounds`, i.e., the lower and upper bound for each dimension.
+    ///
+    /// The initial position is clamped into the box. In each iteration, the search direction
+    /// is projected such that the line search moves towards `P(x + d)`, where `P` clamps into
+    /// the box, and the accepted position is clamped as well. The optimization stops as soon
+    /// as the projected gradient `x - P(x - ∇f(x))` drops below the gradient tolerance, which
+    /// is satisfied at minima located on the boundary.
+    ///
+    /// Line searches with step widths of at most `1`, e.g., the default `ArmijoLineSearch`,
+    /// never evaluate the function outside of the box.
+    pub fn minimize_with_bounds<F: Function1 + ?Sized>(&self, function: &F,
+                                                       initial_position: Vec<f64>,
+                                                       bounds: &[(f64, f64)]) -> Report
+    {
+        assert_eq!(initial_position.len(), bounds.len(),
+            "number of bounds must match the dimensionality of the position");
+        assert!(bounds.iter().all(|&(lower, upper)| lower <= upper),
+            "lower bounds must not be larger than upper bounds");
+
+        self.minimize_within(function, clamp(&initial_position, bounds), Some(bounds))
+    }
+
+    // performs the minimization, restricted to the box given by the `bounds` if present
+    fn minimize_within<F: Function1 + ?Sized>(&self, function: &F, initial_position: Vec<f64>,
+                                              bounds: Option<&[(f64, f64)]>) -> Report
+    {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
 
-    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
         info!("Starting gradient descent minimization: gradient_tolerance = {:?},
-            max_iterations = {:?}, line_search = {:?}",
-            self.gradient_tolerance, self.max_iterations, self.line_search);
+            max_iterations = {:?}, target_value = {:?}, min_step = {:?}, line_search = {:?}",
+            self.gradient_tolerance, self.max_iterations, self.target_value, self.min_step,
+            self.line_search);
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "GradientDescent",
+            gradient_tolerance = self.gradient_tolerance,
+            relative_gradient_tolerance = ?self.relative_gradient_tolerance,
+            max_iterations = ?self.max_iterations,
+            target_value = ?self.target_value, min_step = self.min_step,
+            position_tolerance = ?self.position_tolerance,
+            max_step_norm = ?self.max_step_norm, line_search = ?self.line_search,
+            variant = ?self.variant).entered();
 
         let mut position = initial_position;
-        let mut value = function.value(&position);
+        let (mut value, mut gradient) = function.value_and_gradient(&position);
 
-        if log_enabled!(Trace) {
+        if trace_enabled!() {
             info!("Starting with y = {:?} for x = {:?}", value, position);
         } else {
             info!("Starting with y = {:?}", value);
         }
 
+        if self.verify_gradient {
+            verify_gradient(function, &position, value, &gradient);
+        }
+
         let mut iteration = 0;
+        let mut small_steps = 0;
+        let mut previous_step_width = None;
+        let mut history = SearchHistory::new();
+        let mut trajectory = if self.record_trajectory { Some(Vec::new()) } else { None };
+        let mut step_widths = if self.record_step_widths { Some(Vec::new()) } else { None };
+        let mut best = if self.return_best { Some((position.clone(), value)) } else { None };
+        let mut previous_position = position.clone();
+        let mut previous_direction = vec![0.0; position.len()];
+
+        // the larger of the absolute and the relative threshold of the gradient components
+        let gradient_tolerance = match self.relative_gradient_tolerance {
+            Some(relative_gradient_tolerance) => {
+                let scale = gradient.iter().fold(0.0, |scale: f64, g| scale.max(g.abs()));
+
+                self.gradient_tolerance.max(relative_gradient_tolerance * scale)
+            },
+            None => self.gradient_tolerance
+        };
+
+        let convergence = loop {
+            if self.target_value.is_some_and(|target_value| value <= target_value) {
+                info!("Reached target value, stopping optimization");
 
-        loop {
-            let gradient = function.gradient(&position);
+                break Convergence::TargetValue;
+            }
+
+            match bounds {
+                Some(bounds) => {
+                    // the projected gradient measures stationarity within the box
+                    let projected: Vec<_> = clamp_step(&position, &gradient, -1.0, bounds)
+                        .iter()
+                        .zip(&position)
+                        .map(|(p, x)| x - p)
+                        .collect();
+
+                    if is_saddle_point(&projected, gradient_tolerance) {
+                        info!("Projected gradient to small, stopping optimization");
 
-            if is_saddle_point(&gradient, self.gradient_tolerance) {
-                info!("Gradient to small, stopping optimization");
+                        break Convergence::GradientTolerance;
+                    }
+                },
+                None => if is_saddle_point(&gradient, gradient_tolerance) {
+                    info!("Gradient to small, stopping optimization");
 
-                return Solution::new(position, value);
+                    break Convergence::GradientTolerance;
+                }
             }
 
-            let direction: Vec<_> = gradient.into_iter().map(|g| -g).collect();
+            // the position the line search starts from, if it differs from the current one
+            let mut extrapolation = None;
+
+            let direction: Vec<_> = match self.variant {
+                Variant::Vanilla => gradient.iter().map(|g| -g).collect(),
+                Variant::Momentum(beta) => {
+                    let direction: Vec<_> = gradient.iter().zip(&previous_direction)
+                        .map(|(g, d)| -g + beta * d)
+                        .collect();
+
+                    if dot(&direction, &gradient) < 0.0 {
+                        direction
+                    } else {
+                        gradient.iter().map(|g| -g).collect()
+                    }
+                },
+                Variant::Nesterov(beta) => {
+                    let mut y: Vec<_> = position.iter().zip(&previous_position)
+                        .map(|(x, p)| x + beta * (x - p))
+                        .collect();
+
+                    if let Some(bounds) = bounds {
+                        y = clamp(&y, bounds);
+                    }
+
+                    let (y_value, y_gradient) = function.value_and_gradient(&y);
+
+                    if y_value <= value && y_gradient.iter().any(|&g| g != 0.0) {
+                        let direction = y_gradient.iter().map(|g| -g).collect();
+                        extrapolation = Some((y, y_value, y_gradient));
+                        direction
+                    } else {
+                        gradient.iter().map(|g| -g).collect()
+                    }
+                }
+            };
+
+            let (origin, origin_value, origin_gradient) = match extrapolation {
+                Some((ref y, y_value, ref y_gradient)) => (y, y_value, y_gradient),
+                None => (&position, value, &gradient)
+            };
+
+            // move towards the projection of the target, falling back to the projected
+            // steepest descent if the projection does not descend anymore
+            let direction = match bounds {
+                Some(bounds) => {
+                    let projected = projected_direction(origin, &direction, bounds);
+
+                    if dot(origin_gradient, &projected) < 0.0 {
+                        projected
+                    } else {
+                        let steepest: Vec<_> = origin_gradient.iter().map(|g| -g).collect();
+                        projected_direction(origin, &steepest, bounds)
+                    }
+                },
+                None => direction
+            };
+
+            // only the trial steps are limited, the direction remains a descent direction
+            let direction = match self.max_step_norm {
+                Some(max_step_norm) => limit_norm(direction, max_step_norm),
+                None => direction
+            };
+
+            let slope = -dot(origin_gradient, &direction);
+
+            // the line search starts with the value and the gradient at the origin
+            let known = Known {
+                function,
+                position: origin,
+                value: origin_value,
+                gradient: origin_gradient
+            };
+
+            let next = match previous_step_width {
+                // the origin is already stationary within the box
+                _ if slope <= 0.0 => Step::new(origin, &direction, 0.0),
+                Some((step_width, previous_slope)) if self.step_width_hint => {
+                    // assume the same first-order change as in the previous iteration
+                    let hint = step_width * previous_slope / slope;
+                    self.line_search.search_in(&mut history, &known, origin, &direction,
+                        Some(hint))
+                },
+                _ => self.line_search.search_in(&mut history, &known, origin, &direction, None)
+            };
 
-            let iter_xs = self.line_search.search(function, &position, &direction);
+            let mut next_position = match bounds {
+                Some(bounds) => clamp(&next.position, bounds),
+                None => next.position
+            };
 
-            position = iter_xs;
-            value = function.value(&position);
+            // line searches might still take longer steps, e.g., using step widths above `1`,
+            // the limited step remains within the box as it is convex
+            if let Some(max_step_norm) = self.max_step_norm {
+                let change: Vec<_> = next_position.iter().zip(&position)
+                    .map(|(a, b)| a - b)
+                    .collect();
+
+                next_position = position.iter().zip(limit_norm(change, max_step_norm))
+                    .map(|(x, s)| x + s)
+                    .collect();
+            }
+
+            let step = next_position.iter().zip(&position)
+                .map(|(a, b)| (a - b).powi(2))
+                .fold(0.0, Add::add)
+                .sqrt();
+
+            let relative_step = step / (dot(&position, &position).sqrt() + f64::EPSILON);
+
+            let step_width = next.step_width;
+
+            if step_width > 0.0 && step_width.is_finite() {
+                previous_step_width = Some((step_width, slope));
+            }
+
+            if let Some(ref mut step_widths) = step_widths {
+                step_widths.push(step_width);
+            }
+
+            if step < self.min_step {
+                small_steps += 1;
+            } else {
+                small_steps = 0;
+            }
+
+            previous_position = match self.variant {
+                Variant::Nesterov(_) => mem::replace(&mut position, next_position),
+                _ => {
+                    position = next_position;
+                    Vec::new()
+                }
+            };
+            previous_direction = direction;
+
+            let (next_value, next_gradient) = function.value_and_gradient(&position);
+
+            let slack = STRICT_TOLERANCE * value.abs().max(1.0);
+
+            if self.strict && (next_value > value + slack || next_value.is_nan()) {
+                panic!("step {} increased the value from {:?} to {:?} using a step width of \
+                    {:?}, check the gradient and the line search", iteration + 1, value,
+                    next_value, step_width);
+            }
+
+            value = next_value;
+            gradient = next_gradient;
 
             iteration += 1;
 
-            if log_enabled!(Trace) {
-                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            if let Some(ref mut trajectory) = trajectory {
+                trajectory.push((position.clone(), value));
+            }
+
+            if let Some((ref mut best_position, ref mut best_value)) = best {
+                if value < *best_value {
+                    best_position.clone_from(&position);
+                    *best_value = value;
+                }
+            }
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}, step_width = {:?}", iteration, value,
+                    position, step_width);
             } else {
-                debug!("Iteration {:6}: y = {:?}", iteration, value);
+                debug!("Iteration {:6}: y = {:?}, step_width = {:?}", iteration, value,
+                    step_width);
             }
 
-            let reached_max_iterations = self.max_iterations.map_or(false,
-                    |max_iterations| iteration == max_iterations);
+            let reached_max_iterations = self.max_iterations == Some(iteration);
 
             if reached_max_iterations {
                 info!("Reached maximal number of iterations, stopping optimization");
 
-                return Solution::new(position, value);
+                break Convergence::MaxIterations;
+            }
+
+            if small_steps == SMALL_STEP_ITERATIONS {
+                info!("Steps to small, stopping optimization");
+
+                break Convergence::SmallStep;
+            }
+
+            if self.position_tolerance.is_some_and(|tolerance| relative_step < tolerance) {
+                info!("Relative step too small, stopping optimization");
+
+                break Convergence::PositionTolerance;
+            }
+        };
+
+        if let Some((best_position, best_value)) = best {
+            if best_value < value {
+                info!("Returning best iterate with y = {:?} instead of y = {:?}", best_value,
+                    value);
+
+                position = best_position;
+                value = best_value;
             }
         }
+
+        let mut report = Report::new(position, value, iteration, convergence);
+        report.trajectory = trajectory;
+        report.step_widths = step_widths;
+        report
+    }
+}
+
+impl<F: Function1 + ?Sized, S: LineSearch> Minimizer<F> for GradientDescent<S>
+{
+    type Solution = Report;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Report {
+        self.minimize_within(function, initial_position, None)
+    }
+}
+
+
+// rescales the vector to the euclidean norm `max_norm` if it is longer
+fn limit_norm(vector: Vec<f64>, max_norm: f64) -> Vec<f64> {
+    let norm = dot(&vector, &vector).sqrt();
+
+    if norm > max_norm {
+        vector.into_iter().map(|v| v * max_norm / norm).collect()
+    } else {
+        vector
+    }
+}
+
+// clamps the position into the box
+fn clamp(position: &[f64], bounds: &[(f64, f64)]) -> Vec<f64> {
+    position.iter().zip(bounds).map(|(x, &(lower, upper))| x.clamp(lower, upper)).collect()
+}
+
+// clamps the position `x + γ d` into the box
+fn clamp_step(position: &[f64], direction: &[f64], step_width: f64, bounds: &[(f64, f64)])
+    -> Vec<f64>
+{
+    position.iter().zip(direction).zip(bounds)
+        .map(|((x, d), &(lower, upper))| (x + step_width * d).clamp(lower, upper))
+        .collect()
+}
+
+// the direction `P(x + d) - x` from the position towards the projection of `x + d`
+fn projected_direction(position: &[f64], direction: &[f64], bounds: &[(f64, f64)]) -> Vec<f64> {
+    clamp_step(position, direction, 1.0, bounds).iter().zip(position).map(|(p, x)| p - x).collect()
+}
+
+
+// grants the line search and the minimizer shared access to a stateful function, which is
+// never evaluated reentrantly
+struct Exclusive<'a, F: ?Sized + 'a>(RefCell<&'a mut F>);
+
+impl<'a, F: MutFunction1 + ?Sized> Function for Exclusive<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.0.borrow_mut().value(position)
+    }
+}
+
+impl<'a, F: MutFunction1 + ?Sized> Function1 for Exclusive<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.0.borrow_mut().gradient(position)
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        self.0.borrow_mut().value_and_gradient(position)
+    }
+}
+
+
+// answers the evaluations at the known `position` without evaluating the function again
+struct Known<'a, F: ?Sized + 'a> {
+    function: &'a F,
+    position: &'a [f64],
+    value: f64,
+    gradient: &'a [f64]
+}
+
+impl<'a, F: Function1 + ?Sized> Function for Known<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        if position == self.position {
+            self.value
+        } else {
+            self.function.value(position)
+        }
+    }
+}
+
+impl<'a, F: Function1 + ?Sized> Function1 for Known<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        if position == self.position {
+            self.gradient.to_vec()
+        } else {
+            self.function.gradient(position)
+        }
+    }
+
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        if position == self.position {
+            (self.value, self.gradient.to_vec())
+        } else {
+            self.function.value_and_gradient(position)
+        }
+    }
+}
+
+
+// panics if the gradient disagrees with its finite difference approximation
+fn verify_gradient<F: Function1 + ?Sized>(function: &F, position: &[f64], value: f64,
+                                         gradient: &[f64]) {
+    let approximation = forward_difference(function, position, value);
+
+    for (i, (a, n)) in gradient.iter().zip(&approximation).enumerate() {
+        let scale = a.abs().max(n.abs()).max(1.0);
+
+        // also rejects NaN
+        let agrees = (a - n).abs() <= GRADIENT_CHECK_TOLERANCE * scale;
+
+        if !agrees {
+            error!("Gradient component {} is {:?}, but its finite difference approximation is \
+                {:?} at x = {:?}", i, a, n, position);
+
+            panic!("gradient disagrees with its finite difference approximation: {:?} vs. {:?}",
+                gradient, approximation);
+        }
     }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use problems::{Sphere, Rosenbrock};
+    use problems::{Problem, Sphere, Rosenbrock};
+    use types::{Function, Function1, Func1, MutFunction, MutFunction1, Minimizer, Convergence,
+        ConfigError};
+    use adapters::{Affine, Counting};
+    use line_search::{LineSearch, Step, FixedStepWidth, ArmijoLineSearch};
 
-    use super::GradientDescent;
+    use super::{GradientDescent, Variant};
 
     test_minimizer!{GradientDescent::new(),
         sphere => Sphere::default(),
         rosenbrock => Rosenbrock::default()}
+
+    // an almost flat but unbounded slope, i.e., the gradient never vanishes
+    struct Slope;
+
+    impl Function for Slope {
+        fn value(&self, position: &[f64]) -> f64 {
+            1.0e-3 * position[0]
+        }
+    }
+
+    impl Function1 for Slope {
+        fn gradient(&self, _position: &[f64]) -> Vec<f64> {
+            vec![1.0e-3]
+        }
+    }
+
+    #[test]
+    fn test_min_step() {
+        let report = GradientDescent::new()
+            .min_step(1.0e-2)
+            .minimize(&Slope, vec![0.0]);
+
+        assert_eq!(report.convergence, Convergence::SmallStep);
+        assert_eq!(report.iterations, 3);
+    }
+
+    #[test]
+    fn test_position_tolerance() {
+        // the gradient vanishes slowly around the very shallow minimum at 2
+        let shallow = Func1(|x: &[f64]| (x[0] - 2.0).powi(6),
+            |x: &[f64]| vec![6.0 * (x[0] - 2.0).powi(5)]);
+
+        let minimizer = GradientDescent::new()
+            .gradient_tolerance(1.0e-12)
+            .max_iterations(Some(10000));
+
+        let plain = minimizer.clone().minimize(&shallow, vec![0.0]);
+        let report = minimizer
+            .position_tolerance(1.0e-4)
+            .minimize(&shallow, vec![0.0]);
+
+        assert_eq!(plain.convergence, Convergence::MaxIterations);
+        assert_eq!(report.convergence, Convergence::PositionTolerance);
+        assert!(report.iterations < 1000, "{} iterations", report.iterations);
+        assert!((report.position[0] - 2.0).abs() < 0.2, "{:?}", report.position);
+    }
+
+    #[test]
+    fn test_max_step_norm() {
+        // the first trial steps of the line search overshoot the minimum by far
+        let steep = Counting::new(Affine::new(Sphere::default(), 1000.0, 0.0));
+        let capped = Counting::new(Affine::new(Sphere::default(), 1000.0, 0.0));
+
+        let minimizer = GradientDescent::new().max_iterations(Some(3));
+
+        minimizer.minimize(&steep, vec![3.0, 4.0]);
+
+        let report = minimizer
+            .max_step_norm(Some(1.0))
+            .record_trajectory(true)
+            .minimize(&capped, vec![3.0, 4.0]);
+
+        assert!(capped.values() < steep.values(), "{} vs. {}", capped.values(), steep.values());
+
+        let mut previous = vec![3.0, 4.0];
+
+        for (position, _) in report.trajectory.unwrap() {
+            let step = position.iter().zip(&previous).map(|(a, b)| (a - b).powi(2)).sum::<f64>();
+
+            assert!(step.sqrt() <= 1.0 + 1.0e-12);
+
+            previous = position;
+        }
+    }
+
+    // Rosenbrock function sharing the residual `y - x²` between the value and the gradient
+    struct FusedRosenbrock;
+
+    impl Function for FusedRosenbrock {
+        fn value(&self, x: &[f64]) -> f64 {
+            self.value_and_gradient(x).0
+        }
+    }
+
+    impl Function1 for FusedRosenbrock {
+        fn gradient(&self, x: &[f64]) -> Vec<f64> {
+            self.value_and_gradient(x).1
+        }
+
+        fn value_and_gradient(&self, x: &[f64]) -> (f64, Vec<f64>) {
+            let residual = x[1] - x[0].powi(2);
+
+            let value = (1.0 - x[0]).powi(2) + 100.0 * residual.powi(2);
+            let gradient = vec![-2.0 + 400.0 * x[0].powi(3) - 400.0 * x[0] * x[1] + 2.0 * x[0],
+                                200.0 * residual];
+
+            (value, gradient)
+        }
+    }
+
+    #[test]
+    fn test_value_and_gradient() {
+        let separate = Counting::new(Rosenbrock::default());
+        let fused = Counting::new(FusedRosenbrock);
+
+        let separate_report = GradientDescent::new().minimize(&separate, vec![-1.5, 2.0]);
+        let fused_report = GradientDescent::new().minimize(&fused, vec![-1.5, 2.0]);
+
+        assert_eq!(separate_report.position, fused_report.position);
+        assert_eq!(separate_report.iterations, fused_report.iterations);
+
+        // each gradient is evaluated along with its value, saving a separate evaluation
+        assert_eq!(fused.gradients(), 0);
+        assert_eq!(fused.values(), separate.values());
+
+        // the line search reuses the value and the gradient at the current position instead
+        // of evaluating them again, thus both are evaluated once per iteration
+        assert_eq!(fused.values_and_gradients(), fused_report.iterations + 1);
+        assert_eq!(separate.gradients() + separate.values_and_gradients(),
+            separate_report.iterations + 1);
+    }
+
+    #[test]
+    fn test_target_value() {
+        let report = GradientDescent::new()
+            .line_search(FixedStepWidth::new(0.1))
+            .target_value(Some(1.0e-6))
+            .record_trajectory(true)
+            .minimize(&Sphere::default(), vec![1.0, -1.0]);
+
+        assert_eq!(report.convergence, Convergence::TargetValue);
+        assert!(report.value <= 1.0e-6);
+
+        let trajectory = report.trajectory.unwrap();
+
+        // the threshold has not been crossed before
+        assert!(trajectory[..trajectory.len() - 1].iter().all(|&(_, value)| value > 1.0e-6));
+    }
+
+    #[test]
+    fn test_record_trajectory() {
+        let report = GradientDescent::new()
+            .record_trajectory(true)
+            .minimize(&Rosenbrock::default(), vec![-1.5, 2.0]);
+
+        let trajectory = report.trajectory.expect("trajectory has not been recorded");
+
+        assert_eq!(trajectory.len() as u64, report.iterations);
+        assert_eq!(trajectory.last(), Some(&(report.position, report.value)));
+
+        let report = GradientDescent::new().minimize(&Rosenbrock::default(), vec![-1.5, 2.0]);
+
+        assert!(report.trajectory.is_none());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_events() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tracing::{Event, Level, Metadata, Subscriber};
+        use tracing::span::{Attributes, Id, Record};
+
+        // counts the created spans as well as the emitted per-iteration events
+        #[derive(Default)]
+        struct Counter {
+            spans: AtomicUsize,
+            iterations: AtomicUsize
+        }
+
+        struct CountingSubscriber(Arc<Counter>);
+
+        impl Subscriber for CountingSubscriber {
+            fn enabled(&self, _metadata: &Metadata) -> bool {
+                true
+            }
+
+            fn new_span(&self, attributes: &Attributes) -> Id {
+                assert_eq!(attributes.metadata().name(), "minimize");
+
+                self.0.spans.fetch_add(1, Ordering::SeqCst);
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _span: &Id, _values: &Record) {}
+
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+            fn event(&self, event: &Event) {
+                if *event.metadata().level() == Level::DEBUG {
+                    self.0.iterations.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+
+            fn enter(&self, _span: &Id) {}
+
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let counter = Arc::new(Counter::default());
+
+        let report = tracing::subscriber::with_default(CountingSubscriber(counter.clone()), || {
+            GradientDescent::new()
+                .max_iterations(Some(10))
+                .minimize(&Rosenbrock::default(), vec![-1.5, 2.0])
+        });
+
+        assert_eq!(report.iterations, 10);
+        assert_eq!(counter.spans.load(Ordering::SeqCst), 1);
+        assert_eq!(counter.iterations.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn test_step_width_hint() {
+        let evaluations = |step_width_hint: bool| {
+            let counting = Counting::new(Rosenbrock::default());
+
+            // a fine-grained backtracking, which is expensive when starting from scratch
+            let report = GradientDescent::new()
+                .line_search(ArmijoLineSearch::new(0.1, 1.0, 0.9, 0.0))
+                .step_width_hint(step_width_hint)
+                .minimize(&counting, vec![-1.2, 1.0]);
+
+            assert_eq!(report.convergence, Convergence::GradientTolerance);
+
+            counting.values() + counting.values_and_gradients()
+        };
+
+        let plain = evaluations(false);
+        let hinted = evaluations(true);
+
+        assert!(hinted < plain, "{} vs. {}", hinted, plain);
+    }
+
+    #[test]
+    fn test_return_best() {
+        // the fixed step width is too large for the well of -cos(x) around the minimum at 0,
+        // thus the descent overshoots and bounces around after the first iteration
+        let cosine = Func1(|x: &[f64]| -x[0].cos(), |x: &[f64]| vec![x[0].sin()]);
+
+        let minimizer = GradientDescent::new()
+            .line_search(FixedStepWidth::new(3.0))
+            .max_iterations(Some(10))
+            .record_trajectory(true);
+
+        let last = minimizer.minimize(&cosine, vec![2.0]);
+        let best = minimizer.return_best(true).minimize(&cosine, vec![2.0]);
+
+        assert!(best.value < last.value, "{} vs. {}", best.value, last.value);
+        assert_eq!(best.iterations, last.iterations);
+
+        let trajectory = last.trajectory.unwrap();
+        let lowest = trajectory.iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+
+        assert_eq!(&(best.position, best.value), lowest);
+    }
+
+    #[test]
+    fn test_record_step_widths() {
+        let report = GradientDescent::new()
+            .line_search(FixedStepWidth::new(0.1))
+            .record_step_widths(true)
+            .minimize(&Sphere::default(), vec![1.0, -1.0]);
+
+        let step_widths = report.step_widths.expect("step widths have not been recorded");
+
+        assert_eq!(step_widths.len() as u64, report.iterations);
+        assert!(step_widths.iter().all(|&step_width| step_width == 0.1));
+
+        let report = GradientDescent::new().minimize(&Sphere::default(), vec![1.0, -1.0]);
+
+        assert!(report.step_widths.is_none());
+    }
+
+    #[test]
+    fn test_verify_gradient() {
+        let report = GradientDescent::new()
+            .verify_gradient(true)
+            .minimize(&Sphere::new(3), vec![1.0, -2.0, 3.0]);
+
+        assert!(report.position.iter().all(|x| x.abs() < 1.0e-3));
+    }
+
+    #[test]
+    #[should_panic(expected = "gradient disagrees")]
+    fn test_verify_wrong_gradient() {
+        // the gradient of x² lacks the factor 2
+        let wrong = Func1(|x: &[f64]| x[0].powi(2), |x: &[f64]| vec![x[0]]);
+
+        GradientDescent::new()
+            .verify_gradient(true)
+            .minimize(&wrong, vec![1.0]);
+    }
+
+    #[test]
+    fn test_minimize_mut() {
+        // counts its evaluations without interior mutability
+        struct Stateful {
+            values: u64,
+            gradients: u64
+        }
+
+        impl MutFunction for Stateful {
+            fn value(&mut self, position: &[f64]) -> f64 {
+                self.values += 1;
+
+                Rosenbrock::default().value(position)
+            }
+        }
+
+        impl MutFunction1 for Stateful {
+            fn gradient(&mut self, position: &[f64]) -> Vec<f64> {
+                self.gradients += 1;
+
+                Rosenbrock::default().gradient(position)
+            }
+        }
+
+        let mut stateful = Stateful { values: 0, gradients: 0 };
+        let counting = Counting::new(Rosenbrock::default());
+
+        let report = GradientDescent::new().minimize_mut(&mut stateful, vec![-1.5, 2.0]);
+        let expected = GradientDescent::new().minimize(&counting, vec![-1.5, 2.0]);
+
+        assert_eq!(report.position, expected.position);
+        assert_eq!(report.iterations, expected.iterations);
+
+        assert_eq!(stateful.values, counting.values() + counting.values_and_gradients());
+        assert_eq!(stateful.gradients, counting.gradients() + counting.values_and_gradients());
+        assert!(stateful.gradients > report.iterations);
+    }
+
+    #[test]
+    fn test_variants() {
+        let variants = [Variant::Vanilla, Variant::Momentum(0.9), Variant::Nesterov(0.9)];
+
+        for &variant in &variants {
+            let report = GradientDescent::new()
+                .variant(variant)
+                .minimize(&Sphere::default(), vec![1.0, -2.0]);
+
+            assert_eq!(report.convergence, Convergence::GradientTolerance, "{:?}", variant);
+            assert!(report.position.iter().all(|x| x.abs() < 1.0e-4), "{:?}", variant);
+        }
+
+        let vanilla = GradientDescent::new().minimize(&Rosenbrock::default(), vec![-1.2, 1.0]);
+
+        for &variant in &variants[1..] {
+            let report = GradientDescent::new()
+                .variant(variant)
+                .minimize(&Rosenbrock::default(), vec![-1.2, 1.0]);
+
+            assert_eq!(report.convergence, Convergence::GradientTolerance, "{:?}", variant);
+            assert!(report.iterations < vanilla.iterations, "{:?} took {} vs. {} iterations",
+                variant, report.iterations, vanilla.iterations);
+        }
+    }
+
+    #[test]
+    fn test_clone() {
+        let configured = GradientDescent::new()
+            .line_search(ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0))
+            .variant(Variant::Nesterov(0.9))
+            .gradient_tolerance(1.0e-8)
+            .record_step_widths(true);
+
+        let clone = configured.clone();
+
+        assert_eq!(format!("{:?}", clone), format!("{:?}", configured));
+
+        let original = configured.minimize(&Sphere::new(3), vec![1.0, -2.0, 0.5]);
+        let cloned = clone.minimize(&Sphere::new(3), vec![1.0, -2.0, 0.5]);
+
+        assert_eq!(cloned.position, original.position);
+        assert_eq!(cloned.iterations, original.iterations);
+        assert_eq!(cloned.step_widths, original.step_widths);
+    }
+
+    #[test]
+    fn test_relative_gradient_tolerance() {
+        // the scaled function takes exactly the same steps due to the power of two
+        let scale = 2.0f64.powi(20);
+        let scaled = Affine::new(Sphere::default(), scale, 0.0);
+
+        let minimize = |function: &dyn Function1, step_width: f64, relative: bool| {
+            let gd = GradientDescent::new().line_search(FixedStepWidth::new(step_width));
+            let gd = if relative { gd.relative_gradient_tolerance(1.0e-3) } else { gd };
+
+            gd.minimize(function, vec![1.0, -2.0])
+        };
+
+        let plain = minimize(&Sphere::default(), 0.1, true);
+        let relative = minimize(&scaled, 0.1 / scale, true);
+
+        assert_eq!(plain.convergence, Convergence::GradientTolerance);
+        assert_eq!(relative.iterations, plain.iterations);
+        assert_eq!(relative.position, plain.position);
+
+        // whereas the absolute tolerance depends on the scale
+        let absolute = minimize(&scaled, 0.1 / scale, false);
+
+        assert!(absolute.iterations > plain.iterations);
+    }
+
+    #[test]
+    fn test_minimize_with_bounds() {
+        // evaluating an illegal position of a problem panics
+        let problem = Sphere::bounded(2, 1.0, 3.0);
+
+        for variant in &[Variant::Vanilla, Variant::Momentum(0.9), Variant::Nesterov(0.9)] {
+            let report = GradientDescent::new()
+                .variant(*variant)
+                .max_iterations(Some(1000))
+                .minimize_with_bounds(&problem, vec![2.9, 1.5], &problem.domain());
+
+            assert_eq!(report.convergence, Convergence::GradientTolerance, "{:?}", variant);
+            assert_eq!(report.position, problem.minimum().0, "{:?}", variant);
+        }
+
+        // an infeasible initial position is clamped into the box first
+        let report = GradientDescent::new()
+            .minimize_with_bounds(&problem, vec![-5.0, 5.0], &problem.domain());
+
+        assert_eq!(report.position, problem.minimum().0);
+
+        // the unconstrained minimum at [1, 1] is cut off by the upper bound of y
+        let report = GradientDescent::new()
+            .max_iterations(Some(100000))
+            .minimize_with_bounds(&Rosenbrock::default(), vec![-1.2, 0.0],
+                &[(-2.0, 2.0), (-2.0, 0.5)]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+        assert!((report.position[1] - 0.5).abs() < 1.0e-6, "{:?}", report.position);
+        assert!(report.position[0] > 0.7 && report.position[0] < 0.71, "{:?}", report.position);
+    }
+
+    // a broken line search stepping into the opposite direction
+    #[derive(Debug)]
+    struct Ascent;
+
+    impl LineSearch for Ascent {
+        fn search<F>(&self, _function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+            where F: Function1 + ?Sized
+        {
+            Step::new(initial_position, direction, -0.1)
+        }
+    }
+
+    #[test]
+    fn test_strict() {
+        let report = GradientDescent::new()
+            .strict(true)
+            .minimize(&Rosenbrock::default(), vec![-1.2, 1.0]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+
+        // without the check, the ascent is not noticed
+        let report = GradientDescent::new()
+            .line_search(Ascent)
+            .max_iterations(Some(5))
+            .minimize(&Sphere::default(), vec![1.0, -2.0]);
+
+        assert!(report.value > 5.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "step 1 increased the value from 5.0 to")]
+    fn test_strict_ascent() {
+        GradientDescent::new()
+            .line_search(Ascent)
+            .strict(true)
+            .minimize(&Sphere::default(), vec![1.0, -2.0]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(GradientDescent::new().validate(), Ok(()));
+        assert_eq!(GradientDescent::new().max_iterations(Some(0)).validate(),
+            Err(ConfigError::OutOfRange { name: "max_iterations", value: 0.0, range: "[1, ∞)" }));
+        assert_eq!(GradientDescent::new().variant(Variant::Nesterov(1.0)).validate(),
+            Err(ConfigError::OutOfRange { name: "beta", value: 1.0, range: "[0, 1)" }));
+    }
+
+    #[test]
+    #[should_panic(expected = "gradient_tolerance = -1 must be in range (0, ∞]")]
+    fn test_invalid_configuration() {
+        GradientDescent::new()
+            .gradient_tolerance(-1.0)
+            .minimize(&Sphere::default(), vec![1.0, -2.0]);
+    }
 }
@@ -0,0 +1,379 @@
+use types::{VectorFunction1, Minimizer, Convergence, Report, ConfigError, check_range,
+    check_positive, check_count};
+use utils::{is_saddle_point, dot, norm};
+
+
+/// A Hessian-free optimizer for nonlinear least-squares problems, i.e., minimizing
+/// `f(x) = ½ ‖r(x)‖²` of a vector-valued residual function `r`.
+///
+/// In each iteration, the step `p` solves the damped Gauss-Newton system
+/// `(JᵀJ + λ I) p = -Jᵀr` using the conjugate gradient method. In contrast to the Hessian,
+/// the Gauss-Newton matrix `JᵀJ` is positive semidefinite, thus each step is a descent
+/// direction. The Jacobian `J` is never formed, only products with it and its transpose are
+/// required, see `VectorFunction1::jacobian_product()` and
+/// `VectorFunction1::jacobian_transpose_product()`, which scales to large problems.
+///
+/// The damping `λ` is adapted in the manner of Levenberg-Marquardt, i.e., it is decreased
+/// if the quadratic model predicts the actual decrease well and increased otherwise
+/// (Martens, 2010). Steps that do not decrease the function are rejected. Once the increasing
+/// damping shrinks the step below the precision of the position, the optimization stops.
+#[derive(Debug, Copy, Clone)]
+pub struct HessianFree {
+    initial_damping: f64,
+    cg_iterations: Option<u64>,
+    gradient_tolerance: f64,
+    max_iterations: Option<u64>
+}
+
+impl HessianFree {
+    /// Creates a new `HessianFree` optimizer using the following defaults:
+    ///
+    /// - **`initial_damping`** = `1.0`
+    /// - **`cg_iterations`** = `None`
+    /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`max_iterations`** = `None`
+    pub fn new() -> HessianFree {
+        HessianFree {
+            initial_damping: 1.0,
+            cg_iterations: None,
+            gradient_tolerance: 1.0e-4,
+            max_iterations: None
+        }
+    }
+
+    /// Adjusts the initial damping `λ` of the Gauss-Newton matrix.
+    pub fn initial_damping(mut self, initial_damping: f64) -> Self {
+        self.initial_damping = initial_damping;
+        self
+    }
+
+    /// Adjusts the number of maximally run conjugate gradient iterations per step. A value
+    /// of `None` uses the number of dimensions.
+    pub fn cg_iterations(mut self, cg_iterations: Option<u64>) -> Self {
+        self.cg_iterations = cg_iterations;
+        self
+    }
+
+    /// Adjusts the gradient tolerance which is used as abort criterion to decide
+    /// whether we reached a plateau.
+    pub fn gradient_tolerance(mut self, gradient_tolerance: f64) -> Self {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_range("initial_damping", self.initial_damping, "(0, ∞)",
+            self.initial_damping > 0.0 && self.initial_damping.is_finite())?;
+        check_count("cg_iterations", self.cg_iterations.unwrap_or(1))?;
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))
+    }
+
+    // solves (JᵀJ + λ I) p = -g using the conjugate gradient method, stopping as soon as the
+    // residual drops below min(0.5, √‖g‖) ‖g‖
+    fn solve<F>(&self, function: &F, position: &[f64], gradient: &[f64], damping: f64)
+        -> Vec<f64>
+        where F: VectorFunction1 + ?Sized
+    {
+        let tolerance = norm(gradient).sqrt().min(0.5) * norm(gradient);
+        let cg_iterations = self.cg_iterations.unwrap_or(position.len() as u64);
+
+        let mut step = vec![0.0; position.len()];
+        let mut residual = gradient.to_vec();
+        let mut direction: Vec<_> = gradient.iter().map(|g| -g).collect();
+
+        for _ in 0..cg_iterations {
+            let product = gauss_newton_product(function, position, &direction, damping);
+            let curvature = dot(&direction, &product);
+
+            if curvature <= 0.0 {
+                break;
+            }
+
+            let alpha = dot(&residual, &residual) / curvature;
+
+            for (p, d) in step.iter_mut().zip(&direction) {
+                *p += alpha * d;
+            }
+
+            let next_residual: Vec<_> = residual.iter().zip(&product)
+                .map(|(r, gd)| r + alpha * gd)
+                .collect();
+
+            if norm(&next_residual) <= tolerance {
+                break;
+            }
+
+            let beta = dot(&next_residual, &next_residual) / dot(&residual, &residual);
+
+            for (d, r) in direction.iter_mut().zip(&next_residual) {
+                *d = -r + beta * *d;
+            }
+
+            residual = next_residual;
+        }
+
+        step
+    }
+}
+
+impl Default for HessianFree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: VectorFunction1 + ?Sized> Minimizer<F> for HessianFree {
+    type Solution = Report;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Report {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "HessianFree",
+            initial_damping = self.initial_damping, cg_iterations = ?self.cg_iterations,
+            gradient_tolerance = self.gradient_tolerance,
+            max_iterations = ?self.max_iterations).entered();
+
+        let mut position = initial_position;
+        let mut residuals = function.value(&position);
+        let mut value = 0.5 * dot(&residuals, &residuals);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut damping = self.initial_damping;
+
+        let convergence = loop {
+            let gradient = function.jacobian_transpose_product(&position, &residuals);
+
+            if is_saddle_point(&gradient, self.gradient_tolerance) {
+                info!("Gradient to small, stopping optimization");
+
+                break Convergence::GradientTolerance;
+            }
+
+            let step = self.solve(function, &position, &gradient, damping);
+
+            // the decrease predicted by the undamped Gauss-Newton model gᵀp + ½ ‖Jp‖²
+            let product = function.jacobian_product(&position, &step);
+            let predicted = -(dot(&gradient, &step) + 0.5 * dot(&product, &product));
+
+            let candidate: Vec<_> = position.iter().zip(&step).map(|(x, p)| x + p).collect();
+
+            // the damping grew so large that the step does not change the position anymore
+            if candidate == position {
+                info!("Step too small, stopping optimization");
+
+                break Convergence::SmallStep;
+            }
+
+            let candidate_residuals = function.value(&candidate);
+            let candidate_value = 0.5 * dot(&candidate_residuals, &candidate_residuals);
+
+            let ratio = if predicted > 0.0 {
+                (value - candidate_value) / predicted
+            } else {
+                -1.0
+            };
+
+            if ratio < 0.25 {
+                damping *= 1.5;
+            } else if ratio > 0.75 {
+                damping *= 2.0 / 3.0;
+            }
+
+            if ratio > 0.0 {
+                position = candidate;
+                residuals = candidate_residuals;
+                value = candidate_value;
+            } else {
+                debug!("Rejected step with ratio = {:?}, increasing damping to {:?}", ratio,
+                    damping);
+            }
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}, damping = {:?}", iteration, value,
+                    position, damping);
+            } else {
+                debug!("Iteration {:6}: y = {:?}, damping = {:?}", iteration, value, damping);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        Report::new(position, value, iteration, convergence)
+    }
+}
+
+
+// computes (JᵀJ + λ I) v without forming J
+fn gauss_newton_product<F>(function: &F, position: &[f64], direction: &[f64], damping: f64)
+    -> Vec<f64>
+    where F: VectorFunction1 + ?Sized
+{
+    let product = function.jacobian_product(position, direction);
+
+    function.jacobian_transpose_product(position, &product).into_iter().zip(direction)
+        .map(|(p, v)| p + damping * v)
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use types::{VectorFunction, VectorFunction1, Minimizer, Convergence, ConfigError};
+
+    use super::HessianFree;
+
+    // residuals of fitting y = a exp(-b t) + c to observations (t, y), providing the
+    // products with the Jacobian without forming it
+    struct ExponentialDecay {
+        observations: Vec<(f64, f64)>,
+        jacobians: Cell<u64>
+    }
+
+    impl ExponentialDecay {
+        fn new(a: f64, b: f64, c: f64) -> ExponentialDecay {
+            let observations = (0..50).map(|i| {
+                let t = i as f64 / 10.0;
+
+                (t, a * (-b * t).exp() + c)
+            }).collect();
+
+            ExponentialDecay {
+                observations,
+                jacobians: Cell::new(0)
+            }
+        }
+
+        // the partial derivatives of the residual of the observation at t
+        fn derivatives(w: &[f64], t: f64) -> [f64; 3] {
+            let e = (-w[1] * t).exp();
+
+            [e, -w[0] * t * e, 1.0]
+        }
+    }
+
+    impl VectorFunction for ExponentialDecay {
+        fn value(&self, w: &[f64]) -> Vec<f64> {
+            self.observations.iter().map(|&(t, y)| w[0] * (-w[1] * t).exp() + w[2] - y).collect()
+        }
+    }
+
+    impl VectorFunction1 for ExponentialDecay {
+        fn jacobian(&self, w: &[f64]) -> Vec<f64> {
+            self.jacobians.set(self.jacobians.get() + 1);
+
+            self.observations.iter().flat_map(|&(t, _)| ExponentialDecay::derivatives(w, t))
+                .collect()
+        }
+
+        fn jacobian_product(&self, w: &[f64], v: &[f64]) -> Vec<f64> {
+            self.observations.iter().map(|&(t, _)| {
+                ExponentialDecay::derivatives(w, t).iter().zip(v).map(|(d, v)| d * v).sum()
+            }).collect()
+        }
+
+        fn jacobian_transpose_product(&self, w: &[f64], u: &[f64]) -> Vec<f64> {
+            let mut product = vec![0.0; 3];
+
+            for (&(t, _), u) in self.observations.iter().zip(u) {
+                for (p, d) in product.iter_mut().zip(&ExponentialDecay::derivatives(w, t)) {
+                    *p += d * u;
+                }
+            }
+
+            product
+        }
+    }
+
+    #[test]
+    fn test_exponential_decay() {
+        let problem = ExponentialDecay::new(2.5, 1.3, 0.5);
+
+        let report = HessianFree::new()
+            .gradient_tolerance(1.0e-10)
+            .minimize(&problem, vec![1.0, 0.5, 0.0]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+        assert!(report.value < 1.0e-15, "{} is not close to 0", report.value);
+
+        for (w, expected) in report.position.iter().zip(&[2.5, 1.3, 0.5]) {
+            assert!((w - expected).abs() < 1.0e-6, "{:?} is not the minimum", report.position);
+        }
+
+        // only products with the Jacobian have been used
+        assert_eq!(problem.jacobians.get(), 0);
+    }
+
+    #[test]
+    fn test_truncated_cg() {
+        let problem = ExponentialDecay::new(2.5, 1.3, 0.5);
+
+        // a single conjugate gradient iteration amounts to a scaled gradient step, which
+        // still converges thanks to the adaptive damping, albeit slower
+        let exact = HessianFree::new()
+            .minimize(&problem, vec![1.0, 0.5, 0.0]);
+        let truncated = HessianFree::new()
+            .cg_iterations(Some(1))
+            .max_iterations(Some(10000))
+            .minimize(&problem, vec![1.0, 0.5, 0.0]);
+
+        assert_eq!(truncated.convergence, Convergence::GradientTolerance);
+        assert!(exact.iterations < truncated.iterations, "{} vs. {}", exact.iterations,
+            truncated.iterations);
+    }
+
+    #[test]
+    fn test_small_step() {
+        let mut problem = ExponentialDecay::new(2.5, 1.3, 0.5);
+
+        // noisy observations leave residuals at the minimum
+        for (i, observation) in problem.observations.iter_mut().enumerate() {
+            observation.1 += 1.0e-2 * (i as f64).sin();
+        }
+
+        let minimum = HessianFree::new()
+            .minimize(&problem, vec![1.0, 0.5, 0.0]);
+
+        // the gradient cannot drop below the tolerance due to rounding errors, such that all
+        // further steps are rejected
+        let report = HessianFree::new()
+            .gradient_tolerance(1.0e-300)
+            .minimize(&problem, vec![1.0, 0.5, 0.0]);
+
+        assert_eq!(report.convergence, Convergence::SmallStep);
+        assert!(report.value <= minimum.value, "{} vs. {}", report.value, minimum.value);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(HessianFree::new().validate(), Ok(()));
+        assert_eq!(HessianFree::new().cg_iterations(Some(0)).validate(),
+            Err(ConfigError::OutOfRange { name: "cg_iterations", value: 0.0, range: "[1, ∞)" }));
+    }
+}
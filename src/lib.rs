@@ -6,47 +6,211 @@
 //!
 //! - **`Function`** - Specifies a function that can be minimized
 //! - **`Function1`** - Extends a `Function` by its first derivative
+//! - **`Function2`** - Extends a `Function1` by its second derivative, the Hessian
+//! - **`MutFunction`** / **`MutFunction1`** - Analogous to `Function` and `Function1` but for
+//!   functions mutating internal state during evaluation
+//! - **`VectorFunction`** - Specifies a vector-valued function, e.g., as part of a composition
+//! - **`VectorFunction1`** - Extends a `VectorFunction` by its Jacobian and products with it
 //! - **`Summation`** - Represents a summation of functions, exploited, e.g., by SGD
 //! - **`Summation1`** - Analogous to `Function` and `Function1` but for `Summation`
+//! - **`Summation2`** - Analogous to `Function2` but for `Summation`, e.g., for stochastic
+//!   Newton methods
+//! - **`SummationAccuracy`** - Specifies how the terms of a `Summation` are summed up
 //! - **`Minimizer`** - A minimization algorithm
 //! - **`Evaluation`** - A function evaluation `f(x) = y` that is returned by a `Minimizer`
+//! - **`MinByValue`** - Selects the best `Evaluation` of an iterator, also available as `best()`
+//! - **`ConfigError`** - An invalid configuration reported by the fallible constructors, e.g.,
+//!   `ArmijoLineSearch::try_new()`, and the `validate()` methods of the minimizers
 //! - **`Func`** - A new-type wrapper for the `Function` trait
+//! - **`Func1`** - A new-type wrapper for the `Function1` trait
 //! - **`NumericalDifferentiation`** - Provides numerical differentiation for arbitrary `Function`s
+//! - **`PartialAnalytic`** - Completes a partially known gradient by numerical differentiation
+//! - **`Spsa`** - Estimates the gradient of a `Function` stochastically using two evaluations
+//!   per estimate (SPSA)
+//! - **`AutoDiff`** - Provides exact derivatives using automatic differentiation on `Dual`
+//!   numbers, requires the `autodiff` feature
+//! - **`Realified`** - Minimizes a `ComplexFunction` of complex parameters given its Wirtinger
+//!   gradient, requires the `complex` feature
+//! - **`NumericalJacobian`** - Provides the numerical Jacobian of arbitrary vector-valued functions
+//! - **`Affine`** - Scales and offsets the value of a `Function`
+//! - **`Sum`** - The sum of two `Function`s, created by simply adding them
+//! - **`Concat`** - The concatenation of the terms of two `Summation`s
+//! - **`ParallelTerms`** - Evaluates the term gradients of a mini batch concurrently, requires
+//!   the `rayon` feature
+//! - **`Compose`** - The composition of a `Function` and a `VectorFunction`
+//! - **`Standardized`** - Standardizes the input of a `Function` per coordinate
+//! - **`Counting`** - Counts the evaluations of a `Function`
+//! - **`Memoized`** - Caches the recent values of a `Function`
+//! - **`Tracking`** - Tracks the best evaluation of a `Function`
+//! - **`Negated`** - Negates a `Function` and its derivatives, e.g., to maximize it
 //!
 //! # Algorithms
 //!
 //! Currently, the following algorithms are implemented. This list is not final and being
 //! expanded over time.
 //!
-//! - **`GradientDescent`** - Iterative gradient descent minimization, supporting various line
-//!   search methods:
+//! - **`GradientDescent`** - Iterative gradient descent minimization, optionally using
+//!   (Nesterov) momentum, see `Variant`, and supporting various line search methods:
 //!    - *`FixedStepWidth`* - No line search is performed, but a fixed step width is used
+//!    - *`DiminishingStep`* - No line search is performed, but a diminishing step width is used
 //!    - *`ExactLineSearch`* - Exhaustive line search over a set of step widths
 //!    - *`ArmijoLineSearch`* - Backtracking line search using the Armijo rule as stopping
-//!      criterion
+//!      criterion, optionally restricted to a box, see *`BoundedArmijoLineSearch`*
+//!    - *`NonmonotoneLineSearch`* - Backtracking line search using the Armijo rule relative
+//!      to the maximal value of the recent iterations
+//! - **`SubgradientDescent`** - Iterative subgradient descent minimization of non-smooth
+//!   convex functions using a diminishing step width
+//! - **`CoordinateDescent`** - Iterative minimization along a single coordinate at a time,
+//!   selected either cyclic, random or by the Gauss-Southwell rule
+//! - **`TrustRegion`** - Iterative minimization of a quadratic model within a region of
+//!   adaptive size, requiring Hessian-vector products only:
+//!    - *`SteihaugCG`* - Truncated conjugate gradient method solving the subproblem
+//! - **`HessianFree`** - Minimization of nonlinear least-squares problems solving the damped
+//!   Gauss-Newton system using conjugate gradients and Jacobian-vector products only
+//! - **`SR1`** - Quasi-Newton minimization using the symmetric rank-one update of the inverse
+//!   Hessian approximation
+//! - **`SpectralProjectedGradient`** - Projected gradient minimization within a box using
+//!   spectral step widths and a nonmonotone line search
 //! - **`StochasticGradientDescent`** - Iterative stochastic gradient descenent minimazation,
-//!   currently using a fixed step width
+//!   using either a fixed step width or a schedule:
+//!    - *`CosineAnnealing`* - Cosine annealing of the step width with warm restarts (SGDR)
+//! - **`LineSearchStochasticGradientDescent`** - Variant of `StochasticGradientDescent`
+//!   choosing each step width by a line search on the current mini batch
+//! - **`ParallelStochasticGradientDescent`** - Lock-free multithreaded variant of
+//!   `StochasticGradientDescent` (Hogwild), requires the `rayon` feature
+//! - **`Svrg`** - Stochastic gradient descent reducing the variance of the steps using the full
+//!   gradient at periodic snapshots
+//! - **`Adadelta`** - Stochastic gradient descent adapting the step width per coordinate
+//!   without requiring a step width at all
+//! - **`Adam`** - Stochastic gradient descent using adaptive estimates of the first and second
+//!   moments, optionally applying Nesterov momentum (Nadam, see `Adam::nadam()`) or AMSGrad
+//! - **`NaturalGradientDescent`** - Stochastic gradient descent preconditioned by the inverse
+//!   curvature of the terms, e.g., the Fisher information, requiring a `Summation2`
+//!
+//! Minimizers can be combined as well:
+//!
+//! - **`Then`** - Runs a minimizer and refines its solution using a second one
+//! - **`Reparameterized`** - Runs a minimizer in a transformed search space, e.g., in log
+//!   space to enforce positivity
+//! - **`RandomRestart`** - Restarts a minimizer from random positions of a problem until a
+//!   time budget elapses, returning the best solution
+//! - **`ParallelRandomRestart`** - Concurrent variant of `RandomRestart`, requires the `rayon`
+//!   feature
+//! - **`WithEvalBudget`** - Aborts a minimizer once a budget of function evaluations is
+//!   exhausted, returning the best evaluation so far
+//! - **`WithObserver`** - Notifies an `Observer` about each iteration of a minimizer, e.g., to
+//!   collect a record of the progress independently of the logging backend
+//!
+//! # Logging
+//!
+//! The progress of each minimization is logged using the `log` crate. Enabling the `tracing`
+//! feature switches over to the `tracing` crate, which additionally wraps each minimization
+//! in a span carrying the name of the algorithm and its hyperparameters.
 
 
+#[cfg(not(feature = "tracing"))]
 #[macro_use]
 extern crate log;
+#[cfg(feature = "tracing")]
+#[macro_use]
+extern crate tracing;
 extern crate rand;
+extern crate rand_distr;
 extern crate rand_pcg;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+
+/// Tests whether trace logging is enabled, regardless of the used logging backend.
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_enabled {
+    () => { log_enabled!(::log::Level::Trace) };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_enabled {
+    () => { enabled!(::tracing::Level::TRACE) };
+}
+
+/// Implements `Add` for the listed function types along with their generic parameters, such
+/// that adding any `Function` yields the `Sum` of both, e.g., `Func(f) + Sphere::default()`.
+macro_rules! impl_add {
+    ( $( [$($generics:tt)*] $name:ty ),* $(,)? ) => {
+        $(
+            impl<$($generics)* H: ::types::Function> ::std::ops::Add<H> for $name {
+                type Output = ::adapters::Sum<$name, H>;
+
+                fn add(self, other: H) -> Self::Output {
+                    ::adapters::Sum::new(self, other)
+                }
+            }
+        )*
+    };
+}
 
 
 #[macro_use]
 pub mod problems;
 
+pub mod utils;
+pub mod benchmark;
+pub mod losses;
+pub mod scalar;
+
 mod types;
-mod utils;
 mod numeric;
+#[cfg(feature = "autodiff")]
+mod autodiff;
+#[cfg(feature = "complex")]
+mod complex;
+mod adapters;
 mod line_search;
 mod gd;
+mod subgradient;
+mod cd;
+mod trust_region;
+mod hessian_free;
+mod sr1;
+mod spg;
 mod sgd;
+mod svrg;
+mod ngd;
+mod adadelta;
+mod adam;
+mod combinators;
 
 
-pub use types::{Function, Function1, Func, Minimizer, Evaluation, Summation, Summation1};
-pub use numeric::NumericalDifferentiation;
-pub use line_search::{LineSearch, FixedStepWidth, ExactLineSearch, ArmijoLineSearch};
-pub use gd::GradientDescent;
-pub use sgd::StochasticGradientDescent;
+pub use types::{Function, Function1, Function2, Func, Func1, MutFunction, MutFunction1,
+    HessianProduct, Minimizer, Evaluation, MinByValue, best, Summation, Summation1, Summation2,
+    SummationAccuracy, VectorFunction, VectorFunction1, Solution, Convergence, Report,
+    SgdReport, TrustRegionReport, ConfigError};
+pub use numeric::{NumericalDifferentiation, PartialAnalytic, Spsa, NumericalJacobian, Stencil};
+#[cfg(feature = "autodiff")]
+pub use autodiff::{AutoDiff, Dual};
+#[cfg(feature = "complex")]
+pub use complex::{Complex, ComplexFunction, Realified};
+pub use adapters::{Affine, Sum, Concat, Compose, Standardized, NullSpaceProjected, Counting,
+    Memoized, Tracking, Negated, Noisy};
+#[cfg(feature = "rayon")]
+pub use adapters::ParallelTerms;
+pub use line_search::{LineSearch, Step, SearchHistory, FixedStepWidth, DiminishingStep,
+    ExactLineSearch, ArmijoLineSearch, BoundedArmijoLineSearch, NonDescent,
+    NonmonotoneLineSearch};
+pub use gd::{GradientDescent, Variant};
+pub use subgradient::SubgradientDescent;
+pub use cd::{CoordinateDescent, CoordinateRule};
+pub use trust_region::{TrustRegion, StepSolver, SteihaugCG};
+pub use hessian_free::HessianFree;
+pub use sr1::SR1;
+pub use spg::SpectralProjectedGradient;
+pub use sgd::{StochasticGradientDescent, CosineAnnealing, LineSearchStochasticGradientDescent};
+#[cfg(feature = "rayon")]
+pub use sgd::ParallelStochasticGradientDescent;
+pub use svrg::Svrg;
+pub use ngd::NaturalGradientDescent;
+pub use adadelta::Adadelta;
+pub use adam::Adam;
+pub use combinators::{Then, Reparameterized, Transformed, RandomRestart, WithEvalBudget,
+    EvalBudgeted, Observer, WithObserver, Observed};
+#[cfg(feature = "rayon")]
+pub use combinators::ParallelRandomRestart;
@@ -1,15 +1,93 @@
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::ops::Add;
 
-use types::{Function, Function1};
+use types::{Function, Function1, ConfigError, check_range, check_step_width};
+use scalar::minimize_1d;
+
+
+/// The result of a line search, i.e., the new position `x + γ d` along with the chosen step
+/// width `γ`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    /// The new position.
+    pub position: Vec<f64>,
+    /// The chosen step width `γ`, which is `0.0` if the position has not been changed.
+    pub step_width: f64
+}
+
+impl Step {
+    /// Creates a new `Step` by going the `step_width` along the `direction`.
+    pub fn new(initial_position: &[f64], direction: &[f64], step_width: f64) -> Step {
+        let position = initial_position.iter().zip(direction)
+            .map(|(x, d)| x + step_width * d)
+            .collect();
+
+        Step {
+            position,
+            step_width
+        }
+    }
+}
+
+
+/// The history of the line searches within a single minimization, which stateful line
+/// searches like `DiminishingStep` or `NonmonotoneLineSearch` continue, see
+/// `LineSearch::search_in()`.
+///
+/// Minimizers create a new history for each minimization, such that consecutive minimizations
+/// do not influence each other.
+#[derive(Debug, Clone, Default)]
+pub struct SearchHistory {
+    searches: u64,
+    values: VecDeque<f64>
+}
+
+impl SearchHistory {
+    /// Creates a new `SearchHistory` of a minimization without any searches so far.
+    pub fn new() -> SearchHistory {
+        SearchHistory::default()
+    }
+}
 
 
 /// Define a line search method, i.e., choosing an appropriate step width.
 pub trait LineSearch: Debug {
     /// Performs the actual line search given the current `position` `x` and a `direction` to go to.
-    /// Returns the new position.
-    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Vec<f64>
-        where F: Function1;
+    /// Returns the new position along with the chosen step width.
+    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function1 + ?Sized;
+
+    /// Performs the actual line search like `search()`, but given a `hint` for the initial
+    /// step width, e.g., the step width accepted in the previous iteration. Returns the new
+    /// position along with the chosen step width.
+    ///
+    /// By default, the hint is ignored.
+    fn search_with_hint<F>(&self, function: &F, initial_position: &[f64], direction: &[f64],
+                           hint: f64) -> Step
+        where F: Function1 + ?Sized
+    {
+        let _ = hint;
+
+        self.search(function, initial_position, direction)
+    }
+
+    /// Performs the actual line search like `search()`, or like `search_with_hint()` if a
+    /// `hint` is given, continuing the `history` of the previous searches of the current
+    /// minimization. Returns the new position along with the chosen step width.
+    ///
+    /// By default, the history is ignored.
+    fn search_in<F>(&self, history: &mut SearchHistory, function: &F, initial_position: &[f64],
+                    direction: &[f64], hint: Option<f64>) -> Step
+        where F: Function1 + ?Sized
+    {
+        let _ = history;
+
+        match hint {
+            Some(hint) => self.search_with_hint(function, initial_position, direction, hint),
+            None => self.search(function, initial_position, direction)
+        }
+    }
 }
 
 
@@ -22,33 +100,102 @@ pub struct FixedStepWidth {
 impl FixedStepWidth {
     /// Creates a new `FixedStepWidth` given the static step width.
     pub fn new(fixed_step_width: f64) -> FixedStepWidth {
-        assert!(fixed_step_width > 0.0 && fixed_step_width.is_finite(),
-            "fixed_step_width must be greater than 0 and finite");
+        FixedStepWidth::try_new(fixed_step_width).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new `FixedStepWidth` like `new()`, but returns an error instead of
+    /// panicking if the step width is invalid.
+    pub fn try_new(fixed_step_width: f64) -> Result<FixedStepWidth, ConfigError> {
+        check_step_width("fixed_step_width", fixed_step_width)?;
 
-        FixedStepWidth {
+        Ok(FixedStepWidth {
             fixed_step_width
-        }
+        })
     }
 }
 
 impl LineSearch for FixedStepWidth {
-    fn search<F>(&self, _function: &F, initial_position: &[f64], direction: &[f64]) -> Vec<f64>
-        where F: Function
+    fn search<F>(&self, _function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function + ?Sized
+    {
+        Step::new(initial_position, direction, self.fixed_step_width)
+    }
+}
+
+
+/// Uses a diminishing step width `γₖ = γ₀ / kᵖ` in the `k`-th search instead of performing an
+/// actual line search, e.g., `γₖ = γ₀ / √k` for `p = 0.5`.
+///
+/// In contrast to `ArmijoLineSearch`, the objective function is never evaluated and the
+/// known convergence rates of Gradient Descent on convex functions apply. However, the
+/// step widths are not adapted to the function at all, so the convergence is usually much
+/// slower and a too large `γ₀` might even lead to divergence in the first iterations.
+///
+/// The searches are counted per minimization by its `SearchHistory`, thus a single `search()`
+/// always uses the step width `γ₀`.
+#[derive(Debug, Copy, Clone)]
+pub struct DiminishingStep {
+    initial_step_width: f64,
+    exponent: f64
+}
+
+impl DiminishingStep {
+    /// Creates a new `DiminishingStep` given the `initial_step_width` `γ₀` > 0 and the
+    /// schedule `exponent` `p` ∈ (0, 1].
+    pub fn new(initial_step_width: f64, exponent: f64) -> DiminishingStep {
+        DiminishingStep::try_new(initial_step_width, exponent)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new `DiminishingStep` like `new()`, but returns an error instead of
+    /// panicking if a parameter is invalid.
+    pub fn try_new(initial_step_width: f64, exponent: f64)
+        -> Result<DiminishingStep, ConfigError>
+    {
+        check_step_width("initial_step_width", initial_step_width)?;
+        check_range("exponent", exponent, "(0, 1]", exponent > 0.0 && exponent <= 1.0)?;
+
+        Ok(DiminishingStep {
+            initial_step_width,
+            exponent
+        })
+    }
+}
+
+impl LineSearch for DiminishingStep {
+    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function1 + ?Sized
     {
-        initial_position.iter().cloned().zip(direction).map(|(x, d)| {
-            x + self.fixed_step_width * d
-        }).collect()
+        self.search_in(&mut SearchHistory::new(), function, initial_position, direction, None)
+    }
+
+    /// Uses the step width `γₖ` of the `k`-th search of the `history`, ignoring the `hint`.
+    fn search_in<F>(&self, history: &mut SearchHistory, _function: &F, initial_position: &[f64],
+                    direction: &[f64], _hint: Option<f64>) -> Step
+        where F: Function1 + ?Sized
+    {
+        history.searches += 1;
+
+        let k = history.searches as f64;
+        let step_width = self.initial_step_width / k.powf(self.exponent);
+
+        Step::new(initial_position, direction, step_width)
     }
 }
 
 
 /// Brute-force line search minimizing the objective function over a set of
 /// step width candidates, also known as exact line search.
+///
+/// Since the candidates form a geometric grid, a large `increase_factor` might miss the
+/// minimum along the direction by far. Optionally, the best candidate is refined within the
+/// bracket of its neighbouring candidates, see `refine()`.
 #[derive(Debug, Copy, Clone)]
 pub struct ExactLineSearch {
     start_step_width: f64,
     stop_step_width: f64,
-    increase_factor: f64
+    increase_factor: f64,
+    refinement: Option<f64>
 }
 
 impl ExactLineSearch {
@@ -59,41 +206,69 @@ impl ExactLineSearch {
     pub fn new(start_step_width: f64, stop_step_width: f64, increase_factor: f64) ->
         ExactLineSearch
     {
-        assert!(start_step_width > 0.0 && start_step_width.is_finite(),
-            "start_step_width must be greater than 0 and finite");
-        assert!(stop_step_width > start_step_width && stop_step_width.is_finite(),
-            "stop_step_width must be greater than start_step_width");
-        assert!(increase_factor > 1.0 && increase_factor.is_finite(),
-            "increase_factor must be greater than 1 and finite");
+        ExactLineSearch::try_new(start_step_width, stop_step_width, increase_factor)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new `ExactLineSearch` like `new()`, but returns an error instead of
+    /// panicking if a parameter is invalid.
+    pub fn try_new(start_step_width: f64, stop_step_width: f64, increase_factor: f64)
+        -> Result<ExactLineSearch, ConfigError>
+    {
+        check_step_width("start_step_width", start_step_width)?;
+        check_step_width("stop_step_width", stop_step_width)?;
+        check_range("stop_step_width", stop_step_width, "(start_step_width, ∞)",
+            stop_step_width > start_step_width)?;
+        check_range("increase_factor", increase_factor, "(1, ∞)",
+            increase_factor > 1.0 && increase_factor.is_finite())?;
 
-        ExactLineSearch {
+        Ok(ExactLineSearch {
             start_step_width,
             stop_step_width,
-            increase_factor
-        }
+            increase_factor,
+            refinement: None
+        })
+    }
+
+    /// Refines the best step width candidate using the golden section search, see
+    /// `scalar::minimize_1d()`, within the bracket given by the preceding candidate, or `0`,
+    /// and the succeeding one, until the bracket is narrower than the relative `tolerance`
+    /// times the best candidate. If the value decreases up to the last candidate, there is
+    /// no such bracket and the best candidate is returned as is.
+    pub fn refine(mut self, tolerance: f64) -> Self {
+        assert!(tolerance > 0.0 && tolerance.is_finite(), "tolerance must be > 0 and finite");
+
+        self.refinement = Some(tolerance);
+        self
     }
 }
 
 impl LineSearch for ExactLineSearch {
-    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Vec<f64>
-        where F: Function1
+    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function1 + ?Sized
     {
-        let mut min_position = initial_position.to_vec();
+        let mut min_step = Step::new(initial_position, direction, 0.0);
         let mut min_value = function.value(initial_position);
 
+        // the candidates preceding and succeeding the best one
+        let mut bracket = (0.0, None);
+        let mut previous_step_width = 0.0;
+
         let mut step_width = self.start_step_width;
 
         loop {
-            let position: Vec<_> = initial_position.iter().cloned().zip(direction).map(|(x, d)| {
-                x + step_width * d
-            }).collect();
-            let value = function.value(&position);
+            let step = Step::new(initial_position, direction, step_width);
+            let value = function.value(&step.position);
 
             if value < min_value {
-                min_position = position;
+                min_step = step;
                 min_value = value;
+                bracket = (previous_step_width, None);
+            } else if min_step.step_width > 0.0 && min_step.step_width == previous_step_width {
+                bracket.1 = Some(step_width);
             }
 
+            previous_step_width = step_width;
             step_width *= self.increase_factor;
 
             if step_width >= self.stop_step_width {
@@ -101,66 +276,643 @@ impl LineSearch for ExactLineSearch {
             }
         }
 
-        min_position
+        match (self.refinement, bracket) {
+            (Some(tolerance), (lower, Some(upper))) => {
+                let value = |t: f64| function.value(&Step::new(initial_position, direction, t)
+                    .position);
+
+                let (step_width, _) = minimize_1d(value, (lower, min_step.step_width, upper),
+                    tolerance * min_step.step_width);
+
+                Step::new(initial_position, direction, step_width)
+            },
+            _ => min_step
+        }
     }
 }
 
 
+/// Specifies how `ArmijoLineSearch` handles a direction `d` that is not a descent direction,
+/// i.e., `∇f(x)ᵀd ≥ 0`, which usually indicates a flawed direction, e.g., of an indefinite
+/// quasi-Newton approximation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NonDescent {
+    /// Panics with a message stating the slope along the direction.
+    Panic,
+    /// Searches along the steepest descent direction `-∇f(x)` instead, where the returned
+    /// step width refers to that direction. At a stationary point, the position is retained.
+    SteepestDescent
+}
+
+
 /// Backtracking line search evaluating the Armijo rule at each step width.
+///
+/// The backtracking stops at the minimal step width, e.g., to prevent a freeze on noisy
+/// functions, in which case the last trial position is returned even though it does not
+/// satisfy the Armijo rule.
+///
+/// Optionally, the line search is restricted to a box, e.g., the domain of a `Problem`, such
+/// that only feasible positions are evaluated, see `with_bounds()`.
 #[derive(Debug, Copy, Clone)]
 pub struct ArmijoLineSearch {
     control_parameter: f64,
     initial_step_width: f64,
-    decay_factor: f64
+    decay_factor: f64,
+    min_step_width: f64,
+    non_descent: NonDescent
 }
 
 impl ArmijoLineSearch {
     /// Creates a new `ArmijoLineSearch` given the `control_parameter` ∈ (0, 1), the
-    /// `initial_step_width` > 0 and the `decay_factor` ∈ (0, 1).
+    /// `initial_step_width` > 0, the `decay_factor` ∈ (0, 1) and the `min_step_width`
+    /// ∈ [0, `initial_step_width`], where a value of `0.0` disables the minimal step width.
     ///
     /// Armijo used in his paper the values 0.5, 1.0 and 0.5, respectively.
-    pub fn new(control_parameter: f64, initial_step_width: f64, decay_factor: f64) ->
-        ArmijoLineSearch
+    pub fn new(control_parameter: f64, initial_step_width: f64, decay_factor: f64,
+        min_step_width: f64) -> ArmijoLineSearch
     {
-        assert!(control_parameter > 0.0 && control_parameter < 1.0,
-            "control_parameter must be in range (0, 1)");
-        assert!(initial_step_width > 0.0 && initial_step_width.is_finite(),
-            "initial_step_width must be > 0 and finite");
-        assert!(decay_factor > 0.0 && decay_factor < 1.0, "decay_factor must be in range (0, 1)");
+        ArmijoLineSearch::try_new(control_parameter, initial_step_width, decay_factor,
+            min_step_width).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new `ArmijoLineSearch` like `new()`, but returns an error instead of
+    /// panicking if a parameter is invalid.
+    pub fn try_new(control_parameter: f64, initial_step_width: f64, decay_factor: f64,
+        min_step_width: f64) -> Result<ArmijoLineSearch, ConfigError>
+    {
+        check_range("control_parameter", control_parameter, "(0, 1)",
+            control_parameter > 0.0 && control_parameter < 1.0)?;
+        check_step_width("initial_step_width", initial_step_width)?;
+        check_range("decay_factor", decay_factor, "(0, 1)",
+            decay_factor > 0.0 && decay_factor < 1.0)?;
+        check_range("min_step_width", min_step_width, "[0, initial_step_width]",
+            min_step_width >= 0.0 && min_step_width <= initial_step_width)?;
 
-        ArmijoLineSearch {
+        Ok(ArmijoLineSearch {
             control_parameter,
             initial_step_width,
-            decay_factor
+            decay_factor,
+            min_step_width,
+            non_descent: NonDescent::Panic
+        })
+    }
+
+    /// Restricts the line search to the box given by the `bounds`, i.e., the lower and upper
+    /// bound for each dimension, assuming that the initial position is feasible.
+    ///
+    /// The initial step width is capped such that the first trial position stays feasible,
+    /// and all trial positions are clamped into the box. If the initial position is located
+    /// at the boundary, the Armijo rule is evaluated along the projected step.
+    pub fn with_bounds(self, bounds: Vec<(f64, f64)>) -> BoundedArmijoLineSearch {
+        assert!(bounds.iter().all(|&(lower, upper)| lower <= upper),
+            "lower bounds must not be larger than upper bounds");
+
+        BoundedArmijoLineSearch {
+            line_search: self,
+            bounds
         }
     }
+
+    /// Adjusts how a direction is handled that is not a descent direction, see `NonDescent`.
+    /// By default, the line search panics.
+    pub fn on_non_descent(mut self, non_descent: NonDescent) -> Self {
+        self.non_descent = non_descent;
+        self
+    }
 }
 
 impl LineSearch for ArmijoLineSearch {
-    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Vec<f64>
-        where F: Function1
+    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function1 + ?Sized
+    {
+        self.search_with_hint(function, initial_position, direction, self.initial_step_width)
+    }
+
+    /// Starts the backtracking at the `hint` instead of the initial step width.
+    fn search_with_hint<F>(&self, function: &F, initial_position: &[f64], direction: &[f64],
+                           hint: f64) -> Step
+        where F: Function1 + ?Sized
+    {
+        self.backtrack(function, initial_position, direction, hint, None)
+    }
+}
+
+impl ArmijoLineSearch {
+    fn backtrack<F>(&self, function: &F, initial_position: &[f64], direction: &[f64], hint: f64,
+                    bounds: Option<&[(f64, f64)]>) -> Step
+        where F: Function1 + ?Sized
     {
-        let initial_value = function.value(initial_position);
-        let gradient = function.gradient(initial_position);
+        assert!(hint > 0.0 && hint.is_finite(), "hint must be > 0 and finite");
+
+        let (initial_value, gradient) = function.value_and_gradient(initial_position);
+
+        let mut m = gradient.iter().zip(direction).map(|(g, d)| g * d).fold(0.0, Add::add);
+
+        let steepest_descent: Vec<_>;
+        let mut direction = direction;
+
+        if m >= 0.0 || m.is_nan() {
+            match self.non_descent {
+                NonDescent::Panic => panic!("direction is not a descent direction, the slope \
+                    ∇f(x)ᵀd = {:?} must be negative", m),
+                NonDescent::SteepestDescent => {
+                    debug!("Direction is not a descent direction, using the steepest descent");
+
+                    steepest_descent = gradient.iter().map(|g| -g).collect();
+                    direction = &steepest_descent;
+                    m = -gradient.iter().map(|g| g * g).fold(0.0, Add::add);
+
+                    // there is no descent direction at all
+                    if m >= 0.0 || m.is_nan() {
+                        return Step::new(initial_position, direction, 0.0);
+                    }
+                }
+            }
+        }
+
+        let t = -self.control_parameter * m;
+
+        if let Some(bounds) = bounds {
+            assert_eq!(bounds.len(), initial_position.len());
+
+            // largest step width keeping the first trial position feasible
+            let max_step_width = initial_position.iter().zip(direction).zip(bounds)
+                .map(|((&x, &d), &(lower, upper))| {
+                    if d > 0.0 {
+                        (upper - x) / d
+                    } else if d < 0.0 {
+                        (lower - x) / d
+                    } else {
+                        f64::INFINITY
+                    }
+                })
+                .fold(f64::INFINITY, f64::min);
+
+            let mut step_width = if max_step_width > 0.0 {
+                hint.min(max_step_width)
+            } else {
+                hint
+            };
+
+            loop {
+                let position: Vec<_> = initial_position.iter().zip(direction).zip(bounds)
+                    .map(|((x, d), &(lower, upper))| (x + step_width * d).clamp(lower, upper))
+                    .collect();
+
+                // the projected step, which equals the actual step unless it has been clamped
+                let m = gradient.iter().zip(&position).zip(initial_position)
+                    .map(|((g, p), x)| g * (p - x))
+                    .fold(0.0, Add::add);
+
+                // we cannot move along the direction without leaving the box
+                if m >= 0.0 {
+                    return Step::new(initial_position, direction, 0.0);
+                }
+
+                let value = function.value(&position);
+
+                if value <= initial_value + self.control_parameter * m ||
+                    step_width * self.decay_factor < self.min_step_width
+                {
+                    return Step {
+                        position,
+                        step_width
+                    };
+                }
+
+                step_width *= self.decay_factor;
+            }
+        }
+
+        let mut step_width = hint;
+
+        loop {
+            let step = Step::new(initial_position, direction, step_width);
+            let value = function.value(&step.position);
+
+            if value <= initial_value - step_width * t ||
+                step_width * self.decay_factor < self.min_step_width
+            {
+                return step;
+            }
+
+            step_width *= self.decay_factor;
+        }
+    }
+}
+
+
+/// Backtracking line search evaluating the Armijo rule like `ArmijoLineSearch`, but restricted
+/// to a box, see `ArmijoLineSearch::with_bounds()`.
+#[derive(Debug, Clone)]
+pub struct BoundedArmijoLineSearch {
+    line_search: ArmijoLineSearch,
+    bounds: Vec<(f64, f64)>
+}
+
+impl BoundedArmijoLineSearch {
+    /// Returns the lower and upper bound for each dimension.
+    pub fn bounds(&self) -> &[(f64, f64)] {
+        &self.bounds
+    }
+}
+
+impl LineSearch for BoundedArmijoLineSearch {
+    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function1 + ?Sized
+    {
+        self.search_with_hint(function, initial_position, direction,
+            self.line_search.initial_step_width)
+    }
+
+    /// Starts the backtracking at the `hint` instead of the initial step width.
+    fn search_with_hint<F>(&self, function: &F, initial_position: &[f64], direction: &[f64],
+                           hint: f64) -> Step
+        where F: Function1 + ?Sized
+    {
+        self.line_search.backtrack(function, initial_position, direction, hint,
+            Some(&self.bounds))
+    }
+}
+
+
+/// Nonmonotone backtracking line search by Grippo, Lampariello and Lucidi, evaluating the
+/// Armijo rule against the maximal value of the last `memory` searches instead of the value
+/// at the initial position.
+///
+/// Hence, a step may increase the value temporarily, which often speeds up the descent
+/// through narrow curved valleys, e.g., of the Rosenbrock function, where a monotone line
+/// search enforces tiny steps. A `memory` of `1` results in the ordinary Armijo rule.
+///
+/// The values at the initial positions of the recent searches are kept per minimization by
+/// its `SearchHistory`, thus a single `search()` applies the ordinary Armijo rule.
+#[derive(Debug, Copy, Clone)]
+pub struct NonmonotoneLineSearch {
+    control_parameter: f64,
+    initial_step_width: f64,
+    decay_factor: f64,
+    memory: usize
+}
+
+impl NonmonotoneLineSearch {
+    /// Creates a new `NonmonotoneLineSearch` given the `control_parameter` ∈ (0, 1), the
+    /// `initial_step_width` > 0, the `decay_factor` ∈ (0, 1) and the `memory` > 0, i.e., the
+    /// number of recent values whose maximum serves as reference.
+    ///
+    /// Grippo, Lampariello and Lucidi used in their paper the values 1e-4, 1.0, 0.5 and 10,
+    /// respectively.
+    pub fn new(control_parameter: f64, initial_step_width: f64, decay_factor: f64,
+        memory: usize) -> NonmonotoneLineSearch
+    {
+        NonmonotoneLineSearch::try_new(control_parameter, initial_step_width, decay_factor,
+            memory).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new `NonmonotoneLineSearch` like `new()`, but returns an error instead of
+    /// panicking if a parameter is invalid.
+    pub fn try_new(control_parameter: f64, initial_step_width: f64, decay_factor: f64,
+        memory: usize) -> Result<NonmonotoneLineSearch, ConfigError>
+    {
+        check_range("control_parameter", control_parameter, "(0, 1)",
+            control_parameter > 0.0 && control_parameter < 1.0)?;
+        check_step_width("initial_step_width", initial_step_width)?;
+        check_range("decay_factor", decay_factor, "(0, 1)",
+            decay_factor > 0.0 && decay_factor < 1.0)?;
+        check_range("memory", memory as f64, "[1, ∞)", memory > 0)?;
+
+        Ok(NonmonotoneLineSearch {
+            control_parameter,
+            initial_step_width,
+            decay_factor,
+            memory
+        })
+    }
+}
+
+impl LineSearch for NonmonotoneLineSearch {
+    fn search<F>(&self, function: &F, initial_position: &[f64], direction: &[f64]) -> Step
+        where F: Function1 + ?Sized
+    {
+        self.search_in(&mut SearchHistory::new(), function, initial_position, direction, None)
+    }
+
+    /// Starts the backtracking at the `hint` instead of the initial step width.
+    fn search_with_hint<F>(&self, function: &F, initial_position: &[f64], direction: &[f64],
+                           hint: f64) -> Step
+        where F: Function1 + ?Sized
+    {
+        self.search_in(&mut SearchHistory::new(), function, initial_position, direction,
+            Some(hint))
+    }
+
+    /// Evaluates the Armijo rule against the maximal value of the last `memory` searches of
+    /// the `history`, starting the backtracking at the `hint` if given.
+    fn search_in<F>(&self, history: &mut SearchHistory, function: &F, initial_position: &[f64],
+                    direction: &[f64], hint: Option<f64>) -> Step
+        where F: Function1 + ?Sized
+    {
+        let hint = hint.unwrap_or(self.initial_step_width);
+
+        assert!(hint > 0.0 && hint.is_finite(), "hint must be > 0 and finite");
+
+        let (initial_value, gradient) = function.value_and_gradient(initial_position);
 
         let m = gradient.iter().zip(direction).map(|(g, d)| g * d).fold(0.0, Add::add);
         let t = -self.control_parameter * m;
 
         assert!(t > 0.0);
 
-        let mut step_width = self.initial_step_width;
+        let reference = {
+            let values = &mut history.values;
+
+            while values.len() >= self.memory {
+                values.pop_front();
+            }
+            values.push_back(initial_value);
+
+            values.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+        };
+
+        let mut step_width = hint;
 
         loop {
-            let position: Vec<_> = initial_position.iter().cloned().zip(direction).map(|(x, d)| {
-                x + step_width * d
-            }).collect();
-            let value = function.value(&position);
+            let step = Step::new(initial_position, direction, step_width);
+            let value = function.value(&step.position);
 
-            if value <= initial_value - step_width * t {
-                return position;
+            // give up once the step width vanished, e.g., due to rounding errors
+            if value <= reference - step_width * t || step_width * t < f64::MIN_POSITIVE {
+                return step;
             }
 
             step_width *= self.decay_factor;
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use problems::{Problem, Sphere, Rosenbrock};
+    use types::{Func1, Function, Function1, Convergence, Minimizer, ConfigError};
+    use gd::GradientDescent;
+
+    use super::{Step, SearchHistory, LineSearch, FixedStepWidth, ExactLineSearch,
+        ArmijoLineSearch, NonDescent, DiminishingStep, NonmonotoneLineSearch};
+
+    // parabola with additive noise that makes every position look worse than the given one
+    struct Noisy(f64);
+
+    impl Function for Noisy {
+        fn value(&self, x: &[f64]) -> f64 {
+            x[0].powi(2) + if x[0] == self.0 { 0.0 } else { 1.0e-2 }
+        }
+    }
+
+    impl Function1 for Noisy {
+        fn gradient(&self, x: &[f64]) -> Vec<f64> {
+            vec![2.0 * x[0]]
+        }
+    }
+
+    #[test]
+    fn test_armijo_min_step_width() {
+        let function = Noisy(0.1);
+        let direction = vec![-0.2];
+
+        let frozen = ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0)
+            .search(&function, &[0.1], &direction);
+
+        assert_eq!(frozen.position, vec![0.1]);
+
+        let step = ArmijoLineSearch::new(0.5, 1.0, 0.5, 1.0e-3)
+            .search(&function, &[0.1], &direction);
+
+        let step_width = (step.position[0] - 0.1) / direction[0];
+
+        assert!((step.step_width - step_width).abs() < 1.0e-12);
+        assert!((1.0e-3..2.0e-3).contains(&step_width), "{} violates the floor", step_width);
+    }
+
+    #[test]
+    #[should_panic(expected = "direction is not a descent direction")]
+    fn test_armijo_ascent_direction() {
+        ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0).search(&Sphere::default(), &[1.0, -2.0],
+            &[1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_armijo_steepest_descent_fallback() {
+        let sphere = Sphere::default();
+        let line_search = ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0)
+            .on_non_descent(NonDescent::SteepestDescent);
+
+        // the ascent direction is replaced by the negative gradient [-2, 4]
+        let step = line_search.search(&sphere, &[1.0, -2.0], &[1.0, -1.0]);
+
+        assert_eq!(step.step_width, 0.5);
+        assert_eq!(step.position, vec![0.0, 0.0]);
+
+        // there is no descent direction at the minimum
+        let step = line_search.search(&sphere, &[0.0, 0.0], &[1.0, -1.0]);
+
+        assert_eq!(step.step_width, 0.0);
+        assert_eq!(step.position, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_step_width() {
+        let sphere = Sphere::default();
+        let position = [1.0, -2.0];
+        let direction: Vec<_> = sphere.gradient(&position).iter().map(|g| -g).collect();
+
+        // the full step overshoots to [-1, 2], thus a single backtracking step is required
+        let step = ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0).search(&sphere, &position, &direction);
+
+        assert_eq!(step.step_width, 0.5);
+        assert_eq!(step.position, vec![0.0, 0.0]);
+
+        let step = FixedStepWidth::new(0.1).search(&sphere, &position, &direction);
+
+        assert_eq!(step.step_width, 0.1);
+        assert_eq!(step, Step::new(&position, &direction, 0.1));
+
+        let line_search = DiminishingStep::new(0.1, 0.5);
+        let mut history = SearchHistory::new();
+
+        assert_eq!(line_search.search_in(&mut history, &sphere, &position, &direction, None)
+            .step_width, 0.1);
+        assert_eq!(line_search.search_in(&mut history, &sphere, &position, &direction, None)
+            .step_width, 0.1 / 2.0f64.sqrt());
+
+        // a single search starts at the first step width
+        assert_eq!(line_search.search(&sphere, &position, &direction).step_width, 0.1);
+    }
+
+    #[test]
+    fn test_diminishing_step() {
+        let line_search = DiminishingStep::new(0.1, 0.5);
+
+        let report = GradientDescent::new()
+            .line_search(line_search)
+            .max_iterations(Some(10000))
+            .minimize(&Sphere::default(), vec![1.0, -2.0]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+        assert!(report.position.iter().all(|x| x.abs() < 1.0e-4));
+
+        // the step widths diminish over the iterations of a minimization only
+        let gd = GradientDescent::new()
+            .line_search(line_search)
+            .max_iterations(Some(10))
+            .record_step_widths(true);
+
+        let first = gd.minimize(&Sphere::default(), vec![1.0, -2.0]);
+        let second = gd.minimize(&Sphere::default(), vec![1.0, -2.0]);
+
+        assert_eq!(first.position, second.position);
+        assert_eq!(first.step_widths, second.step_widths);
+        assert_eq!(first.step_widths.as_ref().map(|step_widths| step_widths[0]), Some(0.1));
+    }
+
+    #[test]
+    fn test_try_new() {
+        let step_width = |name, value| Err(ConfigError::NonPositiveStepWidth { name, value });
+        let out_of_range = |name, value, range| {
+            Err(ConfigError::OutOfRange { name, value, range })
+        };
+
+        assert!(FixedStepWidth::try_new(0.5).is_ok());
+        assert_eq!(FixedStepWidth::try_new(0.0).map(|_| ()), step_width("fixed_step_width", 0.0));
+        assert_eq!(FixedStepWidth::try_new(f64::INFINITY).map(|_| ()),
+            step_width("fixed_step_width", f64::INFINITY));
+
+        assert!(DiminishingStep::try_new(1.0, 0.5).is_ok());
+        assert_eq!(DiminishingStep::try_new(-1.0, 0.5).map(|_| ()),
+            step_width("initial_step_width", -1.0));
+        assert_eq!(DiminishingStep::try_new(1.0, 1.5).map(|_| ()),
+            out_of_range("exponent", 1.5, "(0, 1]"));
+
+        assert!(ExactLineSearch::try_new(0.1, 10.0, 2.0).is_ok());
+        assert_eq!(ExactLineSearch::try_new(0.0, 10.0, 2.0).map(|_| ()),
+            step_width("start_step_width", 0.0));
+        assert_eq!(ExactLineSearch::try_new(0.1, 0.05, 2.0).map(|_| ()),
+            out_of_range("stop_step_width", 0.05, "(start_step_width, ∞)"));
+        assert_eq!(ExactLineSearch::try_new(0.1, 10.0, 1.0).map(|_| ()),
+            out_of_range("increase_factor", 1.0, "(1, ∞)"));
+
+        assert!(ArmijoLineSearch::try_new(0.5, 1.0, 0.5, 0.1).is_ok());
+        assert_eq!(ArmijoLineSearch::try_new(1.0, 1.0, 0.5, 0.0).map(|_| ()),
+            out_of_range("control_parameter", 1.0, "(0, 1)"));
+        assert_eq!(ArmijoLineSearch::try_new(0.5, 0.0, 0.5, 0.0).map(|_| ()),
+            step_width("initial_step_width", 0.0));
+        assert_eq!(ArmijoLineSearch::try_new(0.5, 1.0, 0.0, 0.0).map(|_| ()),
+            out_of_range("decay_factor", 0.0, "(0, 1)"));
+        assert_eq!(ArmijoLineSearch::try_new(0.5, 1.0, 0.5, 2.0).map(|_| ()),
+            out_of_range("min_step_width", 2.0, "[0, initial_step_width]"));
+
+        assert!(NonmonotoneLineSearch::try_new(1.0e-4, 1.0, 0.5, 10).is_ok());
+        assert_eq!(NonmonotoneLineSearch::try_new(1.0e-4, 1.0, 0.5, 0).map(|_| ()),
+            out_of_range("memory", 0.0, "[1, ∞)"));
+
+        // NaN is rejected as well
+        assert!(ArmijoLineSearch::try_new(f64::NAN, 1.0, 0.5, 0.0).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "decay_factor = 1.5 must be in range (0, 1)")]
+    fn test_new_panics() {
+        ArmijoLineSearch::new(0.5, 1.0, 1.5, 0.0);
+    }
+
+    #[test]
+    fn test_exact_line_search_refine() {
+        let sphere = Sphere::default();
+
+        let position = [1.0, 2.0];
+        let direction: Vec<_> = sphere.gradient(&position).iter().map(|g| -g).collect();
+
+        // the candidates 0.125, 0.375, 1.125 and 3.375 miss the minimum at 0.5
+        let grid = ExactLineSearch::new(0.125, 5.0, 3.0);
+
+        let coarse = grid.search(&sphere, &position, &direction);
+        let refined = grid.refine(1.0e-6).search(&sphere, &position, &direction);
+
+        assert_eq!(coarse.step_width, 0.375);
+        assert!((refined.step_width - 0.5).abs() < 1.0e-6, "{}", refined.step_width);
+        assert!(sphere.value(&refined.position) < sphere.value(&coarse.position));
+
+        // the value decreases up to the last candidate, thus there is nothing to refine
+        let short = ExactLineSearch::new(0.0625, 0.3, 2.0).refine(1.0e-6);
+
+        assert_eq!(short.search(&sphere, &position, &direction).step_width, 0.25);
+    }
+
+    #[test]
+    fn test_armijo_with_bounds() {
+        // evaluating an illegal position of a problem panics
+        let problem = Sphere::bounded(2, 1.0, 3.0);
+
+        let line_search = ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0).with_bounds(problem.domain());
+
+        assert_eq!(line_search.bounds(), &[(1.0, 3.0); 2][..]);
+
+        let report = GradientDescent::new()
+            .line_search(line_search)
+            .max_iterations(Some(100))
+            .minimize(&problem, vec![2.9, 1.5]);
+
+        assert!(problem.is_legal_position(&report.position));
+        assert_eq!(report.position, problem.minimum().0);
+        assert_eq!(report.value, problem.minimum().1);
+    }
+
+    #[test]
+    fn test_nonmonotone_line_search() {
+        let parabola = Func1(|x: &[f64]| x[0].powi(2), |x: &[f64]| vec![2.0 * x[0]]);
+
+        let line_search = NonmonotoneLineSearch::new(1.0e-4, 1.0, 0.5, 10);
+        let mut history = SearchHistory::new();
+
+        assert_eq!(line_search.search_in(&mut history, &parabola, &[2.0], &[-1.0], None).position,
+            vec![1.0]);
+
+        // overshooting to a larger value is accepted, since it is below the value at 2
+        let step = line_search.search_in(&mut history, &parabola, &[1.0], &[-2.5], None);
+
+        assert_eq!(step.position, vec![-1.5]);
+        assert_eq!(step.step_width, 1.0);
+
+        // in contrast to the monotone Armijo rule of a single search
+        let step = line_search.search(&parabola, &[1.0], &[-2.5]);
+
+        assert_eq!(step.position, vec![-0.25]);
+        assert_eq!(step.step_width, 0.5);
+    }
+
+    #[test]
+    fn test_nonmonotone_rosenbrock() {
+        let problem = Rosenbrock::default();
+
+        let gd = GradientDescent::new()
+            .line_search(NonmonotoneLineSearch::new(1.0e-4, 1.0, 0.5, 10))
+            .max_iterations(Some(100000))
+            .record_trajectory(true);
+
+        let report = gd.minimize(&problem, vec![-1.2, 1.0]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+        assert!((report.position[0] - 1.0).abs() < 1.0e-2, "{:?}", report.position);
+        assert!((report.position[1] - 1.0).abs() < 1.0e-2, "{:?}", report.position);
+
+        // some steps are accepted although they increase the value
+        let trajectory = report.trajectory.as_ref().unwrap();
+
+        assert!(trajectory.windows(2).any(|pair| pair[1].1 > pair[0].1));
+
+        // each minimization starts with an empty history
+        let second = gd.minimize(&problem, vec![-1.2, 1.0]);
+
+        assert_eq!(report.position, second.position);
+        assert_eq!(report.iterations, second.iterations);
+    }
+}
@@ -0,0 +1,140 @@
+//! Common loss functions measuring the distance of a position to a fixed target, e.g., to
+//! build objectives for fitting problems.
+
+use types::{Function, Function1};
+
+
+/// The squared euclidean distance to a `target`, i.e., `f(x) = ‖x - t‖² = Σᵢ (xᵢ - tᵢ)²`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SquaredError {
+    /// The target `t` at which the loss attains its minimum of zero.
+    pub target: Vec<f64>
+}
+
+impl SquaredError {
+    /// Creates a new `SquaredError` loss given the `target`.
+    pub fn new(target: Vec<f64>) -> SquaredError {
+        SquaredError {
+            target
+        }
+    }
+}
+
+impl Function for SquaredError {
+    fn value(&self, position: &[f64]) -> f64 {
+        assert_eq!(position.len(), self.target.len());
+
+        position.iter().zip(&self.target).map(|(x, t)| (x - t).powi(2)).sum()
+    }
+
+    fn is_convex(&self) -> Option<bool> {
+        Some(true)
+    }
+}
+
+impl Function1 for SquaredError {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        assert_eq!(position.len(), self.target.len());
+
+        position.iter().zip(&self.target).map(|(x, t)| 2.0 * (x - t)).collect()
+    }
+}
+
+impl_add!{[] SquaredError}
+
+
+/// The `L1` distance to a `target`, i.e., `f(x) = ‖x - t‖₁ = Σᵢ |xᵢ - tᵢ|`.
+///
+/// The function is not differentiable at `xᵢ = tᵢ`, where the subgradient `0` is returned
+/// for the `i`-th component, which makes it suitable for, e.g., `SubgradientDescent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbsoluteError {
+    /// The target `t` at which the loss attains its minimum of zero.
+    pub target: Vec<f64>
+}
+
+impl AbsoluteError {
+    /// Creates a new `AbsoluteError` loss given the `target`.
+    pub fn new(target: Vec<f64>) -> AbsoluteError {
+        AbsoluteError {
+            target
+        }
+    }
+}
+
+impl Function for AbsoluteError {
+    fn value(&self, position: &[f64]) -> f64 {
+        assert_eq!(position.len(), self.target.len());
+
+        position.iter().zip(&self.target).map(|(x, t)| (x - t).abs()).sum()
+    }
+
+    fn is_convex(&self) -> Option<bool> {
+        Some(true)
+    }
+}
+
+impl Function1 for AbsoluteError {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        assert_eq!(position.len(), self.target.len());
+
+        position.iter().zip(&self.target)
+            .map(|(x, t)| if x == t { 0.0 } else { (x - t).signum() })
+            .collect()
+    }
+}
+
+impl_add!{[] AbsoluteError}
+
+
+#[cfg(test)]
+mod tests {
+    use numeric::NumericalDifferentiation;
+    use types::{Function, Function1, Minimizer};
+    use gd::GradientDescent;
+
+    use super::{SquaredError, AbsoluteError};
+
+    fn assert_numerical_gradient<F: Function1 + Clone>(loss: &F, positions: &[[f64; 3]]) {
+        let numeric = NumericalDifferentiation::new(loss.clone());
+
+        for position in positions {
+            let analytic = loss.gradient(position);
+            let approximation = numeric.gradient(position);
+
+            for (a, b) in analytic.iter().zip(approximation) {
+                assert!((a - b).abs() < 1.0e-4 * a.abs().max(1.0), "{} != {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_squared_error() {
+        let loss = SquaredError::new(vec![1.0, -2.0, 0.5]);
+
+        assert_eq!(loss.value(&[1.0, -2.0, 0.5]), 0.0);
+        assert_eq!(loss.value(&[2.0, 0.0, 0.5]), 5.0);
+
+        assert_numerical_gradient(&loss, &[[0.0, 0.0, 0.0], [3.0, -1.0, 2.5], [-4.0, 7.0, 0.1]]);
+
+        let report = GradientDescent::new().minimize(&loss, vec![0.0; 3]);
+
+        for (x, t) in report.position.iter().zip(&loss.target) {
+            assert!((x - t).abs() < 1.0e-4, "{:?} is not close to the target", report.position);
+        }
+    }
+
+    #[test]
+    fn test_absolute_error() {
+        let loss = AbsoluteError::new(vec![1.0, -2.0, 0.5]);
+
+        assert_eq!(loss.value(&[2.0, 0.0, 0.5]), 3.0);
+
+        // keep clear of the kinks, where the finite differences are meaningless
+        assert_numerical_gradient(&loss, &[[0.0, 0.0, 0.0], [3.0, -1.0, 2.5], [-4.0, 7.0, 0.1]]);
+
+        // the subgradient vanishes in the components which match the target
+        assert_eq!(loss.gradient(&[1.0, 0.0, 0.5]), vec![0.0, 1.0, 0.0]);
+        assert_eq!(loss.gradient(&[1.0, -2.0, 0.5]), vec![0.0; 3]);
+    }
+}
@@ -0,0 +1,351 @@
+use rand::{SeedableRng, random};
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64Mcg;
+
+use types::{Minimizer, Summation2, SummationAccuracy, Convergence, SgdReport, ConfigError,
+    check_range, check_step_width, check_count};
+use utils::solve_linear_system;
+
+
+/// Provides stochastic _natural gradient_ descent, i.e., stochastic Gradient Descent
+/// preconditioned by the inverse curvature of each mini batch.
+///
+/// In each step, the average gradient `g` of the terms of a random mini batch is
+/// preconditioned by the average curvature `C` of a term, i.e., the position is moved by
+/// `-γ (C + λ I)⁻¹ g` using the damping `λ`. The curvature is an exponential moving average
+/// over the recent mini batches, whose terms provide their curvature matrices using
+/// `Summation2::term_hessian()`. It should return the Fisher information or the Gauss-Newton
+/// matrix of a term if its Hessian is not positive semidefinite, e.g., for the negative
+/// log-likelihood of a generalized linear model, both coincide with the Hessian.
+///
+/// Thus, the steps are nearly invariant to the scaling of the coordinates, which speeds up
+/// the convergence on curved loss surfaces considerably, at the cost of solving an `n × n`
+/// linear system per step.
+#[derive(Debug, Clone)]
+pub struct NaturalGradientDescent {
+    rng: Pcg64Mcg,
+    max_iterations: Option<u64>,
+    target_value: Option<f64>,
+    mini_batch: usize,
+    summation_accuracy: SummationAccuracy,
+    step_width: f64,
+    damping: f64,
+    curvature_decay: f64
+}
+
+impl NaturalGradientDescent {
+    /// Creates a new `NaturalGradientDescent` optimizer using the following defaults:
+    ///
+    /// - **`step_width`** = `0.1`
+    /// - **`damping`** = `1e-2`
+    /// - **`curvature_decay`** = `0.9`
+    /// - **`mini_batch`** = `10`
+    /// - **`summation_accuracy`** = `SummationAccuracy::Sequential`
+    /// - **`target_value`** = `None`
+    /// - **`max_iterations`** = `1000`
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new() -> NaturalGradientDescent {
+        NaturalGradientDescent {
+            rng: Pcg64Mcg::new(random()),
+            max_iterations: Some(1000),
+            target_value: None,
+            mini_batch: 10,
+            summation_accuracy: SummationAccuracy::Sequential,
+            step_width: 0.1,
+            damping: 1.0e-2,
+            curvature_decay: 0.9
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations, i.e., passes over all terms. A value
+    /// of `None` instructs the optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(&mut self, max_iterations: Option<u64>) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Adjusts the target value, i.e., the optimization is stopped as soon as the value
+    /// drops to or below it. A value of `None` disables this criterion.
+    pub fn target_value(&mut self, target_value: Option<f64>) -> &mut Self {
+        self.target_value = target_value;
+        self
+    }
+
+    /// Adjusts the mini batch size, i.e., how many terms are considered in one step at most.
+    /// Larger mini batches estimate the curvature more accurately.
+    pub fn mini_batch(&mut self, mini_batch: usize) -> &mut Self {
+        self.mini_batch = mini_batch;
+        self
+    }
+
+    /// Adjusts how the terms are summed up when evaluating the objective function, see
+    /// `SummationAccuracy`.
+    pub fn summation_accuracy(&mut self, summation_accuracy: SummationAccuracy) -> &mut Self {
+        self.summation_accuracy = summation_accuracy;
+        self
+    }
+
+    /// Adjusts the step width of the preconditioned steps.
+    pub fn step_width(&mut self, step_width: f64) -> &mut Self {
+        self.step_width = step_width;
+        self
+    }
+
+    /// Adjusts the damping `λ`, which keeps the curvature matrix regular and limits the steps
+    /// along directions of little curvature.
+    pub fn damping(&mut self, damping: f64) -> &mut Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Adjusts the decay ∈ [0, 1) of the exponential moving average over the curvature of
+    /// the recent mini batches, which stabilizes the estimate of the curvature matrix. A value
+    /// of `0.0` uses the curvature of the current mini batch only.
+    pub fn curvature_decay(&mut self, curvature_decay: f64) -> &mut Self {
+        self.curvature_decay = curvature_decay;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        check_count("mini_batch", self.mini_batch as u64)?;
+        check_step_width("step_width", self.step_width)?;
+        check_range("damping", self.damping, "(0, ∞)",
+            self.damping > 0.0 && self.damping.is_finite())?;
+        check_range("curvature_decay", self.curvature_decay, "[0, 1)",
+            (0.0..1.0).contains(&self.curvature_decay))
+    }
+}
+
+impl Default for NaturalGradientDescent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Summation2> Minimizer<F> for NaturalGradientDescent {
+    type Solution = SgdReport;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> SgdReport {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "NaturalGradientDescent",
+            max_iterations = ?self.max_iterations, target_value = ?self.target_value,
+            mini_batch = self.mini_batch, summation_accuracy = ?self.summation_accuracy,
+            step_width = self.step_width, damping = self.damping).entered();
+
+        let n = initial_position.len();
+
+        let mut position = initial_position;
+        let mut value = function.value_with(&position, self.summation_accuracy);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut terms: Vec<_> = (0..function.terms()).collect();
+        let mut rng = self.rng.clone();
+
+        // the average curvature of a single term
+        let mut curvature = vec![0.0; n * n];
+        let mut step = 0;
+
+        let convergence = loop {
+            if self.target_value.is_some_and(|target_value| value <= target_value) {
+                info!("Reached target value, stopping optimization");
+
+                break Convergence::TargetValue;
+            }
+
+            // ensure that we don't run into cycles
+            terms.shuffle(&mut rng);
+
+            for batch in terms.chunks(self.mini_batch) {
+                let size = batch.len() as f64;
+
+                let gradient: Vec<_> = function.partial_gradient(&position, batch).into_iter()
+                    .map(|g| g / size)
+                    .collect();
+                let batch_curvature = function.partial_hessian(&position, batch);
+
+                // the first mini batch initializes the average without any bias
+                let decay = if step == 0 { 0.0 } else { self.curvature_decay };
+
+                for (c, b) in curvature.iter_mut().zip(batch_curvature) {
+                    *c = decay * *c + (1.0 - decay) * b / size;
+                }
+
+                step += 1;
+
+                let mut damped = curvature.clone();
+
+                for i in 0..n {
+                    damped[i * n + i] += self.damping;
+                }
+
+                // an indefinite curvature matrix might still be singular, fall back to the
+                // plain gradient in this case
+                let direction = solve_linear_system(&damped, &gradient).unwrap_or(gradient);
+
+                for (x, d) in position.iter_mut().zip(direction) {
+                    *x -= self.step_width * d;
+                }
+            }
+
+            value = function.value_with(&position, self.summation_accuracy);
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        SgdReport::new(position, value, iteration, convergence)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+    use rand_pcg::Pcg64Mcg;
+
+    use types::{Minimizer, Summation, Summation1, Summation2, Convergence, ConfigError};
+    use sgd::StochasticGradientDescent;
+
+    use super::NaturalGradientDescent;
+
+    // negative log-likelihood of a logistic regression, i.e., ∑ᵢ ln(1 + exp(-yᵢ wᵀxᵢ)), whose
+    // features are scaled differently
+    struct LogisticRegression {
+        observations: Vec<(Vec<f64>, f64)>
+    }
+
+    impl LogisticRegression {
+        fn new(coefficients: &[f64], samples: usize, seed: u64) -> LogisticRegression {
+            let mut rng = Pcg64Mcg::seed_from_u64(seed);
+
+            let observations = (0..samples).map(|_| {
+                // an intercept followed by features of an increasing scale
+                let x: Vec<f64> = (0..coefficients.len()).map(|i| if i == 0 {
+                    1.0
+                } else {
+                    3.0f64.powi(i as i32) * rng.sample::<f64, _>(StandardNormal)
+                }).collect();
+
+                let margin: f64 = coefficients.iter().zip(&x).map(|(w, x)| w * x).sum();
+                let y = if rng.gen::<f64>() < 1.0 / (1.0 + (-margin).exp()) { 1.0 } else { -1.0 };
+
+                (x, y)
+            }).collect();
+
+            LogisticRegression {
+                observations
+            }
+        }
+
+        // the margin yᵢ wᵀxᵢ of a term
+        fn margin(&self, w: &[f64], term: usize) -> f64 {
+            let (ref x, y) = self.observations[term];
+
+            y * w.iter().zip(x).map(|(w, x)| w * x).sum::<f64>()
+        }
+    }
+
+    impl Summation for LogisticRegression {
+        fn terms(&self) -> usize {
+            self.observations.len()
+        }
+
+        fn term_value(&self, w: &[f64], term: usize) -> f64 {
+            (-self.margin(w, term)).exp().ln_1p()
+        }
+    }
+
+    impl Summation1 for LogisticRegression {
+        fn term_gradient(&self, w: &[f64], term: usize) -> Vec<f64> {
+            let (ref x, y) = self.observations[term];
+            let sigma = 1.0 / (1.0 + self.margin(w, term).exp());
+
+            x.iter().map(|x| -y * sigma * x).collect()
+        }
+    }
+
+    impl Summation2 for LogisticRegression {
+        fn term_hessian(&self, w: &[f64], term: usize) -> Vec<f64> {
+            let x = &self.observations[term].0;
+            let sigma = 1.0 / (1.0 + self.margin(w, term).exp());
+            let weight = sigma * (1.0 - sigma);
+
+            x.iter().flat_map(|a| x.iter().map(move |b| weight * a * b)).collect()
+        }
+    }
+
+    #[test]
+    fn test_faster_than_sgd() {
+        let problem = LogisticRegression::new(&[0.5, -1.0, 0.3], 200, 42);
+
+        // using all terms at once amounts to the damped Newton method
+        let minimum = NaturalGradientDescent::new()
+            .mini_batch(problem.terms())
+            .step_width(1.0)
+            .max_iterations(Some(50))
+            .minimize(&problem, vec![0.0; 3])
+            .value;
+
+        let ngd = NaturalGradientDescent::new()
+            .seed(42)
+            .mini_batch(20)
+            .step_width(0.05)
+            .target_value(Some(minimum + 0.01))
+            .minimize(&problem, vec![0.0; 3]);
+
+        assert_eq!(ngd.convergence, Convergence::TargetValue);
+
+        // the scaling of the features slows plain SGD down for any step width
+        for &step_width in &[0.001, 0.003, 0.006] {
+            let sgd = StochasticGradientDescent::new()
+                .seed(42)
+                .mini_batch(20)
+                .step_width(step_width)
+                .target_value(Some(minimum + 0.01))
+                .minimize(&problem, vec![0.0; 3]);
+
+            assert!(ngd.epochs < sgd.epochs, "{} vs. {}", ngd.epochs, sgd.epochs);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(NaturalGradientDescent::new().validate(), Ok(()));
+        assert_eq!(NaturalGradientDescent::new().damping(0.0).validate(),
+            Err(ConfigError::OutOfRange { name: "damping", value: 0.0, range: "(0, ∞)" }));
+    }
+}
@@ -1,12 +1,81 @@
-use std::f64::EPSILON;
+use std::cell::RefCell;
+use rand::{Rng, RngCore, SeedableRng, random};
+use rand_pcg::Pcg64Mcg;
 
 use problems::Problem;
-use types::{Function, Function1};
+use types::{Function, Function1, VectorFunction, VectorFunction1};
+
+
+/// Specifies the finite difference stencil, i.e., the positions at which the function is
+/// evaluated to approximate each partial derivative.
+///
+/// Each stencil uses a step width `h` that balances its truncation error against the
+/// rounding error, i.e., the more accurate the stencil the larger the step width.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Stencil {
+    /// One step forward difference `(f(x + h) - f(x)) / h` with an error of `O(h)` and
+    /// `h = √εx`, requiring a single evaluation per dimension.
+    #[default]
+    Forward,
+    /// Two step forward difference `(-3 f(x) + 4 f(x + h) - f(x + 2h)) / 2h` with an error
+    /// of `O(h²)` and `h = ∛ε max(|x|, 1)`, requiring two evaluations per dimension.
+    Forward2,
+    /// Central difference `(f(x + h) - f(x - h)) / 2h` with an error of `O(h²)` and
+    /// `h = ∛ε max(|x|, 1)`, requiring two evaluations per dimension.
+    Central,
+    /// Five-point central difference `(f(x - 2h) - 8 f(x - h) + 8 f(x + h) - f(x + 2h)) / 12h`
+    /// with an error of `O(h⁴)` and `h = ε^⅕ max(|x|, 1)`, requiring four evaluations per
+    /// dimension.
+    FivePoint
+}
+
+impl Stencil {
+    // the step width h at the coordinate `x_i`
+    fn step_width(self, x_i: f64) -> f64 {
+        let order = match self {
+            Stencil::Forward => return step_width(x_i),
+            Stencil::Forward2 | Stencil::Central => 2.0,
+            Stencil::FivePoint => 4.0
+        };
+
+        f64::EPSILON.powf(1.0 / (order + 1.0)) * x_i.abs().max(1.0)
+    }
+
+    // the largest distance to `x_i` of the evaluated positions in terms of `h`
+    fn reach(self) -> f64 {
+        match self {
+            Stencil::Forward | Stencil::Central => 1.0,
+            Stencil::Forward2 | Stencil::FivePoint => 2.0
+        }
+    }
+
+    // approximates the `i`-th partial derivative at `x` with value `value` using the step
+    // width `h`, which may be negative to turn the forward into backward differences
+    fn derivative<F: Function + ?Sized>(self, function: &F, x: &mut [f64], value: f64, i: usize,
+                                        h: f64) -> f64 {
+        let x_i = x[i];
+
+        let mut at = |offset: f64| {
+            x[i] = x_i + offset * h;
+            let value = function.value(x);
+            x[i] = x_i;
+            value
+        };
+
+        match self {
+            Stencil::Forward => (at(1.0) - value) / h,
+            Stencil::Forward2 => (-3.0 * value + 4.0 * at(1.0) - at(2.0)) / (2.0 * h),
+            Stencil::Central => (at(1.0) - at(-1.0)) / (2.0 * h),
+            Stencil::FivePoint => (at(-2.0) - 8.0 * at(-1.0) + 8.0 * at(1.0) - at(2.0)) / (12.0 * h)
+        }
+    }
+}
 
 
 /// Wraps a function for which to provide numeric differentiation.
 ///
-/// Uses simple one step forward finite difference with step width `h = √εx`.
+/// Uses simple one step forward finite difference with step width `h = √εx` by default,
+/// see `stencil()` for more accurate alternatives.
 ///
 /// # Examples
 ///
@@ -21,7 +90,9 @@ use types::{Function, Function1};
 /// assert!(square.gradient(&[-1.0])[0] < 1.0);
 /// ```
 pub struct NumericalDifferentiation<F: Function> {
-    function: F
+    function: F,
+    domain: Option<Vec<(f64, f64)>>,
+    stencil: Stencil
 }
 
 impl<F: Function> NumericalDifferentiation<F> {
@@ -29,7 +100,33 @@ impl<F: Function> NumericalDifferentiation<F> {
     /// combination with numeric differentiation to find the derivatives.
     pub fn new(function: F) -> Self {
         NumericalDifferentiation {
-            function
+            function,
+            domain: None,
+            stencil: Stencil::Forward
+        }
+    }
+
+    /// Specifies the finite difference stencil to use. Wider stencils are more accurate,
+    /// in particular for functions contaminated by rounding errors or small noise, but
+    /// require more evaluations of the function, see `Stencil`.
+    pub fn stencil(mut self, stencil: Stencil) -> Self {
+        self.stencil = stencil;
+        self
+    }
+}
+
+impl<F: Problem> NumericalDifferentiation<F> {
+    /// Creates a new differentiable function like `new()`, which respects the domain of
+    /// the supplied `problem`, i.e., switches to a backward finite difference for each
+    /// coordinate where the forward steps would leave the domain. Central stencils fall back
+    /// to the one-sided `Forward2` stencil in such a case.
+    pub fn bounded(problem: F) -> Self {
+        let domain = problem.domain();
+
+        NumericalDifferentiation {
+            function: problem,
+            domain: Some(domain),
+            stencil: Stencil::Forward
         }
     }
 }
@@ -38,35 +135,20 @@ impl<F: Function> Function for NumericalDifferentiation<F> {
     fn value(&self, position: &[f64]) -> f64 {
         self.function.value(position)
     }
+
+    fn is_convex(&self) -> Option<bool> {
+        self.function.is_convex()
+    }
 }
 
 impl<F: Function> Function1 for NumericalDifferentiation<F> {
     fn gradient(&self, position: &[f64]) -> Vec<f64> {
-        let mut x: Vec<_> = position.to_vec();
+        let gradient = finite_difference(&self.function, position, self.value(position),
+            self.domain.as_deref(), self.stencil);
 
-        let current = self.value(&x);
-
-        position.iter().cloned().enumerate().map(|(i, x_i)| {
-            let h = if x_i == 0.0 {
-                EPSILON * 1.0e10
-            } else {
-                (EPSILON * x_i.abs()).sqrt()
-            };
-
-            assert!(h.is_finite());
-
-            x[i] = x_i + h;
-
-            let forward = self.function.value(&x);
-
-            x[i] = x_i;
-
-            let d_i = (forward - current) / h;
+        assert!(gradient.iter().all(|d_i| d_i.is_finite()));
 
-            assert!(d_i.is_finite());
-
-            d_i
-        }).collect()
+        gradient
     }
 }
 
@@ -89,34 +171,344 @@ impl<F: Problem> Problem for NumericalDifferentiation<F> {
         self.function.minimum()
     }
 
-    fn random_start(&self) -> Vec<f64> {
-        self.function.random_start()
+    fn typical_scale(&self) -> Vec<f64> {
+        self.function.typical_scale()
+    }
+
+    fn random_start_seeded(&self, rng: &mut dyn RngCore) -> Vec<f64> {
+        self.function.random_start_seeded(rng)
     }
 }
 
+impl_add!{[F: Function,] NumericalDifferentiation<F>}
+
+
+/// Wraps a function whose gradient is only partially known analytically, approximating
+/// the remaining partial derivatives by finite differences.
+///
+/// The `partial_gradient` returns `Some(∂f/∂xᵢ)` for each coordinate whose derivative is
+/// cheap to compute and `None` otherwise, such that the function is only evaluated for the
+/// latter. The finite differences use the `Stencil::Forward` stencil by default.
+///
+/// # Examples
+///
+/// ```
+/// # use self::optimization::*;
+/// // the derivative of the second coordinate is considered to be unknown
+/// let function = PartialAnalytic::new(Func(|x: &[f64]| x[0] * x[0] + x[1].sin()),
+///     |x: &[f64]| vec![Some(2.0 * x[0]), None]);
+///
+/// let gradient = function.gradient(&[3.0, 0.0]);
+///
+/// assert_eq!(gradient[0], 6.0);
+/// assert!((gradient[1] - 1.0).abs() < 1.0e-6);
+/// ```
+pub struct PartialAnalytic<F: Function, G: Fn(&[f64]) -> Vec<Option<f64>>> {
+    function: F,
+    partial_gradient: G,
+    stencil: Stencil
+}
+
+impl<F: Function, G: Fn(&[f64]) -> Vec<Option<f64>>> PartialAnalytic<F, G> {
+    /// Creates a new differentiable function given the `function` and its
+    /// `partial_gradient`.
+    pub fn new(function: F, partial_gradient: G) -> Self {
+        PartialAnalytic {
+            function,
+            partial_gradient,
+            stencil: Stencil::Forward
+        }
+    }
+
+    /// Specifies the finite difference stencil to use for the unknown partial derivatives,
+    /// see `NumericalDifferentiation::stencil()`.
+    pub fn stencil(mut self, stencil: Stencil) -> Self {
+        self.stencil = stencil;
+        self
+    }
+}
+
+impl<F: Function, G: Fn(&[f64]) -> Vec<Option<f64>>> Function for PartialAnalytic<F, G> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(position)
+    }
+}
+
+impl<F: Function, G: Fn(&[f64]) -> Vec<Option<f64>>> Function1 for PartialAnalytic<F, G> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        let partials = (self.partial_gradient)(position);
+
+        assert_eq!(partials.len(), position.len(),
+            "partial_gradient must return a component for each coordinate");
+
+        // the value is only required if some derivatives are approximated
+        let value = if partials.iter().any(Option::is_none) {
+            self.value(position)
+        } else {
+            f64::NAN
+        };
+
+        let mut x = position.to_vec();
+
+        partials.into_iter().enumerate().map(|(i, partial)| {
+            partial.unwrap_or_else(|| {
+                let h = self.stencil.step_width(position[i]);
+                let d_i = self.stencil.derivative(&self.function, &mut x, value, i, h);
+
+                assert!(d_i.is_finite());
+
+                d_i
+            })
+        }).collect()
+    }
+}
+
+impl_add!{[F: Function, G: Fn(&[f64]) -> Vec<Option<f64>>,] PartialAnalytic<F, G>}
+
+
+/// Wraps a function for which to provide a stochastic estimate of its gradient using the
+/// simultaneous perturbation stochastic approximation (SPSA) by Spall (1992).
+///
+/// All coordinates are perturbed at once along a random direction `Δ` of Rademacher signs
+/// `±1`, i.e., `ĝᵢ = (f(x + cΔ) - f(x - cΔ)) / 2cΔᵢ`, which requires only two evaluations
+/// of the function regardless of the number of dimensions. Thus, the estimate is far cheaper
+/// than finite differences in many dimensions, but noisy, while its expectation is the
+/// gradient up to the truncation error. Hence, it suits a fixed or diminishing step width,
+/// whereas the Armijo rule fails in more than a few dimensions, as the norm of the estimate
+/// overstates the slope along it. Likewise, a gradient tolerance might be met prematurely,
+/// as the estimate almost vanishes whenever `Δ` is nearly orthogonal to the gradient.
+///
+/// The estimate is reused as long as the gradient is requested at the very same position,
+/// e.g., by a line search, such that the slope along a direction remains consistent.
+#[derive(Debug)]
+pub struct Spsa<F: Function> {
+    function: F,
+    perturbation: Option<f64>,
+    rng: RefCell<Pcg64Mcg>,
+    estimate: RefCell<Option<(Vec<f64>, Vec<f64>)>>
+}
+
+impl<F: Function> Spsa<F> {
+    /// Creates a new differentiable function by using the supplied `function` in
+    /// combination with SPSA to estimate its gradient. The used random number generator is
+    /// randomly seeded.
+    pub fn new(function: F) -> Self {
+        Spsa {
+            function,
+            perturbation: None,
+            rng: RefCell::new(Pcg64Mcg::new(random())),
+            estimate: RefCell::new(None)
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(self, seed: u64) -> Self {
+        *self.rng.borrow_mut() = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the perturbation `c`. By default, the step width of the `Stencil::Central`
+    /// stencil at the largest absolute coordinate is used, while noisy functions require a
+    /// larger perturbation.
+    pub fn perturbation(mut self, perturbation: f64) -> Self {
+        assert!(perturbation > 0.0 && perturbation.is_finite(),
+            "perturbation must be > 0 and finite");
+
+        self.perturbation = Some(perturbation);
+        self
+    }
+}
+
+impl<F: Function> Function for Spsa<F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.value(position)
+    }
+
+    fn is_convex(&self) -> Option<bool> {
+        self.function.is_convex()
+    }
+}
+
+impl<F: Function> Function1 for Spsa<F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        if let Some((estimated_position, gradient)) = self.estimate.borrow().as_ref() {
+            if estimated_position.as_slice() == position {
+                return gradient.clone();
+            }
+        }
+
+        let c = self.perturbation.unwrap_or_else(|| {
+            Stencil::Central.step_width(position.iter().fold(0.0, |m: f64, x| m.max(x.abs())))
+        });
+
+        let signs: Vec<f64> = {
+            let mut rng = self.rng.borrow_mut();
+
+            position.iter().map(|_| if rng.gen() { 1.0 } else { -1.0 }).collect()
+        };
+
+        let forward: Vec<_> = position.iter().zip(&signs).map(|(x, s)| x + c * s).collect();
+        let backward: Vec<_> = position.iter().zip(&signs).map(|(x, s)| x - c * s).collect();
+
+        let difference = (self.function.value(&forward) - self.function.value(&backward)) /
+            (2.0 * c);
+
+        assert!(difference.is_finite(), "the value is not finite at the perturbed positions \
+            using the perturbation {:?}, check the domain of the function", c);
+
+        let gradient: Vec<_> = signs.iter().map(|s| difference / s).collect();
+
+        *self.estimate.borrow_mut() = Some((position.to_vec(), gradient.clone()));
+
+        gradient
+    }
+}
+
+impl_add!{[F: Function,] Spsa<F>}
+
+
+/// Wraps a vector-valued function `g: Rⁿ → Rᵐ` for which to provide its Jacobian by
+/// numeric differentiation.
+///
+/// Analogous to `NumericalDifferentiation`, each column of the Jacobian is approximated by
+/// a one step forward finite difference with step width `h = √εx`.
+pub struct NumericalJacobian<F: Fn(&[f64]) -> Vec<f64>> {
+    function: F
+}
+
+impl<F: Fn(&[f64]) -> Vec<f64>> NumericalJacobian<F> {
+    /// Creates a new differentiable vector-valued function by using the supplied `function`
+    /// in combination with numeric differentiation to find its Jacobian.
+    pub fn new(function: F) -> Self {
+        NumericalJacobian {
+            function
+        }
+    }
+}
+
+impl<F: Fn(&[f64]) -> Vec<f64>> VectorFunction for NumericalJacobian<F> {
+    fn value(&self, position: &[f64]) -> Vec<f64> {
+        (self.function)(position)
+    }
+}
+
+impl<F: Fn(&[f64]) -> Vec<f64>> VectorFunction1 for NumericalJacobian<F> {
+    fn jacobian(&self, position: &[f64]) -> Vec<f64> {
+        let n = position.len();
+
+        let mut x: Vec<_> = position.to_vec();
+
+        let current = self.value(&x);
+        let m = current.len();
+
+        let mut jacobian = vec![0.0; m * n];
+
+        for (j, x_j) in position.iter().cloned().enumerate() {
+            let h = step_width(x_j);
+
+            x[j] = x_j + h;
+
+            let forward = self.value(&x);
+
+            x[j] = x_j;
+
+            assert_eq!(forward.len(), m);
+
+            for (i, (f, c)) in forward.into_iter().zip(&current).enumerate() {
+                let d_ij = (f - c) / h;
+
+                assert!(d_ij.is_finite());
+
+                jacobian[i * n + j] = d_ij;
+            }
+        }
+
+        jacobian
+    }
+}
+
+
+/// Approximates the gradient of `function` at `position`, whose `value` is already known,
+/// using one step forward finite differences.
+pub fn forward_difference<F: Function + ?Sized>(function: &F, position: &[f64], value: f64)
+    -> Vec<f64>
+{
+    finite_difference(function, position, value, None, Stencil::Forward)
+}
+
+// uses backward differences for the coordinates where the forward steps leave the domain
+fn finite_difference<F: Function + ?Sized>(function: &F, position: &[f64], value: f64,
+                                           domain: Option<&[(f64, f64)]>, stencil: Stencil)
+    -> Vec<f64>
+{
+    let mut x: Vec<_> = position.to_vec();
+
+    position.iter().cloned().enumerate().map(|(i, x_i)| {
+        let mut stencil = stencil;
+        let mut h = stencil.step_width(x_i);
+
+        if let Some(domain) = domain {
+            let (lower, upper) = domain[i];
+            let reach = stencil.reach() * h;
+
+            if (stencil == Stencil::Central || stencil == Stencil::FivePoint) &&
+                (x_i - reach < lower || x_i + reach > upper)
+            {
+                stencil = Stencil::Forward2;
+            }
+
+            if x_i + stencil.reach() * h > upper {
+                h = -h;
+            }
+        }
+
+        stencil.derivative(function, &mut x, value, i, h)
+    }).collect()
+}
+
+
+/// Computes the step width `h` of a forward finite difference at coordinate `x_i`.
+fn step_width(x_i: f64) -> f64 {
+    let h = if x_i == 0.0 {
+        f64::EPSILON * 1.0e10
+    } else {
+        (f64::EPSILON * x_i.abs()).sqrt()
+    };
+
+    assert!(h.is_finite());
+
+    h
+}
+
 
 #[cfg(test)]
 mod tests {
-    use types::Function1;
-    use problems::{Problem, Sphere, Rosenbrock};
+    use std::cell::Cell;
+
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    use types::{Func, Function, Function1, Minimizer, VectorFunction1};
+    use problems::{self, Sphere, Rosenbrock};
     use utils::are_close;
     use gd::GradientDescent;
+    use line_search::FixedStepWidth;
 
-    use super::NumericalDifferentiation;
+    use super::{NumericalDifferentiation, PartialAnalytic, Spsa, NumericalJacobian, Stencil};
 
     #[test]
     fn test_accuracy() {
-        //let a = Sphere::default();
-        let b = Rosenbrock::default();
-
-        // FIXME: How to iterate over different problems?
-        let problems = vec![b];
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
 
-        for analytical_problem in problems {
-            let numerical_problem = NumericalDifferentiation::new(analytical_problem);
+        for analytical_problem in problems::all() {
+            let numerical_problem = NumericalDifferentiation::new(Func(|x: &[f64]| {
+                analytical_problem.value(x)
+            }));
 
             for _ in 0..1000 {
-                let position = analytical_problem.random_start();
+                let position = analytical_problem.random_start_seeded(&mut rng);
 
                 let analytical_gradient = analytical_problem.gradient(&position);
                 let numerical_gradient = numerical_problem.gradient(&position);
@@ -130,7 +522,188 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_jacobian() {
+        let map = NumericalJacobian::new(|x: &[f64]| vec![x[0] * x[0], x[0] * x[1]]);
+
+        for x in &[[0.0, 0.0], [1.0, -2.0], [-0.5, 3.0], [4.2, 13.37]] {
+            let analytical_jacobian = vec![2.0 * x[0], 0.0,
+                                           x[1], x[0]];
+            let numerical_jacobian = map.jacobian(x);
+
+            assert_eq!(analytical_jacobian.len(), numerical_jacobian.len());
+
+            for (a, n) in analytical_jacobian.into_iter().zip(numerical_jacobian) {
+                assert!((a - n).abs() < 1.0e-5, "{} differs from {} at {:?}", n, a, x);
+            }
+        }
+    }
+
     test_minimizer!{GradientDescent::new(),
         test_gd_sphere => NumericalDifferentiation::new(Sphere::default()),
         test_gd_rosenbrock => NumericalDifferentiation::new(Rosenbrock::default())}
+
+    #[test]
+    fn test_bounded() {
+        let sphere = Sphere::bounded(2, -1.0, 1.0);
+        let numerical = NumericalDifferentiation::bounded(sphere);
+
+        for x in &[[1.0, 0.5], [-1.0, 1.0], [0.0, -1.0]] {
+            let gradient = numerical.gradient(x);
+
+            for (n, a) in gradient.iter().zip(sphere.gradient(x)) {
+                assert!(n.is_finite() && (n - a).abs() < 1.0e-5, "{} differs from {} at {:?}",
+                    n, a, x);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unbounded_at_edge() {
+        NumericalDifferentiation::new(Sphere::bounded(2, -1.0, 1.0)).gradient(&[1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_stencils() {
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        for &stencil in &[Stencil::Forward, Stencil::Forward2, Stencil::Central,
+                          Stencil::FivePoint] {
+            for analytical_problem in problems::all() {
+                let numerical_problem = NumericalDifferentiation::new(Func(|x: &[f64]| {
+                    analytical_problem.value(x)
+                })).stencil(stencil);
+
+                for _ in 0..100 {
+                    let position = analytical_problem.random_start_seeded(&mut rng);
+
+                    let analytical_gradient = analytical_problem.gradient(&position);
+                    let numerical_gradient = numerical_problem.gradient(&position);
+
+                    assert!(analytical_gradient.into_iter().zip(numerical_gradient).all(|(a, n)|
+                        are_close(a, n, 1.0e-3)
+                    ), "{:?} is inaccurate at {:?}", stencil, position);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_five_point_stencil() {
+        let rosenbrock = Rosenbrock::default();
+
+        // a large step width such that the truncation error dominates
+        let h = 1.0e-3;
+
+        for position in &[[-1.2, 1.0], [0.5, -0.3], [2.0, 3.0]] {
+            let value = rosenbrock.value(position);
+            let gradient = rosenbrock.gradient(position);
+
+            let mut x = position.to_vec();
+
+            // both are exact along the quadratic second coordinate, thus sum up the errors
+            let (central_error, five_point_error) = gradient.iter().enumerate()
+                .map(|(i, g)| {
+                    let central = Stencil::Central.derivative(&rosenbrock, &mut x, value, i, h);
+                    let five_point = Stencil::FivePoint.derivative(&rosenbrock, &mut x, value, i,
+                        h);
+
+                    ((central - g).abs(), (five_point - g).abs())
+                })
+                .fold((0.0, 0.0), |(c, f), (central, five_point)| (c + central, f + five_point));
+
+            assert_eq!(&x[..], &position[..]);
+            assert!(five_point_error < central_error, "{} vs. {}", five_point_error,
+                central_error);
+        }
+    }
+
+    #[test]
+    fn test_bounded_stencils() {
+        let sphere = Sphere::bounded(2, -1.0, 1.0);
+
+        for &stencil in &[Stencil::Forward2, Stencil::Central, Stencil::FivePoint] {
+            // evaluating an illegal position of a problem panics
+            let numerical = NumericalDifferentiation::bounded(sphere).stencil(stencil);
+
+            for x in &[[1.0, 0.5], [-1.0, 1.0], [0.0, -1.0]] {
+                for (n, a) in numerical.gradient(x).iter().zip(sphere.gradient(x)) {
+                    assert!((n - a).abs() < 1.0e-6, "{} differs from {} at {:?}", n, a, x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_partial_analytic() {
+        let rosenbrock = Rosenbrock::default();
+        let position = [-1.2, 1.0];
+
+        // all partial derivatives provided
+        let analytic = PartialAnalytic::new(Func(|x: &[f64]| rosenbrock.value(x)),
+            |x: &[f64]| rosenbrock.gradient(x).into_iter().map(Some).collect());
+
+        assert_eq!(analytic.gradient(&position), rosenbrock.gradient(&position));
+
+        // only the first partial derivative provided
+        let evaluations = Cell::new(0);
+        let counted = |x: &[f64]| {
+            evaluations.set(evaluations.get() + 1);
+            rosenbrock.value(x)
+        };
+
+        let partial = PartialAnalytic::new(Func(counted),
+            |x: &[f64]| vec![Some(rosenbrock.gradient(x)[0]), None]);
+        let numerical = NumericalDifferentiation::new(Func(counted));
+
+        let partial_gradient = partial.gradient(&position);
+        let partial_evaluations = evaluations.replace(0);
+
+        let numerical_gradient = numerical.gradient(&position);
+        let numerical_evaluations = evaluations.replace(0);
+
+        assert_eq!(partial_gradient[0], rosenbrock.gradient(&position)[0]);
+        assert_eq!(partial_gradient[1], numerical_gradient[1]);
+        assert!(partial_evaluations < numerical_evaluations,
+            "{} vs. {}", partial_evaluations, numerical_evaluations);
+    }
+
+    #[test]
+    fn test_spsa() {
+        let sphere = Sphere::new(20);
+        let start: Vec<_> = (0..20).map(|i| i as f64 / 10.0 - 1.0).collect();
+
+        let evaluations = Cell::new(0);
+        let counted = |x: &[f64]| {
+            evaluations.set(evaluations.get() + 1);
+            sphere.value(x)
+        };
+
+        let spsa = Spsa::new(Func(counted)).seed(42);
+
+        // the same seed yields the same estimate, which is reused at the same position
+        let gradient = spsa.gradient(&start);
+
+        assert_eq!(evaluations.replace(0), 2);
+        assert_eq!(Spsa::new(Func(counted)).seed(42).gradient(&start), gradient);
+        assert_eq!(spsa.gradient(&start), gradient);
+        assert_eq!(evaluations.replace(0), 2);
+
+        // the expected squared distance to the minimum shrinks by 1 - 1/n per step
+        let report = GradientDescent::new()
+            .line_search(FixedStepWidth::new(0.025))
+            .gradient_tolerance(1.0e-8)
+            .max_iterations(Some(1000))
+            .minimize(&spsa, start.clone());
+
+        assert!(report.value < 1.0e-6, "{} is not close to 0", report.value);
+        assert!(evaluations.get() <= 3 * (report.iterations + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "the value is not finite at the perturbed positions")]
+    fn test_spsa_not_finite() {
+        Spsa::new(Func(|x: &[f64]| x[0].ln())).gradient(&[0.0]);
+    }
 }
@@ -2,6 +2,10 @@
 //!
 //! Currently, the following [optimization test functions] are implemented.
 //!
+//! ## Many Local Minima
+//!
+//! * [`Rastrigin`](http://www.sfu.ca/~ssurjano/rastr.html)
+//!
 //! ## Bowl-Shaped
 //!
 //! * [`Sphere`](http://www.sfu.ca/~ssurjano/spheref.html)
@@ -9,18 +13,36 @@
 //! ## Valley-Shaped
 //!
 //! * [`Rosenbrock`](http://www.sfu.ca/~ssurjano/rosen.html)
+//! * [`RosenbrockN`](http://www.sfu.ca/~ssurjano/rosen.html)
+//!
+//! A `ProblemSuite` runs a minimizer on all of them, e.g., to benchmark it quickly.
 //!
 //! [optimization test functions]: http://www.sfu.ca/~ssurjano/optimization.html
 
-use rand::random;
-use std::f64::INFINITY;
+use rand::{Rng, RngCore, SeedableRng, thread_rng};
+use rand_pcg::Pcg64Mcg;
+use std::any::type_name;
+use std::f64::consts::PI;
 use std::ops::Add;
 
-use types::{Function, Function1};
+use types::{Function, Function1, HessianProduct, Minimizer, Evaluation};
 
 
 /// Specifies a well known optimization problem.
-pub trait Problem: Function + Default {
+///
+/// The trait is object-safe, i.e., different problems can be used uniformly as
+/// `Box<dyn Problem>`, see `all()`.
+pub trait Problem: Function1 {
+    /// Returns a human readable name of the problem, e.g., to tabulate benchmarks.
+    ///
+    /// By default, the name of the type without its path and generic parameters is used.
+    fn name(&self) -> String {
+        let name = type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
+
     /// Returns the dimensionality of the input domain.
     fn dimensions(&self) -> usize;
 
@@ -31,16 +53,172 @@ pub trait Problem: Function + Default {
     /// Returns the position as well as the value of the global minimum.
     fn minimum(&self) -> (Vec<f64>, f64);
 
+    /// Returns a characteristic scale of each coordinate, i.e., the distance along which the
+    /// function changes noticeably, e.g., to serve as a diagonal preconditioner or to choose
+    /// step widths for finite differences.
+    ///
+    /// By default, all coordinates share the scale `1`. Problems with very different
+    /// curvatures along the coordinates should override this method, e.g., by `1 / √Hᵢᵢ`
+    /// using the diagonal of the Hessian at the minimum.
+    fn typical_scale(&self) -> Vec<f64> {
+        vec![1.0; self.dimensions()]
+    }
+
     /// Generates a random and **feasible** position to start a minimization.
-    fn random_start(&self) -> Vec<f64>;
+    fn random_start(&self) -> Vec<f64> {
+        self.random_start_seeded(&mut thread_rng())
+    }
+
+    /// Generates a random and **feasible** position to start a minimization using the
+    /// supplied random number generator, e.g., a seeded one to create re-producable results.
+    fn random_start_seeded(&self, rng: &mut dyn RngCore) -> Vec<f64>;
 
-    /// Tests whether the supplied position is legal for this function.
+    /// Tests whether the supplied position is legal for this function, i.e., whether it is
+    /// finite and lies within the closed domain.
+    ///
+    /// Note that the bounds themselves are legal, e.g., to accept positions repaired by
+    /// clamping, while infinite coordinates are not, even on unbounded domains.
     fn is_legal_position(&self, position: &[f64]) -> bool {
         position.len() == self.dimensions() &&
         position.iter().zip(self.domain()).all(|(&x, (lower, upper))| {
-            lower < x && x < upper
+            x.is_finite() && lower <= x && x <= upper
         })
     }
+
+    /// Returns the distance of the supplied position to the boundary of the domain, i.e., the
+    /// minimum over all coordinates of the distance to the nearer bound, e.g., to cap the
+    /// step widths of a line search.
+    ///
+    /// The distance is `INFINITY` for an unbounded domain and negative for positions that
+    /// are not legal.
+    fn distance_to_boundary(&self, position: &[f64]) -> f64 {
+        position.iter().zip(self.domain())
+            .map(|(&x, (lower, upper))| (x - lower).min(upper - x))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Repairs the supplied position such that it becomes legal for this function.
+    ///
+    /// By default, each coordinate is clamped into its respective domain. Problems with
+    /// constraints other than a box should override this method.
+    fn repair(&self, position: &mut Vec<f64>) {
+        for (x, (lower, upper)) in position.iter_mut().zip(self.domain()) {
+            *x = x.clamp(lower, upper);
+        }
+    }
+}
+
+
+/// A function whose global minimum value is known analytically, although its location
+/// might not be, e.g., to serve as `target_value` of a minimizer in benchmarks.
+///
+/// Every `Problem` knows its minimum value, thus the trait is implemented for all of them.
+pub trait HasMinimumValue {
+    /// Returns the value of the global minimum.
+    fn minimum_value(&self) -> f64;
+}
+
+impl<P: Problem + ?Sized> HasMinimumValue for P {
+    fn minimum_value(&self) -> f64 {
+        self.minimum().1
+    }
+}
+
+
+/// A `Problem` that can be created using its default parameters.
+///
+/// In contrast to `Problem`, this trait is not object-safe and thus only used as a bound
+/// where a problem has to be instantiated generically.
+pub trait ProblemDefault: Problem + Default {}
+
+impl<P: Problem + Default> ProblemDefault for P {}
+
+
+/// Returns every bundled problem using its default parameters, e.g., to benchmark or test
+/// a minimizer on all of them.
+pub fn all() -> Vec<Box<dyn Problem>> {
+    vec![Box::new(Sphere::default()), Box::new(Rosenbrock::default()),
+         Box::new(RosenbrockN::default()), Box::new(Rastrigin::default())]
+}
+
+
+/// Runs a minimizer on a set of problems from several seeded random starts, e.g., to
+/// benchmark it quickly on all bundled problems.
+pub struct ProblemSuite {
+    problems: Vec<Box<dyn Problem>>,
+    starts: usize,
+    seed: u64
+}
+
+impl ProblemSuite {
+    /// Creates a new `ProblemSuite` using the following defaults:
+    ///
+    /// - **`problems`** = `all()`
+    /// - **`starts`** = `10`
+    /// - **`seed`** = `42`
+    pub fn new() -> ProblemSuite {
+        ProblemSuite {
+            problems: all(),
+            starts: 10,
+            seed: 42
+        }
+    }
+
+    /// Replaces the problems to run the minimizer on, e.g., to exclude the ones it is not
+    /// applicable to.
+    pub fn problems(mut self, problems: Vec<Box<dyn Problem>>) -> Self {
+        self.problems = problems;
+        self
+    }
+
+    /// Adjusts the number of random starts per problem.
+    pub fn starts(mut self, starts: usize) -> Self {
+        assert!(starts > 0);
+
+        self.starts = starts;
+        self
+    }
+
+    /// Seeds the random number generator drawing the starts using the supplied `seed`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Runs the `minimizer` from each start of each problem and returns one row per problem,
+    /// consisting of its name, the worst final value as well as the maximal number of
+    /// iterations over all starts, if reported by the solutions.
+    pub fn run<M: Minimizer<dyn Problem>>(&self, minimizer: &M)
+        -> Vec<(String, f64, Option<u64>)>
+    {
+        let mut rng = Pcg64Mcg::seed_from_u64(self.seed);
+
+        self.problems.iter().map(|problem| {
+            let mut worst_value = f64::NEG_INFINITY;
+            let mut max_iterations = None;
+
+            for _ in 0..self.starts {
+                let start = problem.random_start_seeded(&mut rng);
+                let solution = minimizer.minimize(&**problem, start);
+
+                // NaN values are reported as the worst ones
+                worst_value = if worst_value.is_nan() || solution.value().is_nan() {
+                    f64::NAN
+                } else {
+                    worst_value.max(solution.value())
+                };
+                max_iterations = max_iterations.max(solution.iterations());
+            }
+
+            (problem.name(), worst_value, max_iterations)
+        }).collect()
+    }
+}
+
+impl Default for ProblemSuite {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 
@@ -51,9 +229,11 @@ macro_rules! define_problem {
         domain: $domain:expr,
         minimum: $miny:expr,
         at: $minx:expr,
-        start: $start:expr,
+        start: $rng:ident => $start:expr,
         value: $x1:ident => $value:expr,
-        gradient: $x2:ident => $gradient:expr ) =>
+        gradient: $x2:ident => $gradient:expr,
+        convex: $convex:expr
+        $(, typical_scale: $scale:expr)? ) =>
     {
         impl Default for $name {
             fn default() -> Self {
@@ -67,6 +247,10 @@ macro_rules! define_problem {
 
                 $value
             }
+
+            fn is_convex(&$this) -> Option<bool> {
+                Some($convex)
+            }
         }
 
         impl Function1 for $name {
@@ -77,6 +261,8 @@ macro_rules! define_problem {
             }
         }
 
+        impl_add!{[] $name}
+
         impl Problem for $name {
             fn dimensions(&$this) -> usize {
                 $dims
@@ -90,9 +276,15 @@ macro_rules! define_problem {
                 ($minx, $miny)
             }
 
-            fn random_start(&$this) -> Vec<f64> {
+            fn random_start_seeded(&$this, $rng: &mut dyn RngCore) -> Vec<f64> {
                 $start
             }
+
+            $(
+            fn typical_scale(&$this) -> Vec<f64> {
+                $scale
+            }
+            )?
         }
     };
 }
@@ -105,30 +297,60 @@ macro_rules! define_problem {
 /// > f(x) = ∑ᵢ xᵢ²
 ///
 /// *Global minimum*: `f(0,...,0) = 0`
+///
+/// Optionally, each coordinate is restricted to the same interval, in which case the
+/// global minimum is located at the closest point to the origin within that box.
 #[derive(Debug, Copy, Clone)]
 pub struct Sphere {
-    dimensions: usize
+    dimensions: usize,
+    lower: f64,
+    upper: f64
 }
 
 impl Sphere {
+    /// Creates a new unbounded `Sphere` function with the given number of `dimensions`.
     pub fn new(dimensions: usize) -> Sphere {
-        assert!(dimensions > 0, "dimensions must be larger than 1");
+        Sphere::bounded(dimensions, -f64::INFINITY, f64::INFINITY)
+    }
+
+    /// Creates a new `Sphere` function whose coordinates are restricted to the closed
+    /// interval `[lower, upper]`.
+    pub fn bounded(dimensions: usize, lower: f64, upper: f64) -> Sphere {
+        assert!(dimensions > 0, "dimensions must be larger than 0");
+        assert!(lower <= upper, "lower must not be larger than upper");
 
         Sphere {
-            dimensions
+            dimensions,
+            lower,
+            upper
         }
     }
+
+    fn minimizer(&self) -> f64 {
+        0.0f64.clamp(self.lower, self.upper)
+    }
 }
 
 define_problem!{Sphere: self,
     default: Sphere::new(2),
     dimensions: self.dimensions,
-    domain: (0..self.dimensions).map(|_| (-INFINITY, INFINITY)).collect(),
-    minimum: 0.0,
-    at: (0..self.dimensions).map(|_| 0.0).collect(),
-    start: (0..self.dimensions).map(|_| random::<f64>() * 10.24 - 5.12).collect(),
+    domain: (0..self.dimensions).map(|_| (self.lower, self.upper)).collect(),
+    minimum: self.minimizer().powi(2) * self.dimensions as f64,
+    at: (0..self.dimensions).map(|_| self.minimizer()).collect(),
+    start: rng => (0..self.dimensions)
+        .map(|_| (rng.gen::<f64>() * 10.24 - 5.12).clamp(self.lower, self.upper))
+        .collect(),
     value: x => x.iter().map(|x| x.powi(2)).fold(0.0, Add::add),
-    gradient: x => x.iter().map(|x| 2.0 * x).collect()
+    gradient: x => x.iter().map(|x| 2.0 * x).collect(),
+    convex: true
+}
+
+impl HessianProduct for Sphere {
+    fn hessian_product(&self, position: &[f64], direction: &[f64]) -> Vec<f64> {
+        assert!(self.is_legal_position(position));
+
+        direction.iter().map(|v| 2.0 * v).collect()
+    }
 }
 
 
@@ -160,16 +382,144 @@ impl Rosenbrock {
 define_problem!{Rosenbrock: self,
     default: Rosenbrock::new(1.0, 100.0),
     dimensions: 2,
-    domain: vec![(-INFINITY, INFINITY), (-INFINITY, INFINITY)],
+    domain: vec![(-f64::INFINITY, f64::INFINITY), (-f64::INFINITY, f64::INFINITY)],
     minimum: 0.0,
     at: vec![self.a, self.a * self.a],
-    start: (0..2).map(|_| random::<f64>() * 4.096 - 2.048).collect(),
+    start: rng => (0..2).map(|_| rng.gen::<f64>() * 4.096 - 2.048).collect(),
     value: x => (self.a - x[0]).powi(2) + self.b * (x[1] - x[0].powi(2)).powi(2),
     gradient: x => vec![-2.0 * self.a + 4.0 * self.b * x[0].powi(3) - 4.0 * self.b * x[0] * x[1] + 2.0 * x[0],
-                        2.0 * self.b * (x[1] - x[0].powi(2))]
+                        2.0 * self.b * (x[1] - x[0].powi(2))],
+    convex: false,
+    // the inverse square root of the Hessian's diagonal at the minimum
+    typical_scale: vec![1.0 / (2.0 + 8.0 * self.b * self.a.powi(2)).sqrt(),
+                        1.0 / (2.0 * self.b).sqrt()]
+}
+
+impl HessianProduct for Rosenbrock {
+    fn hessian_product(&self, x: &[f64], v: &[f64]) -> Vec<f64> {
+        assert!(self.is_legal_position(x));
+
+        let xx = 2.0 - 4.0 * self.b * x[1] + 12.0 * self.b * x[0].powi(2);
+        let xy = -4.0 * self.b * x[0];
+
+        vec![xx * v[0] + xy * v[1], xy * v[0] + 2.0 * self.b * v[1]]
+    }
 }
 
 
+/// n-dimensional Rosenbrock function.
+///
+/// The common generalization of `Rosenbrock` to more than two dimensions, coupling each
+/// pair of consecutive coordinates:
+///
+/// > f(x) = ∑ᵢ 100 (xᵢ₊₁ - xᵢ²)² + (1 - xᵢ)²
+///
+/// *Global minimum*: `f(1,...,1) = 0`
+#[derive(Debug, Copy, Clone)]
+pub struct RosenbrockN {
+    dimensions: usize
+}
+
+impl RosenbrockN {
+    /// Creates a new `RosenbrockN` function with the given number of `dimensions`.
+    pub fn new(dimensions: usize) -> RosenbrockN {
+        assert!(dimensions > 1, "dimensions must be larger than 1");
+
+        RosenbrockN {
+            dimensions
+        }
+    }
+}
+
+define_problem!{RosenbrockN: self,
+    default: RosenbrockN::new(2),
+    dimensions: self.dimensions,
+    domain: (0..self.dimensions).map(|_| (-f64::INFINITY, f64::INFINITY)).collect(),
+    minimum: 0.0,
+    at: vec![1.0; self.dimensions],
+    start: rng => (0..self.dimensions).map(|_| rng.gen::<f64>() * 4.096 - 2.048).collect(),
+    value: x => x.windows(2)
+        .map(|w| 100.0 * (w[1] - w[0].powi(2)).powi(2) + (1.0 - w[0]).powi(2))
+        .fold(0.0, Add::add),
+    gradient: x => {
+        let mut gradient = vec![0.0; x.len()];
+
+        for i in 0..x.len() - 1 {
+            let valley = x[i + 1] - x[i].powi(2);
+
+            gradient[i] += -400.0 * x[i] * valley - 2.0 * (1.0 - x[i]);
+            gradient[i + 1] += 200.0 * valley;
+        }
+
+        gradient
+    },
+    convex: false,
+    // the inverse square root of the Hessian's diagonal at the minimum
+    typical_scale: (0..self.dimensions).map(|i| {
+        let first: f64 = if i + 1 < self.dimensions { 802.0 } else { 0.0 };
+        let second = if i > 0 { 200.0 } else { 0.0 };
+
+        1.0 / (first + second).sqrt()
+    }).collect()
+}
+
+impl HessianProduct for RosenbrockN {
+    fn hessian_product(&self, x: &[f64], v: &[f64]) -> Vec<f64> {
+        assert!(self.is_legal_position(x));
+
+        let mut product = vec![0.0; x.len()];
+
+        for i in 0..x.len() - 1 {
+            let diagonal = 2.0 - 400.0 * (x[i + 1] - x[i].powi(2)) + 800.0 * x[i].powi(2);
+            let off_diagonal = -400.0 * x[i];
+
+            product[i] += diagonal * v[i] + off_diagonal * v[i + 1];
+            product[i + 1] += off_diagonal * v[i] + 200.0 * v[i + 1];
+        }
+
+        product
+    }
+}
+
+
+/// n-dimensional Rastrigin function.
+///
+/// A non-convex function with a regular lattice of local minima, which traps local
+/// minimizers easily:
+///
+/// > f(x) = 10 n + ∑ᵢ xᵢ² - 10 cos(2π xᵢ)
+///
+/// *Global minimum*: `f(0,...,0) = 0`
+#[derive(Debug, Copy, Clone)]
+pub struct Rastrigin {
+    dimensions: usize
+}
+
+impl Rastrigin {
+    /// Creates a new `Rastrigin` function with the given number of `dimensions`.
+    pub fn new(dimensions: usize) -> Rastrigin {
+        assert!(dimensions > 0, "dimensions must be larger than 0");
+
+        Rastrigin {
+            dimensions
+        }
+    }
+}
+
+define_problem!{Rastrigin: self,
+    default: Rastrigin::new(2),
+    dimensions: self.dimensions,
+    domain: (0..self.dimensions).map(|_| (-f64::INFINITY, f64::INFINITY)).collect(),
+    minimum: 0.0,
+    at: vec![0.0; self.dimensions],
+    start: rng => (0..self.dimensions).map(|_| rng.gen::<f64>() * 10.24 - 5.12).collect(),
+    value: x => x.iter()
+        .map(|x| x.powi(2) - 10.0 * (2.0 * PI * x).cos())
+        .fold(10.0 * x.len() as f64, Add::add),
+    gradient: x => x.iter().map(|x| 2.0 * x + 20.0 * PI * (2.0 * PI * x).sin()).collect(),
+    convex: false
+}
+
 /*
 pub struct McCormick;
 
@@ -182,17 +532,85 @@ impl McCormick {
 define_problem!{McCormick: self,
     default: McCormick::new(),
     dimensions: 2,
-    domain: vec![(-INFINITY, INFINITY), (-INFINITY, INFINITY)],
+    domain: vec![(-f64::INFINITY, f64::INFINITY), (-f64::INFINITY, f64::INFINITY)],
     minimum: -1.9133,
     at: vec![-0.54719, -1.54719],
-    start: vec![random::<f64>() * 5.5 - 1.5, random::<f64>() * 7.0 - 3.0],
+    start: rng => vec![rng.gen::<f64>() * 5.5 - 1.5, rng.gen::<f64>() * 7.0 - 3.0],
     value: x => (x[0] + x[1]).sin() + (x[0] - x[1]).powi(2) - 1.5 * x[0] + 2.5 * x[1] + 1.0,
     gradient: x => vec![(x[0] + x[1]).cos() + 2.0 * (x[0] - x[1]) - 1.5,
-                        (x[0] + x[1]).cos() - 2.0 * (x[0] - x[1]) + 2.5]
+                        (x[0] + x[1]).cos() - 2.0 * (x[0] - x[1]) + 2.5],
+    convex: false
 }
 */
 
 
+/// Sum squared error of a linear regression model over a set of noisy observations, as
+/// used in the `line_fitting` example.
+#[cfg(test)]
+pub struct LinearRegression {
+    observations: Vec<(Vec<f64>, f64)>
+}
+
+#[cfg(test)]
+impl LinearRegression {
+    /// Creates a new regression problem given the true `coefficients` of the linear model,
+    /// i.e., `y = w₀ + w₁ x₁ + ...`, by drawing `samples` noisy observations using `seed`.
+    pub fn new(coefficients: &[f64], samples: usize, seed: u64) -> LinearRegression {
+        use rand::{Rng, SeedableRng};
+        use rand_pcg::Pcg64Mcg;
+
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
+
+        let observations = (0..samples).map(|_| {
+            let x: Vec<f64> = (1..coefficients.len()).map(|_| rng.gen()).collect();
+            let noise = rng.gen::<f64>() - 0.5;
+
+            let y = LinearRegression::model(coefficients, &x) + noise;
+
+            (x, y)
+        }).collect();
+
+        LinearRegression {
+            observations
+        }
+    }
+
+    fn model(w: &[f64], x: &[f64]) -> f64 {
+        w[0] + w[1..].iter().zip(x).map(|(w, x)| w * x).fold(0.0, Add::add)
+    }
+}
+
+#[cfg(test)]
+impl ::types::Summation for LinearRegression {
+    fn terms(&self) -> usize {
+        self.observations.len()
+    }
+
+    fn term_value(&self, w: &[f64], i: usize) -> f64 {
+        let (ref x, y) = self.observations[i];
+
+        0.5 * (y - LinearRegression::model(w, x)).powi(2)
+    }
+}
+
+#[cfg(test)]
+impl ::types::Summation1 for LinearRegression {
+    fn term_gradient(&self, w: &[f64], i: usize) -> Vec<f64> {
+        let (ref x, y) = self.observations[i];
+
+        let e = y - LinearRegression::model(w, x);
+
+        let mut gradient = vec![-e];
+
+        for x in x {
+            gradient.push(-e * x);
+        }
+
+        gradient
+    }
+}
+
+
 #[cfg(test)]
 macro_rules! test_minimizer {
     ( $minimizer:expr, $( $name:ident => $problem:expr ),* ) => {
@@ -202,8 +620,11 @@ macro_rules! test_minimizer {
                 let minimizer = $minimizer;
                 let problem = $problem;
 
+                let mut rng = <::rand_pcg::Pcg64Mcg as ::rand::SeedableRng>::seed_from_u64(42);
+
                 for _ in 0..100 {
-                    let position = $crate::problems::Problem::random_start(&problem);
+                    let position = $crate::problems::Problem::random_start_seeded(&problem,
+                        &mut rng);
 
                     let solution = $crate::Minimizer::minimize(&minimizer,
                         &problem, position);
@@ -220,3 +641,298 @@ macro_rules! test_minimizer {
         )*
     };
 }
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    use numeric::NumericalDifferentiation;
+    use types::{Minimizer, Convergence, Func, Func1};
+    use gd::GradientDescent;
+    use adapters::Affine;
+    use line_search::FixedStepWidth;
+
+    use types::{Function, Function1, HessianProduct};
+
+    use super::{Problem, ProblemDefault, HasMinimumValue, ProblemSuite, Sphere, Rosenbrock,
+        RosenbrockN, all};
+
+    fn boxed<P: ProblemDefault + 'static>() -> Box<dyn Problem> {
+        Box::new(P::default())
+    }
+
+    #[test]
+    fn test_heterogeneous_problems() {
+        let problems: Vec<Box<dyn Problem>> = vec![
+            boxed::<Sphere>(),
+            boxed::<Rosenbrock>(),
+            Box::new(Sphere::new(5)),
+            Box::new(Sphere::bounded(3, -1.0, 1.0)),
+            Box::new(Rosenbrock::new(2.0, 50.0)),
+            Box::new(RosenbrockN::new(3)),
+            Box::new(NumericalDifferentiation::new(Rosenbrock::default()))
+        ];
+
+        let minimizer = GradientDescent::new();
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        for problem in &problems {
+            let start = problem.random_start_seeded(&mut rng);
+
+            assert!(problem.is_legal_position(&start));
+            assert_eq!(problem.gradient(&start).len(), problem.dimensions());
+
+            let report = minimizer.minimize(&**problem, start);
+
+            let distance = report.position.iter().zip(problem.minimum().0)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+
+            assert!(distance < 1.0e-2, "{:?} is not close to {:?}", report.position,
+                problem.minimum().0);
+        }
+    }
+
+    #[test]
+    fn test_all() {
+        let problems = all();
+
+        assert!(!problems.is_empty());
+
+        for problem in problems {
+            let (position, value) = problem.minimum();
+
+            assert!(problem.is_legal_position(&position));
+            assert_eq!(problem.value(&position), value);
+        }
+    }
+
+    #[test]
+    fn test_is_convex() {
+        let convexity: Vec<_> = all().iter().map(|problem| problem.is_convex()).collect();
+
+        assert_eq!(convexity, vec![Some(true), Some(false), Some(false), Some(false)]);
+        assert_eq!(Sphere::bounded(3, 1.0, 3.0).is_convex(), Some(true));
+        assert_eq!(Rosenbrock::new(2.0, 50.0).is_convex(), Some(false));
+
+        // wrappers retain the property as far as it is known
+        assert_eq!(NumericalDifferentiation::new(Rosenbrock::default()).is_convex(), Some(false));
+        assert_eq!((Sphere::default() + Sphere::default()).is_convex(), Some(true));
+        assert_eq!((Sphere::default() + Rosenbrock::default()).is_convex(), None);
+        assert_eq!(Affine::new(Sphere::default(), -1.0, 0.0).is_convex(), None);
+        assert_eq!(Func(|x: &[f64]| x[0]).is_convex(), None);
+    }
+
+    #[test]
+    fn test_problem_suite() {
+        let rows = ProblemSuite::new().run(&GradientDescent::new());
+
+        let names: Vec<_> = all().iter().map(|problem| problem.name()).collect();
+
+        assert_eq!(rows.iter().map(|row| row.0.clone()).collect::<Vec<_>>(), names);
+        assert_eq!(names[..2], ["Sphere".to_string(), "Rosenbrock".to_string()]);
+
+        for (name, value, iterations) in &rows {
+            assert!(value.is_finite() && *value >= 0.0, "{}: {}", name, value);
+            assert!(iterations.is_some_and(|iterations| iterations > 0), "{}", name);
+        }
+
+        // the unimodal problems are solved from every start
+        assert!(rows[0].1 < 1.0e-6 && rows[1].1 < 1.0e-4, "{:?}", rows);
+
+        // a single row per problem is returned regardless of the number of starts
+        let rows = ProblemSuite::new()
+            .problems(vec![Box::new(Sphere::new(5))])
+            .starts(3)
+            .run(&GradientDescent::new());
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].0, "Sphere");
+    }
+
+    // asserts that the scales correspond to the diagonal of the Hessian at the minimum
+    fn assert_curvature_scale<P: Problem + HessianProduct>(problem: &P) {
+        let minimum = problem.minimum().0;
+
+        for (i, scale) in problem.typical_scale().into_iter().enumerate() {
+            let mut unit = vec![0.0; problem.dimensions()];
+            unit[i] = 1.0;
+
+            let curvature = problem.hessian_product(&minimum, &unit)[i];
+
+            assert!((scale - 1.0 / curvature.sqrt()).abs() < 1.0e-12, "{} at {}", scale, i);
+        }
+    }
+
+    #[test]
+    fn test_typical_scale() {
+        assert_eq!(Sphere::new(3).typical_scale(), vec![1.0; 3]);
+
+        assert_curvature_scale(&Rosenbrock::default());
+        assert_curvature_scale(&Rosenbrock::new(2.0, 10.0));
+        assert_curvature_scale(&RosenbrockN::new(4));
+
+        assert_eq!(RosenbrockN::new(2).typical_scale(), Rosenbrock::default().typical_scale());
+    }
+
+    #[test]
+    fn test_is_legal_position() {
+        let problem = Sphere::bounded(2, -1.0, 3.0);
+
+        assert!(problem.is_legal_position(&[-1.0, 3.0]));
+        assert!(!problem.is_legal_position(&[-1.5, 0.0]));
+        assert!(!problem.is_legal_position(&[0.0, 0.0, 0.0]));
+
+        assert!(Sphere::default().is_legal_position(&[1.0e300, -2.0]));
+        assert!(!Sphere::default().is_legal_position(&[f64::INFINITY, 0.0]));
+        assert!(!Sphere::default().is_legal_position(&[f64::NAN, 0.0]));
+    }
+
+    #[test]
+    fn test_distance_to_boundary() {
+        let problem = Sphere::bounded(3, -1.0, 3.0);
+
+        assert_eq!(problem.distance_to_boundary(&[0.0, 2.5, 1.0]), 0.5);
+        assert_eq!(problem.distance_to_boundary(&[-1.0, 0.0, 1.0]), 0.0);
+        assert_eq!(problem.distance_to_boundary(&[0.0, 4.0, 1.0]), -1.0);
+
+        assert_eq!(Sphere::default().distance_to_boundary(&[1.0e300, -2.0]), f64::INFINITY);
+        assert_eq!(Rosenbrock::default().distance_to_boundary(&[-1.2, 1.0]), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_typical_scale_preconditioner() {
+        let rosenbrock = Rosenbrock::default();
+        let scale = rosenbrock.typical_scale();
+
+        // substitutes x = s ⊙ z, such that the curvature along each coordinate becomes 1
+        let scaled = |z: &[f64]| z.iter().zip(&scale).map(|(z, s)| z * s).collect::<Vec<_>>();
+        let preconditioned = Func1(|z: &[f64]| rosenbrock.value(&scaled(z)), |z: &[f64]| {
+            rosenbrock.gradient(&scaled(z)).iter().zip(&scale).map(|(g, s)| g * s).collect()
+        });
+
+        // the step width is half the inverse of the largest curvature along a coordinate
+        let minimize = |function: &dyn Function1, step_width: f64, start: Vec<f64>| {
+            GradientDescent::new()
+                .line_search(FixedStepWidth::new(step_width))
+                .target_value(Some(1.0e-8))
+                .gradient_tolerance(1.0e-12)
+                .minimize(function, start)
+        };
+
+        let start = vec![-1.2, 1.0];
+
+        let plain = minimize(&rosenbrock, 0.5 / 802.0, start.clone());
+        let scaled_start = start.iter().zip(&scale).map(|(x, s)| x / s).collect();
+        let preconditioned = minimize(&preconditioned, 0.5, scaled_start);
+
+        assert_eq!(plain.convergence, Convergence::TargetValue);
+        assert_eq!(preconditioned.convergence, Convergence::TargetValue);
+
+        assert!(preconditioned.iterations < plain.iterations, "{} vs. {}",
+            preconditioned.iterations, plain.iterations);
+    }
+
+    #[test]
+    fn test_hessian_product() {
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        let problems: Vec<Box<dyn HessianProduct>> = vec![
+            Box::new(Sphere::new(4)),
+            Box::new(Rosenbrock::new(2.0, 50.0)),
+            Box::new(RosenbrockN::new(5))
+        ];
+
+        for (problem, dimensions) in problems.iter().zip(&[4, 2, 5]) {
+            let position = Sphere::new(*dimensions).random_start_seeded(&mut rng);
+            let direction = Sphere::new(*dimensions).random_start_seeded(&mut rng);
+
+            // central differences of the gradient along the direction
+            let h = 1.0e-6;
+            let forward: Vec<_> = position.iter().zip(&direction).map(|(x, v)| x + h * v).collect();
+            let backward: Vec<_> = position.iter().zip(&direction).map(|(x, v)| x - h * v).collect();
+
+            let product = problem.hessian_product(&position, &direction);
+
+            for ((p, f), b) in product.iter().zip(problem.gradient(&forward))
+                .zip(problem.gradient(&backward))
+            {
+                let approximation = (f - b) / (2.0 * h);
+
+                assert!((p - approximation).abs() < 1.0e-4 * p.abs().max(1.0),
+                    "{} != {}", p, approximation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimum_value() {
+        assert_eq!(Sphere::new(3).minimum_value(), 0.0);
+        assert_eq!(Sphere::bounded(2, 1.0, 2.0).minimum_value(), 2.0);
+        assert_eq!(Rosenbrock::default().minimum_value(), 0.0);
+        assert_eq!(Rosenbrock::new(2.0, 50.0).minimum_value(), 0.0);
+
+        for problem in all() {
+            assert_eq!(problem.minimum_value(), problem.minimum().1);
+        }
+    }
+
+    #[test]
+    fn test_random_start_seeded() {
+        let sphere = Sphere::new(5);
+        let rosenbrock = Rosenbrock::default();
+
+        assert_eq!(sphere.random_start_seeded(&mut Pcg64Mcg::seed_from_u64(42)),
+                   sphere.random_start_seeded(&mut Pcg64Mcg::seed_from_u64(42)));
+        assert_eq!(rosenbrock.random_start_seeded(&mut Pcg64Mcg::seed_from_u64(42)),
+                   rosenbrock.random_start_seeded(&mut Pcg64Mcg::seed_from_u64(42)));
+
+        assert_ne!(sphere.random_start_seeded(&mut Pcg64Mcg::seed_from_u64(42)),
+                   sphere.random_start_seeded(&mut Pcg64Mcg::seed_from_u64(43)));
+    }
+
+    #[test]
+    fn test_rosenbrock_n_gradient() {
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        for &dimensions in &[2, 5, 10] {
+            let problem = RosenbrockN::new(dimensions);
+            let numeric = NumericalDifferentiation::new(problem);
+
+            for _ in 0..10 {
+                let position = problem.random_start_seeded(&mut rng);
+
+                let analytic = problem.gradient(&position);
+                let approximation = numeric.gradient(&position);
+
+                for (a, b) in analytic.iter().zip(approximation) {
+                    assert!((a - b).abs() < 1.0e-4 * a.abs().max(1.0), "{} != {}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rosenbrock_n_matches_rosenbrock() {
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        let rosenbrock = Rosenbrock::default();
+        let rosenbrock_n = RosenbrockN::new(2);
+
+        for _ in 0..10 {
+            let position = rosenbrock.random_start_seeded(&mut rng);
+
+            assert!((rosenbrock.value(&position) - rosenbrock_n.value(&position)).abs() < 1.0e-9);
+            assert_eq!(rosenbrock.gradient(&position).len(), 2);
+
+            for (a, b) in rosenbrock.gradient(&position).iter()
+                .zip(rosenbrock_n.gradient(&position))
+            {
+                assert!((a - b).abs() < 1.0e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Minimization of scalar functions `f: ℝ → ℝ`, e.g., to implement custom line searches.
+
+
+/// The inverse golden ratio `(√5 - 1) / 2`.
+const INVERSE_GOLDEN_RATIO: f64 = 0.618_033_988_749_894_9;
+
+
+/// Minimizes the scalar function `f` within the `bracket` `(a, b, c)` using the golden
+/// section search and returns the minimizing argument `t` along with its value `f(t)`.
+///
+/// The bracket has to satisfy `a < b < c` (or `a > b > c`) with `f(b) < f(a)` and
+/// `f(b) < f(c)`, which guarantees that it contains a local minimum. In each iteration,
+/// the bracket is shrunk by the factor `0.618` using a single evaluation of `f`, until its
+/// width drops below the `tolerance`.
+///
+/// For smooth functions, `brent()` usually requires far fewer evaluations.
+///
+/// # Examples
+///
+/// ```
+/// # use optimization::scalar::minimize_1d;
+/// let (t, value) = minimize_1d(|t: f64| (t - 2.0).powi(2), (0.0, 1.0, 5.0), 1.0e-8);
+///
+/// assert!((t - 2.0).abs() < 1.0e-8 && value < 1.0e-15);
+/// ```
+pub fn minimize_1d<F: Fn(f64) -> f64>(f: F, bracket: (f64, f64, f64), tolerance: f64)
+    -> (f64, f64)
+{
+    let (a, b, c) = bracket;
+
+    assert!(tolerance > 0.0 && tolerance.is_finite(), "tolerance must be > 0 and finite");
+    assert!((a < b && b < c) || (a > b && b > c), "b must lie in between a and c");
+
+    let mut value = f(b);
+
+    assert!(value <= f(a) && value <= f(c), "bracket must contain a minimum");
+
+    // maintain the outer bounds and the best argument, which is located in the larger segment
+    let (mut lower, mut upper) = if a < c { (a, c) } else { (c, a) };
+    let mut t = b;
+
+    while upper - lower > tolerance {
+        // probe the larger of both segments
+        let probe = if upper - t > t - lower {
+            t + (1.0 - INVERSE_GOLDEN_RATIO) * (upper - t)
+        } else {
+            t - (1.0 - INVERSE_GOLDEN_RATIO) * (t - lower)
+        };
+
+        let probe_value = f(probe);
+
+        if probe_value < value {
+            // the probe becomes the new best argument, shrink towards it
+            if probe > t {
+                lower = t;
+            } else {
+                upper = t;
+            }
+
+            t = probe;
+            value = probe_value;
+        } else if probe > t {
+            upper = probe;
+        } else {
+            lower = probe;
+        }
+    }
+
+    (t, value)
+}
+
+
+/// Minimizes the scalar function `f` within the `bracket` `(a, b, c)` using Brent's method
+/// and returns the minimizing argument `t` along with its value `f(t)`.
+///
+/// The bracket has to satisfy the same conditions as for `minimize_1d()`. In contrast to
+/// the plain golden section search, a parabola is fitted through the three best arguments
+/// so far and its minimum is probed whenever it is located safely inside the bracket,
+/// falling back to a golden section step otherwise. Thus, the convergence is superlinear
+/// near smooth minima while retaining the robustness of the golden section search.
+///
+/// The minimization stops as soon as `t` is guaranteed to be within the `tolerance` of the
+/// minimum, or after `max_iterations`, i.e., evaluations of `f` besides the initial three.
+/// Note that, since `f` is flat near a smooth minimum, a `tolerance` below about `√ε |t|`
+/// cannot be achieved reliably in floating point arithmetic.
+///
+/// # Examples
+///
+/// ```
+/// # use optimization::scalar::brent;
+/// let (t, value) = brent(|t: f64| (t - 2.0).powi(2), (0.0, 1.0, 5.0), 1.0e-8, 100);
+///
+/// assert!((t - 2.0).abs() < 1.0e-8 && value < 1.0e-15);
+/// ```
+pub fn brent<F: Fn(f64) -> f64>(f: F, bracket: (f64, f64, f64), tolerance: f64,
+                                max_iterations: u64) -> (f64, f64)
+{
+    let (a, b, c) = bracket;
+
+    assert!(tolerance > 0.0 && tolerance.is_finite(), "tolerance must be > 0 and finite");
+    assert!((a < b && b < c) || (a > b && b > c), "b must lie in between a and c");
+
+    let (mut lower, mut upper) = if a < c { (a, c) } else { (c, a) };
+
+    // the best, second best and previous second best argument, respectively
+    let (mut x, mut w, mut v) = (b, b, b);
+    let mut fx = f(b);
+    let (mut fw, mut fv) = (fx, fx);
+
+    assert!(fx <= f(a) && fx <= f(c), "bracket must contain a minimum");
+
+    // the current and the second to last step
+    let mut step: f64 = 0.0;
+    let mut previous_step: f64 = 0.0;
+
+    for _ in 0..max_iterations {
+        let middle = 0.5 * (lower + upper);
+        let tol = 0.5 * tolerance + f64::EPSILON * x.abs();
+
+        // both bounds are within the tolerance of x
+        if (x - middle).abs() <= 2.0 * tol - 0.5 * (upper - lower) {
+            break;
+        }
+
+        let mut golden = true;
+
+        if previous_step.abs() > tol {
+            // fit a parabola through x, w and v, whose minimum is located at x + p / q
+            let r = (x - w) * (fx - fv);
+            let q = (x - v) * (fx - fw);
+            let mut p = (x - v) * q - (x - w) * r;
+            let mut q = 2.0 * (q - r);
+
+            if q > 0.0 {
+                p = -p;
+            }
+            q = q.abs();
+
+            // only accept the parabolic step if it lies inside the bracket and is smaller
+            // than half the second to last step, which ensures the convergence
+            if p.abs() < (0.5 * q * previous_step).abs() && p > q * (lower - x) &&
+                p < q * (upper - x)
+            {
+                previous_step = step;
+                step = p / q;
+                golden = false;
+
+                // do not evaluate f too close to the bounds
+                let u = x + step;
+                if u - lower < 2.0 * tol || upper - u < 2.0 * tol {
+                    step = tol.copysign(middle - x);
+                }
+            }
+        }
+
+        if golden {
+            previous_step = if x >= middle { lower - x } else { upper - x };
+            step = (1.0 - INVERSE_GOLDEN_RATIO) * previous_step;
+        }
+
+        // do not evaluate f too close to x
+        let u = if step.abs() >= tol { x + step } else { x + tol.copysign(step) };
+        let fu = f(u);
+
+        if fu <= fx {
+            if u >= x {
+                lower = x;
+            } else {
+                upper = x;
+            }
+
+            v = w;
+            fv = fw;
+            w = x;
+            fw = fx;
+            x = u;
+            fx = fu;
+        } else {
+            if u < x {
+                lower = u;
+            } else {
+                upper = u;
+            }
+
+            if fu <= fw || w == x {
+                v = w;
+                fv = fw;
+                w = u;
+                fw = fu;
+            } else if fu <= fv || v == x || v == w {
+                v = u;
+                fv = fu;
+            }
+        }
+    }
+
+    (x, fx)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::{minimize_1d, brent};
+
+    #[test]
+    fn test_minimize_1d() {
+        let (t, value) = minimize_1d(|t: f64| (t - 2.0).powi(2), (-3.0, 0.0, 10.0), 1.0e-10);
+
+        assert!((t - 2.0).abs() < 1.0e-9, "{} is not close to 2", t);
+        assert!(value < 1.0e-18);
+
+        // a reversed bracket works as well
+        let (t, _) = minimize_1d(|t: f64| (t - 2.0).powi(2), (10.0, 0.0, -3.0), 1.0e-10);
+
+        assert!((t - 2.0).abs() < 1.0e-9, "{} is not close to 2", t);
+    }
+
+    #[test]
+    fn test_minimize_1d_evaluations() {
+        let evaluations = Cell::new(0);
+
+        minimize_1d(|t: f64| {
+            evaluations.set(evaluations.get() + 1);
+            t.cosh()
+        }, (-1.0, 0.5, 1.0), 1.0e-6);
+
+        // apart from the three initial evaluations, each one shrinks the bracket by about
+        // the golden ratio
+        let iterations = (2.0 / 1.0e-6f64).ln() / (1.0 / 0.618_034f64).ln();
+
+        assert!((evaluations.get() as f64) < 5.0 + iterations, "{}", evaluations.get());
+    }
+
+    #[test]
+    fn test_brent() {
+        let (t, value) = brent(|t: f64| (t - 2.0).powi(2), (-3.0, 0.0, 10.0), 1.0e-10, 100);
+
+        assert!((t - 2.0).abs() < 1.0e-9, "{} is not close to 2", t);
+        assert!(value < 1.0e-18);
+
+        // a non-smooth function forces golden section steps
+        let (t, _) = brent(|t: f64| (t - 0.3).abs(), (1.0, 0.5, -1.0), 1.0e-10, 1000);
+
+        assert!((t - 0.3).abs() < 1.0e-9, "{} is not close to 0.3", t);
+    }
+
+    #[test]
+    fn test_brent_evaluations() {
+        let evaluations = Cell::new(0);
+
+        // smooth and unimodal with its minimum at ln(2)
+        let f = |t: f64| {
+            evaluations.set(evaluations.get() + 1);
+            t.exp() - 2.0 * t
+        };
+
+        let (t, _) = minimize_1d(f, (-2.0, 0.0, 3.0), 1.0e-6);
+        let golden_section = evaluations.replace(0);
+
+        assert!((t - 2.0f64.ln()).abs() < 1.0e-6, "{} is not close to ln(2)", t);
+
+        let (t, _) = brent(f, (-2.0, 0.0, 3.0), 1.0e-6, 100);
+        let parabolic = evaluations.replace(0);
+
+        assert!((t - 2.0f64.ln()).abs() < 1.0e-6, "{} is not close to ln(2)", t);
+        assert!(parabolic < golden_section, "{} vs. {}", parabolic, golden_section);
+    }
+
+    #[test]
+    #[should_panic(expected = "bracket must contain a minimum")]
+    fn test_minimize_1d_invalid_bracket() {
+        minimize_1d(|t: f64| t, (0.0, 1.0, 2.0), 1.0e-6);
+    }
+}
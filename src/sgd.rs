@@ -1,39 +1,84 @@
-use log::Level::Trace;
-use rand::{SeedableRng, random};
+use rand::{Rng, SeedableRng, random};
 use rand::seq::SliceRandom;
 use rand_pcg::Pcg64Mcg;
+use std::collections::{BTreeMap, VecDeque};
+use std::f64::consts::PI;
+use std::ops::Range;
+#[cfg(feature = "rayon")]
+use rayon::ThreadPoolBuilder;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "rayon")]
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use types::{Minimizer, Solution, Summation1};
+use line_search::{LineSearch, SearchHistory};
+use types::{Function, Function1, Minimizer, Summation1, SummationAccuracy, Convergence,
+    SgdReport, ConfigError, check_range, check_step_width, check_count};
 
 
 /// Provides _stochastic_ Gradient Descent optimization.
+#[derive(Debug, Clone)]
 pub struct StochasticGradientDescent {
     rng: Pcg64Mcg,
     max_iterations: Option<u64>,
+    target_value: Option<f64>,
+    value_tolerance: f64,
+    smoothing_window: usize,
     mini_batch: usize,
-    step_width: f64
+    summation_accuracy: SummationAccuracy,
+    accumulation_steps: usize,
+    step_width: f64,
+    step_widths: Option<Vec<f64>>,
+    warm_restarts: Option<CosineAnnealing>,
+    warmup: Option<(u64, f64)>,
+    iterate_averaging: bool,
+    ema_decay: Option<f64>,
+    value_sample: Option<f64>,
+    shard: Option<(usize, usize)>
 }
 
 impl StochasticGradientDescent {
     /// Creates a new `StochasticGradientDescent` optimizer using the following defaults:
     ///
     /// - **`step_width`** = `0.01`
+    /// - **`warmup`** = `None`
     /// - **`mini_batch`** = `1`
+    /// - **`summation_accuracy`** = `SummationAccuracy::Sequential`
+    /// - **`accumulation_steps`** = `1`
     /// - **`max_iterations`** = `1000`
+    /// - **`target_value`** = `None`
+    /// - **`value_tolerance`** = `0.0`
+    /// - **`smoothing_window`** = `1`
+    /// - **`iterate_averaging`** = `false`
+    /// - **`ema_decay`** = `None`
+    /// - **`value_sample`** = `None`
     ///
     /// The used random number generator is randomly seeded.
     pub fn new() -> StochasticGradientDescent {
         StochasticGradientDescent {
             rng: Pcg64Mcg::new(random()),
             max_iterations: None,
+            target_value: None,
+            value_tolerance: 0.0,
+            smoothing_window: 1,
             mini_batch: 1,
-            step_width: 0.01
+            summation_accuracy: SummationAccuracy::Sequential,
+            accumulation_steps: 1,
+            step_width: 0.01,
+            step_widths: None,
+            warm_restarts: None,
+            warmup: None,
+            iterate_averaging: false,
+            ema_decay: None,
+            value_sample: None,
+            shard: None
         }
     }
 
     /// Seeds the random number generator using the supplied `seed`.
     ///
-    /// This is useful to create re-producable results.
+    /// This is useful to create re-producable results. A clone of the optimizer starts with
+    /// the same state of the random number generator, thus producing the same results.
     pub fn seed(&mut self, seed: u64) -> &mut Self {
         self.rng = Pcg64Mcg::seed_from_u64(seed);
         self
@@ -42,27 +87,375 @@ impl StochasticGradientDescent {
     /// Adjusts the number of maximally run iterations. A value of `None` instructs the
     /// optimizer to ignore the nubmer of iterations.
     pub fn max_iterations(&mut self, max_iterations: Option<u64>) -> &mut Self {
-        assert!(max_iterations.map_or(true, |max_iterations| max_iterations > 0));
-
         self.max_iterations = max_iterations;
         self
     }
 
+    /// Adjusts the target value, i.e., the optimization is stopped as soon as the value drops
+    /// to or below it. A value of `None` disables this criterion.
+    pub fn target_value(&mut self, target_value: Option<f64>) -> &mut Self {
+        self.target_value = target_value;
+        self
+    }
+
+    /// Adjusts the value tolerance, i.e., the optimization is stopped as soon as the value
+    /// changes less than it between two consecutive iterations. A value of `0.0` disables
+    /// this criterion.
+    pub fn value_tolerance(&mut self, value_tolerance: f64) -> &mut Self {
+        self.value_tolerance = value_tolerance;
+        self
+    }
+
+    /// Adjusts the number of iterations whose values are averaged before the `value_tolerance`
+    /// is applied, i.e., the optimization is stopped as soon as the average over the last
+    /// `smoothing_window` values changes less than the tolerance between two consecutive
+    /// iterations. This prevents noisy values from stopping the optimization prematurely.
+    pub fn smoothing_window(&mut self, smoothing_window: usize) -> &mut Self {
+        self.smoothing_window = smoothing_window;
+        self
+    }
+
     /// Adjusts the mini batch size, i.e., how many terms are considered in one step at most.
     pub fn mini_batch(&mut self, mini_batch: usize) -> &mut Self {
-        assert!(mini_batch > 0);
-
         self.mini_batch = mini_batch;
         self
     }
 
+    /// Adjusts how the terms are summed up when evaluating the objective function, see
+    /// `SummationAccuracy`.
+    pub fn summation_accuracy(&mut self, summation_accuracy: SummationAccuracy) -> &mut Self {
+        self.summation_accuracy = summation_accuracy;
+        self
+    }
+
+    /// Adjusts the number of mini batches whose gradients are accumulated before a single
+    /// step is applied.
+    ///
+    /// Since the gradients are summed up, this is equivalent to a mini batch that is
+    /// `accumulation_steps` times larger, but only ever evaluates `mini_batch` terms at once.
+    pub fn accumulation_steps(&mut self, accumulation_steps: usize) -> &mut Self {
+        self.accumulation_steps = accumulation_steps;
+        self
+    }
+
     /// Adjusts the step size applied for each mini batch.
     pub fn step_width(&mut self, step_width: f64) -> &mut Self {
-        assert!(step_width > 0.0);
-
         self.step_width = step_width;
         self
     }
+
+    /// Adjusts the step size applied for each mini batch individually per coordinate, e.g.,
+    /// to use a different scale for a bias, which overrides the scalar `step_width`. The
+    /// number of step sizes has to match the dimensionality of the position.
+    pub fn step_widths(&mut self, step_widths: Vec<f64>) -> &mut Self {
+        self.step_widths = Some(step_widths);
+        self
+    }
+
+    /// Anneals the step size of each iteration using the supplied `schedule`, which
+    /// replaces the scalar `step_width`. Per-coordinate `step_widths` are scaled relative to
+    /// the maximal rate of the schedule instead.
+    pub fn warm_restarts(&mut self, schedule: CosineAnnealing) -> &mut Self {
+        self.warm_restarts = Some(schedule);
+        self
+    }
+
+    /// Ramps the step size up linearly during the first `epochs` iterations, starting at
+    /// `start_fraction` times the initial step size of the schedule, e.g., to avoid large
+    /// steps while the curvature is unknown. Afterwards, the schedule starts as if there was
+    /// no warmup, i.e., `warm_restarts()` count their iterations from the end of the warmup.
+    pub fn warmup(&mut self, epochs: u64, start_fraction: f64) -> &mut Self {
+        self.warmup = Some((epochs, start_fraction));
+        self
+    }
+
+    /// Enables or disables Polyak-Ruppert averaging, i.e., whether the average of the
+    /// positions after each iteration is returned instead of the last one.
+    ///
+    /// On convex problems, the average converges faster and is less affected by the noise of
+    /// the individual steps. The stopping criteria still refer to the last position.
+    pub fn iterate_averaging(&mut self, iterate_averaging: bool) -> &mut Self {
+        self.iterate_averaging = iterate_averaging;
+        self
+    }
+
+    /// Adjusts the decay ∈ [0, 1) of an exponential moving average (EMA) of the positions,
+    /// i.e., `ema ← decay · ema + (1 - decay) · x` after each step starting at the initial
+    /// position, which is reported as `SgdReport::ema` in addition to the returned position.
+    /// A value of `None` disables the average.
+    ///
+    /// In contrast to `iterate_averaging()`, older positions are forgotten gradually, which
+    /// smooths the noise of the steps while still following a moving minimum. Since the
+    /// steps of `ParallelStochasticGradientDescent` are applied concurrently, it updates the
+    /// average once per iteration instead.
+    pub fn ema_decay(&mut self, ema_decay: Option<f64>) -> &mut Self {
+        self.ema_decay = ema_decay;
+        self
+    }
+
+    /// Adjusts the fraction ∈ (0, 1] of the terms whose values estimate the value after each
+    /// iteration, see `Summation::value_sample()`, e.g., if a full evaluation is too costly
+    /// for monitoring the progress. A value of `None` evaluates all terms.
+    ///
+    /// The estimates are subject to the stopping criteria, which should account for their
+    /// noise, while the value of the returned position is computed exactly.
+    pub fn value_sample(&mut self, value_sample: Option<f64>) -> &mut Self {
+        self.value_sample = value_sample;
+        self
+    }
+
+    /// Restricts the optimizer to the terms of a single shard, see `Summation::shard()`, e.g.,
+    /// to distribute the optimization across several workers. In that case, the reported
+    /// value is the partial sum over the terms of the shard only.
+    pub fn shard(&mut self, shards: usize, index: usize) -> &mut Self {
+        self.shard = Some((shards, index));
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        check_range("value_tolerance", self.value_tolerance, "[0, ∞)",
+            self.value_tolerance >= 0.0 && self.value_tolerance.is_finite())?;
+        check_count("smoothing_window", self.smoothing_window as u64)?;
+        check_count("mini_batch", self.mini_batch as u64)?;
+        check_count("accumulation_steps", self.accumulation_steps as u64)?;
+        check_step_width("step_width", self.step_width)?;
+        for &step_width in self.step_widths.iter().flatten() {
+            check_step_width("step_widths", step_width)?;
+        }
+        if let Some((epochs, start_fraction)) = self.warmup {
+            check_count("epochs", epochs)?;
+            check_range("start_fraction", start_fraction, "[0, 1]",
+                (0.0..=1.0).contains(&start_fraction))?;
+        }
+        if let Some(ema_decay) = self.ema_decay {
+            check_range("ema_decay", ema_decay, "[0, 1)", (0.0..1.0).contains(&ema_decay))?;
+        }
+        if let Some(value_sample) = self.value_sample {
+            check_range("value_sample", value_sample, "(0, 1]",
+                value_sample > 0.0 && value_sample <= 1.0)?;
+        }
+        if let Some((shards, index)) = self.shard {
+            check_range("index", index as f64, "[0, shards)", index < shards)?;
+        }
+        Ok(())
+    }
+
+    /// Creates a version of this optimizer that chooses the step width of each step by
+    /// performing the `line_search` on the value and gradient of the current mini batch, see
+    /// `LineSearchStochasticGradientDescent`.
+    pub fn line_search<L: LineSearch>(&self, line_search: L)
+        -> LineSearchStochasticGradientDescent<L>
+    {
+        LineSearchStochasticGradientDescent {
+            sgd: self.clone(),
+            line_search,
+            growth_factor: 2.0
+        }
+    }
+
+    /// Creates a parallel version of this optimizer that uses `workers` threads, see
+    /// `ParallelStochasticGradientDescent`.
+    #[cfg(feature = "rayon")]
+    pub fn parallel(&self, workers: usize) -> ParallelStochasticGradientDescent {
+        ParallelStochasticGradientDescent {
+            sgd: self.clone(),
+            workers
+        }
+    }
+
+    /// Minimizes a stream of terms online, i.e., pulls the gradient of the next term at the
+    /// current position from `term_gradient` and immediately steps into its negative
+    /// direction, until the stream ends by returning `None`. Returns the final position.
+    ///
+    /// In contrast to `minimize()`, no `Summation` is required, which supports infinite or
+    /// streaming data. Since the objective function is never evaluated, only the step widths
+    /// and `max_iterations` apply, where each pulled gradient counts as an iteration.
+    pub fn minimize_online<G>(&self, mut term_gradient: G, initial_position: Vec<f64>)
+        -> Vec<f64>
+        where G: FnMut(&[f64]) -> Option<Vec<f64>>
+    {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize_online", algorithm = "StochasticGradientDescent",
+            max_iterations = ?self.max_iterations, step_width = self.step_width,
+            warm_restarts = ?self.warm_restarts, warmup = ?self.warmup).entered();
+
+        let mut position = initial_position;
+
+        if trace_enabled!() {
+            info!("Starting online minimization for x = {:?}", position);
+        } else {
+            info!("Starting online minimization");
+        }
+
+        let mut iteration = 0;
+
+        loop {
+            let gradient = match term_gradient(&position) {
+                Some(gradient) => gradient,
+                None => {
+                    info!("Stream of terms ended, stopping optimization");
+
+                    break;
+                }
+            };
+
+            assert_eq!(gradient.len(), position.len());
+
+            let step_widths = self.coordinate_step_widths(position.len(), iteration);
+
+            for ((x, g), step_width) in position.iter_mut().zip(gradient).zip(step_widths) {
+                *x -= step_width * g;
+            }
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: x = {:?}", iteration, position);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break;
+            }
+        }
+
+        position
+    }
+
+    // returns the step size of each coordinate in the given iteration
+    fn coordinate_step_widths(&self, dimensions: usize, iteration: u64) -> Vec<f64> {
+        // the schedule starts after the warmup, which ramps up its initial rate
+        let (iteration, warmup) = match self.warmup {
+            Some((epochs, start_fraction)) if iteration < epochs => {
+                (0, start_fraction + (1.0 - start_fraction) * iteration as f64 / epochs as f64)
+            },
+            Some((epochs, _)) => (iteration - epochs, 1.0),
+            None => (iteration, 1.0)
+        };
+
+        let (step_width, scale) = match self.warm_restarts {
+            Some(ref schedule) => {
+                let rate = schedule.rate(iteration);
+                (warmup * rate, warmup * rate / schedule.eta_max)
+            },
+            None => (warmup * self.step_width, warmup)
+        };
+
+        match self.step_widths {
+            Some(ref step_widths) => {
+                assert_eq!(step_widths.len(), dimensions,
+                    "number of step_widths must match the dimensionality of the position");
+
+                step_widths.iter().map(|step_width| scale * step_width).collect()
+            },
+            None => vec![step_width; dimensions]
+        }
+    }
+
+    // returns the range of terms the optimizer works on
+    fn term_range<F: Summation1>(&self, function: &F) -> Range<usize> {
+        match self.shard {
+            Some((shards, index)) => function.shard(shards, index),
+            None => 0..function.terms()
+        }
+    }
+
+    // computes the value over the terms the optimizer works on
+    fn evaluate<F: Summation1>(&self, function: &F, position: &[f64]) -> f64 {
+        function.partial_value_with(position, self.term_range(function), self.summation_accuracy)
+    }
+
+    // estimates the value over a random sample of the terms the optimizer works on, if
+    // enabled, and computes it over all of them otherwise
+    fn estimate<F: Summation1, R: Rng>(&self, function: &F, position: &[f64], rng: &mut R)
+        -> f64
+    {
+        match self.value_sample {
+            Some(fraction) => function.partial_value_sample(position, self.term_range(function),
+                fraction, self.summation_accuracy, rng),
+            None => self.evaluate(function, position)
+        }
+    }
+
+    // records the value and tests whether the average over the recent values changed less
+    // than the value tolerance, where the averages of two consecutive iterations only differ
+    // in the oldest and the newest value
+    fn reached_plateau(&self, values: &mut VecDeque<f64>, value: f64) -> bool {
+        values.push_back(value);
+
+        if values.len() > self.smoothing_window + 1 {
+            values.pop_front();
+        }
+
+        match (values.front(), values.back()) {
+            (Some(oldest), Some(newest)) if values.len() > self.smoothing_window => {
+                (oldest - newest).abs() / (self.smoothing_window as f64) < self.value_tolerance
+            },
+            _ => false
+        }
+    }
+
+    // returns either the last position or the average of all positions as solution
+    fn report<F: Summation1>(&self, function: &F, position: Vec<f64>, value: f64,
+                             average: Vec<f64>, epochs: u64, convergence: Convergence)
+        -> SgdReport
+    {
+        if self.iterate_averaging {
+            let value = self.evaluate(function, &average);
+            SgdReport::new(average, value, epochs, convergence)
+        } else if self.value_sample.is_some() {
+            let value = self.evaluate(function, &position);
+            SgdReport::new(position, value, epochs, convergence)
+        } else {
+            SgdReport::new(position, value, epochs, convergence)
+        }
+    }
+
+    // moves the exponential moving average towards the position, if enabled
+    fn update_ema(&self, ema: &mut [f64], position: &[f64]) {
+        if let Some(decay) = self.ema_decay {
+            for (e, x) in ema.iter_mut().zip(position) {
+                *e = decay * *e + (1.0 - decay) * x;
+            }
+        }
+    }
+
+    // sums the gradients of the mini batches that make up a single step, as pairs of
+    // coordinate and component, which only cover the coordinates the terms depend on if the
+    // summation provides sparse gradients
+    fn accumulated_gradient<F: Summation1>(&self, function: &F, position: &[f64], terms: &[usize])
+        -> Vec<(usize, f64)>
+    {
+        if function.has_sparse_gradients() {
+            let mut gradient = BTreeMap::new();
+
+            for &term in terms {
+                for (i, g) in function.term_gradient_sparse(position, term) {
+                    *gradient.entry(i).or_insert(0.0) += g;
+                }
+            }
+
+            gradient.into_iter().collect()
+        } else {
+            let mut gradient = vec![0.0; position.len()];
+
+            for batch in terms.chunks(self.mini_batch) {
+                for (g, gi) in gradient.iter_mut().zip(function.partial_gradient(position, batch)) {
+                    *g += gi;
+                }
+            }
+
+            gradient.into_iter().enumerate().collect()
+        }
+    }
 }
 
 impl Default for StochasticGradientDescent {
@@ -72,52 +465,1078 @@ impl Default for StochasticGradientDescent {
 }
 
 impl<F: Summation1> Minimizer<F> for StochasticGradientDescent {
-    type Solution = Solution;
+    type Solution = SgdReport;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> SgdReport {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
 
-    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "StochasticGradientDescent",
+            max_iterations = ?self.max_iterations, target_value = ?self.target_value,
+            value_tolerance = self.value_tolerance, smoothing_window = self.smoothing_window,
+            mini_batch = self.mini_batch,
+            summation_accuracy = ?self.summation_accuracy,
+            accumulation_steps = self.accumulation_steps, step_width = self.step_width,
+            warm_restarts = ?self.warm_restarts, warmup = ?self.warmup,
+            iterate_averaging = self.iterate_averaging,
+            ema_decay = ?self.ema_decay, value_sample = ?self.value_sample).entered();
+
+        let mut rng = self.rng.clone();
         let mut position = initial_position;
-        let mut value = function.value(&position);
+        let mut value = self.estimate(function, &position, &mut rng);
 
-        if log_enabled!(Trace) {
+        if trace_enabled!() {
             info!("Starting with y = {:?} for x = {:?}", value, position);
         } else {
             info!("Starting with y = {:?}", value);
         }
 
         let mut iteration = 0;
-        let mut terms: Vec<_> = (0..function.terms()).collect();
-        let mut rng = self.rng.clone();
+        let mut terms: Vec<_> = self.term_range(function).collect();
+        let mut average = position.clone();
+        let mut ema = position.clone();
+        let mut values = VecDeque::from(vec![value]);
+
+        let convergence = loop {
+            if self.target_value.is_some_and(|target_value| value <= target_value) {
+                info!("Reached target value, stopping optimization");
+
+                break Convergence::TargetValue;
+            }
 
-        loop {
             // ensure that we don't run into cycles
             terms.shuffle(&mut rng);
 
-            for batch in terms.chunks(self.mini_batch) {
-                let gradient = function.partial_gradient(&position, batch);
+            let step_widths = self.coordinate_step_widths(position.len(), iteration);
+
+            for batches in terms.chunks(self.mini_batch * self.accumulation_steps) {
+                let gradient = self.accumulated_gradient(function, &position, batches);
+
+                // step into the direction of the negative gradient, only updating the
+                // coordinates the terms depend on
+                for (i, g) in gradient {
+                    position[i] -= step_widths[i] * g;
+                }
+
+                self.update_ema(&mut ema, &position);
+            }
+
+            value = self.estimate(function, &position, &mut rng);
+
+            iteration += 1;
+
+            for (a, x) in average.iter_mut().zip(&position) {
+                *a += (x - *a) / iteration as f64;
+            }
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+
+            if self.reached_plateau(&mut values, value) {
+                info!("Value changed to little, stopping optimization");
+
+                break Convergence::ValueTolerance;
+            }
+        };
+
+        let mut report = self.report(function, position, value, average, iteration, convergence);
+        report.ema = self.ema_decay.map(|_| ema);
+        report
+    }
+}
+
+
+/// Cosine annealing schedule with warm restarts (SGDR), see
+/// `StochasticGradientDescent::warm_restarts()`.
+///
+/// Within a cycle of `tᵢ` iterations, the rate decreases from `eta_max` to `eta_min` along
+/// a cosine curve, after which it is reset to `eta_max`. The first cycle spans `t0`
+/// iterations, each following one is `t_mult` times longer (Loshchilov & Hutter, 2017):
+///
+/// > η = η_min + ½ (η_max - η_min) (1 + cos(π t_cur / tᵢ))
+#[derive(Debug, Copy, Clone)]
+pub struct CosineAnnealing {
+    t0: u64,
+    t_mult: u64,
+    eta_min: f64,
+    eta_max: f64
+}
+
+impl CosineAnnealing {
+    /// Creates a new schedule whose first cycle spans `t0` iterations, with each following
+    /// cycle being `t_mult` times longer, annealing the rate from `eta_max` to `eta_min`.
+    pub fn new(t0: u64, t_mult: u64, eta_min: f64, eta_max: f64) -> CosineAnnealing {
+        CosineAnnealing::try_new(t0, t_mult, eta_min, eta_max)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new schedule like `new()`, but returns an error instead of panicking if
+    /// `t0` or `t_mult` is `0`, or the rates do not satisfy `0 ≤ eta_min ≤ eta_max`
+    /// with `eta_max > 0`.
+    pub fn try_new(t0: u64, t_mult: u64, eta_min: f64, eta_max: f64)
+        -> Result<CosineAnnealing, ConfigError>
+    {
+        check_count("t0", t0)?;
+        check_count("t_mult", t_mult)?;
+        check_step_width("eta_max", eta_max)?;
+        check_range("eta_min", eta_min, "[0, eta_max]", 0.0 <= eta_min && eta_min <= eta_max)?;
+
+        Ok(CosineAnnealing {
+            t0,
+            t_mult,
+            eta_min,
+            eta_max
+        })
+    }
+
+    /// Returns the rate of the given zero-based `iteration`.
+    pub fn rate(&self, iteration: u64) -> f64 {
+        let mut current = iteration;
+        let mut period = self.t0;
+
+        while current >= period {
+            current -= period;
+            period *= self.t_mult;
+        }
 
-                // step into the direction of the negative gradient
-                for (x, g) in position.iter_mut().zip(gradient) {
-                    *x -= self.step_width * g;
+        self.eta_min + 0.5 * (self.eta_max - self.eta_min) *
+            (1.0 + (PI * current as f64 / period as f64).cos())
+    }
+}
+
+
+/// Provides _stochastic_ Gradient Descent optimization choosing the step widths by a line
+/// search on the current mini batch, which is created using
+/// `StochasticGradientDescent::line_search()`.
+///
+/// In each step, the line search is performed along the negative gradient of the sum over the
+/// terms of the mini batch, e.g., an `ArmijoLineSearch` backtracks until the Armijo rule holds
+/// for the value of the mini batch (Vaswani et al., 2019). This saves the manual tuning of the
+/// step width, which diverges if chosen too large. Since backtracking only ever shrinks the
+/// step width, the next search starts at the accepted step width increased by the
+/// `growth_factor` instead, which forward-tracks the step width whenever larger steps become
+/// acceptable.
+///
+/// All other settings of the `StochasticGradientDescent` apply, except for the step widths and
+/// their schedules, which are replaced by the line search. A mini batch comprises
+/// `mini_batch * accumulation_steps` terms.
+#[derive(Debug, Clone)]
+pub struct LineSearchStochasticGradientDescent<L> {
+    sgd: StochasticGradientDescent,
+    line_search: L,
+    growth_factor: f64
+}
+
+impl<L: LineSearch> LineSearchStochasticGradientDescent<L> {
+    /// Adjusts the factor ≥ 1 the accepted step width is multiplied by to start the line search
+    /// of the next mini batch, which defaults to `2.0`. A value of `1.0` disables the forward
+    /// tracking, such that the step widths shrink monotonically.
+    pub fn growth_factor(&mut self, growth_factor: f64) -> &mut Self {
+        self.growth_factor = growth_factor;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.sgd.validate()?;
+        check_range("growth_factor", self.growth_factor, "[1, ∞)",
+            self.growth_factor >= 1.0 && self.growth_factor.is_finite())
+    }
+}
+
+impl<F: Summation1, L: LineSearch> Minimizer<F> for LineSearchStochasticGradientDescent<L> {
+    type Solution = SgdReport;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> SgdReport {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "LineSearchStochasticGradientDescent",
+            line_search = ?self.line_search, growth_factor = self.growth_factor,
+            max_iterations = ?self.sgd.max_iterations, target_value = ?self.sgd.target_value,
+            value_tolerance = self.sgd.value_tolerance,
+            smoothing_window = self.sgd.smoothing_window,
+            mini_batch = self.sgd.mini_batch, summation_accuracy = ?self.sgd.summation_accuracy,
+            accumulation_steps = self.sgd.accumulation_steps,
+            iterate_averaging = self.sgd.iterate_averaging,
+            ema_decay = ?self.sgd.ema_decay,
+            value_sample = ?self.sgd.value_sample).entered();
+
+        let mut rng = self.sgd.rng.clone();
+        let mut position = initial_position;
+        let mut value = self.sgd.estimate(function, &position, &mut rng);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut terms: Vec<_> = self.sgd.term_range(function).collect();
+        let mut average = position.clone();
+        let mut ema = position.clone();
+        let mut values = VecDeque::from(vec![value]);
+
+        // the step width the next line search starts at, if any step has been accepted yet
+        let mut hint = None;
+        let mut history = SearchHistory::new();
+
+        let convergence = loop {
+            if self.sgd.target_value.is_some_and(|target_value| value <= target_value) {
+                info!("Reached target value, stopping optimization");
+
+                break Convergence::TargetValue;
+            }
+
+            // ensure that we don't run into cycles
+            terms.shuffle(&mut rng);
+
+            for batch in terms.chunks(self.sgd.mini_batch * self.sgd.accumulation_steps) {
+                let batch = MiniBatch {
+                    function,
+                    terms: batch,
+                    summation_accuracy: self.sgd.summation_accuracy
+                };
+
+                let direction: Vec<_> = batch.gradient(&position).into_iter()
+                    .map(|g| -g)
+                    .collect();
+
+                // the terms of the mini batch are minimized already
+                if direction.iter().all(|&d| d == 0.0) {
+                    continue;
                 }
+
+                let step = self.line_search.search_in(&mut history, &batch, &position,
+                    &direction, hint);
+
+                if step.step_width > 0.0 {
+                    hint = Some(self.growth_factor * step.step_width);
+                }
+
+                position = step.position;
+
+                self.sgd.update_ema(&mut ema, &position);
+            }
+
+            value = self.sgd.estimate(function, &position, &mut rng);
+
+            iteration += 1;
+
+            for (a, x) in average.iter_mut().zip(&position) {
+                *a += (x - *a) / iteration as f64;
+            }
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}, hint = {:?}", iteration, value,
+                    position, hint);
+            } else {
+                debug!("Iteration {:6}: y = {:?}, hint = {:?}", iteration, value, hint);
+            }
+
+            let reached_max_iterations = self.sgd.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+
+            if self.sgd.reached_plateau(&mut values, value) {
+                info!("Value changed to little, stopping optimization");
+
+                break Convergence::ValueTolerance;
+            }
+        };
+
+        let mut report = self.sgd.report(function, position, value, average, iteration,
+            convergence);
+        report.ema = self.sgd.ema_decay.map(|_| ema);
+        report
+    }
+}
+
+// the sum over the terms of a mini batch, which the line search is performed on
+struct MiniBatch<'a, F: 'a> {
+    function: &'a F,
+    terms: &'a [usize],
+    summation_accuracy: SummationAccuracy
+}
+
+impl<'a, F: Summation1> Function for MiniBatch<'a, F> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.function.partial_value_with(position, self.terms, self.summation_accuracy)
+    }
+}
+
+impl<'a, F: Summation1> Function1 for MiniBatch<'a, F> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.function.partial_gradient(position, self.terms)
+    }
+}
+
+
+/// Provides lock-free parallel _stochastic_ Gradient Descent optimization, also known as
+/// _Hogwild_, which is created using `StochasticGradientDescent::parallel()`.
+///
+/// In each iteration, the shuffled terms are split into disjoint shares, one for each worker.
+/// The workers process their mini batches concurrently and apply their updates to a shared
+/// position without any locking, i.e., a worker might compute its gradient using a position
+/// that is partially updated by another worker. Consequently, the results are not
+/// reproducible using more than one worker, even when seeded. With a single worker, the
+/// results are identical to the ones of the serial optimizer.
+///
+/// On convex problems, Hogwild converges given a sufficiently small step width, in particular
+/// if the gradients of the individual terms are sparse (Niu et al., 2011).
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone)]
+pub struct ParallelStochasticGradientDescent {
+    sgd: StochasticGradientDescent,
+    workers: usize
+}
+
+#[cfg(feature = "rayon")]
+impl ParallelStochasticGradientDescent {
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.sgd.validate()?;
+        check_count("workers", self.workers as u64)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<F: Summation1 + Sync> Minimizer<F> for ParallelStochasticGradientDescent {
+    type Solution = SgdReport;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> SgdReport {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "ParallelStochasticGradientDescent",
+            workers = self.workers, max_iterations = ?self.sgd.max_iterations,
+            target_value = ?self.sgd.target_value, value_tolerance = self.sgd.value_tolerance,
+            smoothing_window = self.sgd.smoothing_window,
+            mini_batch = self.sgd.mini_batch, summation_accuracy = ?self.sgd.summation_accuracy,
+            accumulation_steps = self.sgd.accumulation_steps,
+            step_width = self.sgd.step_width, warm_restarts = ?self.sgd.warm_restarts,
+            warmup = ?self.sgd.warmup,
+            iterate_averaging = self.sgd.iterate_averaging,
+            ema_decay = ?self.sgd.ema_decay,
+            value_sample = ?self.sgd.value_sample).entered();
+
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.workers)
+            .build()
+            .expect("failed to create the worker threads");
+
+        let mut rng = self.sgd.rng.clone();
+        let mut value = self.sgd.estimate(function, &initial_position, &mut rng);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, initial_position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        // the bits of the shared position, which can be updated atomically
+        let shared: Vec<_> = initial_position.iter().map(|x| AtomicU64::new(x.to_bits())).collect();
+        let load = || shared.iter().map(|x| f64::from_bits(x.load(Ordering::Relaxed))).collect::<Vec<_>>();
+
+        let mut iteration = 0;
+        let mut terms: Vec<_> = self.sgd.term_range(function).collect();
+        let mut average = initial_position.clone();
+        let mut ema = initial_position.clone();
+        let mut values = VecDeque::from(vec![value]);
+
+        let step_terms = self.sgd.mini_batch * self.sgd.accumulation_steps;
+        let steps = terms.len().div_ceil(step_terms);
+        let share = steps.div_ceil(self.workers).max(1) * step_terms;
+
+        let convergence = loop {
+            if self.sgd.target_value.is_some_and(|target_value| value <= target_value) {
+                info!("Reached target value, stopping optimization");
+
+                break Convergence::TargetValue;
             }
 
-            value = function.value(&position);
+            // ensure that we don't run into cycles
+            terms.shuffle(&mut rng);
+
+            let step_widths = self.sgd.coordinate_step_widths(shared.len(), iteration);
+
+            pool.install(|| terms.par_chunks(share).for_each(|terms| {
+                for batches in terms.chunks(step_terms) {
+                    let gradient = self.sgd.accumulated_gradient(function, &load(), batches);
+
+                    // step into the direction of the negative gradient, only updating the
+                    // coordinates the terms depend on
+                    for (i, g) in gradient {
+                        let _ = shared[i].fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                            Some((f64::from_bits(x) - step_widths[i] * g).to_bits())
+                        });
+                    }
+                }
+            }));
+
+            let position = load();
+
+            value = self.sgd.estimate(function, &position, &mut rng);
 
             iteration += 1;
 
-            if log_enabled!(Trace) {
+            for (a, x) in average.iter_mut().zip(&position) {
+                *a += (x - *a) / iteration as f64;
+            }
+
+            self.sgd.update_ema(&mut ema, &position);
+
+            if trace_enabled!() {
                 debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
             } else {
                 debug!("Iteration {:6}: y = {:?}", iteration, value);
             }
 
-            let reached_max_iterations = self.max_iterations.map_or(false,
-                |max_iterations| iteration == max_iterations);
+            let reached_max_iterations = self.sgd.max_iterations == Some(iteration);
 
             if reached_max_iterations {
                 info!("Reached maximal number of iterations, stopping optimization");
-                return Solution::new(position, value);
+
+                break Convergence::MaxIterations;
+            }
+
+            if self.sgd.reached_plateau(&mut values, value) {
+                info!("Value changed to little, stopping optimization");
+
+                break Convergence::ValueTolerance;
             }
+        };
+
+        let mut report = self.sgd.report(function, load(), value, average, iteration,
+            convergence);
+        report.ema = self.sgd.ema_decay.map(|_| ema);
+        report
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::borrow::Borrow;
+    use std::f64::consts::PI;
+    use std::collections::BTreeSet;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    use problems::LinearRegression;
+    use types::{Function, Minimizer, Summation, Summation1, Convergence, ConfigError};
+
+    use line_search::ArmijoLineSearch;
+
+    use super::{StochasticGradientDescent, CosineAnnealing};
+
+    #[test]
+    fn test_accumulation_steps() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let large_batch = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10))
+            .mini_batch(4)
+            .minimize(&problem, vec![1.0; 3]);
+        let accumulated = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10))
+            .mini_batch(1)
+            .accumulation_steps(4)
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(large_batch.position, accumulated.position);
+    }
+
+    #[test]
+    fn test_clone() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.max_iterations(Some(10)).mini_batch(4);
+
+        // even randomly seeded, the clone shares the state of the random number generator
+        let clone = sgd.clone();
+
+        assert_eq!(clone.minimize(&problem, vec![1.0; 3]).position,
+            sgd.minimize(&problem, vec![1.0; 3]).position);
+    }
+
+    #[test]
+    fn test_minimize_online() {
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        // a finite stream of noisy observations of y = 2 + 3 x
+        let mut observations = (0..5000).map(|_| {
+            let x = rng.gen::<f64>();
+            let y = 2.0 + 3.0 * x + 0.1 * (rng.gen::<f64>() - 0.5);
+
+            (x, y)
+        });
+
+        let mut pulls = 0;
+
+        let position = StochasticGradientDescent::new()
+            .step_width(0.1)
+            .minimize_online(|w: &[f64]| {
+                pulls += 1;
+
+                // gradient of the squared error ½ (w₀ + w₁ x - y)²
+                observations.next().map(|(x, y)| {
+                    let e = w[0] + w[1] * x - y;
+                    vec![e, e * x]
+                })
+            }, vec![0.0, 0.0]);
+
+        // the stream has been consumed entirely, after which the optimization stopped
+        assert_eq!(pulls, 5001);
+
+        assert!((position[0] - 2.0).abs() < 0.1 && (position[1] - 3.0).abs() < 0.1,
+            "{:?} is not close to [2, 3]", position);
+
+        // the number of iterations limits the consumed terms of an infinite stream
+        let mut pulls = 0;
+
+        StochasticGradientDescent::new()
+            .max_iterations(Some(10))
+            .minimize_online(|w: &[f64]| {
+                pulls += 1;
+                Some(vec![2.0 * w[0]])
+            }, vec![1.0]);
+
+        assert_eq!(pulls, 10);
+    }
+
+    #[test]
+    fn test_step_widths() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let scalar = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(5))
+            .step_width(0.005)
+            .minimize(&problem, vec![1.0; 3]);
+        let per_coordinate = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(5))
+            .step_widths(vec![0.02, 0.005, 0.005])
+            .minimize(&problem, vec![1.0; 3]);
+
+        // the larger step on the bias speeds up its convergence
+        assert!((per_coordinate.position[0] - 13.37).abs() < (scalar.position[0] - 13.37).abs(),
+            "{:?} vs. {:?}", per_coordinate.position, scalar.position);
+    }
+
+    // sum of parabolas recording the terms whose gradient has been evaluated
+    struct Recording(RefCell<BTreeSet<usize>>);
+
+    impl Summation for Recording {
+        fn terms(&self) -> usize {
+            10
+        }
+
+        fn term_value(&self, x: &[f64], _term: usize) -> f64 {
+            x[0].powi(2)
+        }
+    }
+
+    impl Summation1 for Recording {
+        fn term_gradient(&self, x: &[f64], term: usize) -> Vec<f64> {
+            self.0.borrow_mut().insert(term);
+
+            vec![2.0 * x[0]]
+        }
+    }
+
+    #[test]
+    fn test_shard() {
+        let recording = Recording(RefCell::new(BTreeSet::new()));
+
+        for index in 0..4 {
+            recording.0.borrow_mut().clear();
+
+            StochasticGradientDescent::new()
+                .seed(42)
+                .max_iterations(Some(3))
+                .shard(4, index)
+                .minimize(&recording, vec![1.0]);
+
+            assert!(recording.0.borrow().iter().cloned().eq(recording.shard(4, index)));
         }
     }
+
+    // sum of parabolas (xᵢ - cᵢ)², each depending on a single coordinate only
+    struct Separable {
+        centers: Vec<f64>,
+        sparse: bool
+    }
+
+    impl Summation for Separable {
+        fn terms(&self) -> usize {
+            self.centers.len()
+        }
+
+        fn term_value(&self, x: &[f64], term: usize) -> f64 {
+            (x[term % x.len()] - self.centers[term]).powi(2)
+        }
+    }
+
+    impl Summation1 for Separable {
+        fn term_gradient(&self, x: &[f64], term: usize) -> Vec<f64> {
+            let mut gradient = vec![0.0; x.len()];
+            gradient[term % x.len()] = 2.0 * (x[term % x.len()] - self.centers[term]);
+            gradient
+        }
+
+        fn term_gradient_sparse(&self, x: &[f64], term: usize) -> Vec<(usize, f64)> {
+            if self.sparse {
+                vec![(term % x.len(), 2.0 * (x[term % x.len()] - self.centers[term]))]
+            } else {
+                // the default derived from the dense gradient
+                self.term_gradient(x, term).into_iter()
+                    .enumerate()
+                    .filter(|&(_, g)| g != 0.0)
+                    .collect()
+            }
+        }
+
+        fn has_sparse_gradients(&self) -> bool {
+            self.sparse
+        }
+    }
+
+    #[test]
+    fn test_sparse_gradient() {
+        let centers: Vec<_> = (0..100).map(|i| (i as f64).sin()).collect();
+
+        let sparse = Separable { centers: centers.clone(), sparse: true };
+        let dense = Separable { centers, sparse: false };
+
+        assert_eq!(sparse.term_gradient_sparse(&[0.5; 10], 13),
+                   dense.term_gradient_sparse(&[0.5; 10], 13));
+
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.seed(42).max_iterations(Some(20)).mini_batch(4);
+
+        let sparse_solution = sgd.minimize(&sparse, vec![0.0; 10]);
+        let dense_solution = sgd.minimize(&dense, vec![0.0; 10]);
+
+        assert_eq!(sparse_solution.position, dense_solution.position);
+
+        // each coordinate approaches the mean of its centers
+        for (i, x) in sparse_solution.position.iter().enumerate() {
+            let mean = (0..10).map(|j| (i as f64 + 10.0 * j as f64).sin()).sum::<f64>() / 10.0;
+
+            assert!((x - mean).abs() < 0.1, "{} differs from {}", x, mean);
+        }
+    }
+
+    // forwards to a linear regression, counting the partial gradients
+    struct BatchCounting {
+        problem: LinearRegression,
+        batches: Cell<usize>
+    }
+
+    impl Summation for BatchCounting {
+        fn terms(&self) -> usize {
+            self.problem.terms()
+        }
+
+        fn term_value(&self, w: &[f64], term: usize) -> f64 {
+            self.problem.term_value(w, term)
+        }
+    }
+
+    impl Summation1 for BatchCounting {
+        fn term_gradient(&self, w: &[f64], term: usize) -> Vec<f64> {
+            self.problem.term_gradient(w, term)
+        }
+
+        fn partial_gradient<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, w: &[f64],
+                                                                    terms: T) -> Vec<f64>
+        {
+            self.batches.set(self.batches.get() + 1);
+
+            self.problem.partial_gradient(w, terms)
+        }
+    }
+
+    #[test]
+    fn test_partial_gradient() {
+        let problem = BatchCounting {
+            problem: LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42),
+            batches: Cell::new(0)
+        };
+
+        StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(3))
+            .mini_batch(10)
+            .accumulation_steps(2)
+            .minimize(&problem, vec![1.0; 3]);
+
+        // dense summations are evaluated per mini batch, respecting overrides
+        assert_eq!(problem.batches.get(), 3 * 10);
+    }
+
+    #[test]
+    fn test_target_value() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.seed(42);
+
+        // the first iteration reaching the target
+        let iterations = (1..1000).find(|&iterations| {
+            sgd.max_iterations(Some(iterations)).minimize(&problem, vec![1.0; 3]).value <= 5.0
+        }).expect("target was not reached");
+
+        let expected = sgd.max_iterations(Some(iterations)).minimize(&problem, vec![1.0; 3]);
+        let solution = sgd.max_iterations(None)
+            .target_value(Some(5.0))
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(solution.position, expected.position);
+        assert_eq!(solution.value, expected.value);
+    }
+
+    #[test]
+    fn test_cosine_annealing() {
+        let schedule = CosineAnnealing::new(2, 2, 0.1, 0.5);
+        let rate = |t_cur: f64, t_i: f64| 0.1 + 0.2 * (1.0 + (PI * t_cur / t_i).cos());
+
+        // cycles of length 2, 4 and 8 starting at 0, 2 and 6, respectively
+        let expected = [(0.0, 2.0), (1.0, 2.0),
+                        (0.0, 4.0), (1.0, 4.0), (2.0, 4.0), (3.0, 4.0),
+                        (0.0, 8.0), (1.0, 8.0), (7.0, 8.0)];
+        let iterations = [0, 1, 2, 3, 4, 5, 6, 7, 13];
+
+        for (&iteration, &(t_cur, t_i)) in iterations.iter().zip(&expected) {
+            assert!((schedule.rate(iteration) - rate(t_cur, t_i)).abs() < 1.0e-12);
+        }
+
+        // restarts reset the rate to its maximum
+        assert_eq!(schedule.rate(2), 0.5);
+        assert_eq!(schedule.rate(6), 0.5);
+        assert!(schedule.rate(5) < schedule.rate(4) && schedule.rate(4) < schedule.rate(3));
+        assert_eq!(schedule.rate(14), 0.5);
+    }
+
+    #[test]
+    fn test_warm_restarts() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let constant = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10))
+            .step_width(0.005)
+            .minimize(&problem, vec![1.0; 3]);
+        let annealed = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10))
+            .warm_restarts(CosineAnnealing::new(3, 2, 0.005, 0.005))
+            .minimize(&problem, vec![1.0; 3]);
+
+        // a degenerate schedule corresponds to a constant step width
+        assert_eq!(constant.position, annealed.position);
+
+        let annealed = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(100))
+            .warm_restarts(CosineAnnealing::new(5, 2, 0.0001, 0.01))
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert!(annealed.value < constant.value);
+    }
+
+    #[test]
+    fn test_warmup() {
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.step_width(0.1).warmup(4, 0.25);
+
+        // the rate increases linearly from 0.025 to 0.1 within four iterations
+        for (iteration, &expected) in [0.025, 0.04375, 0.0625, 0.08125, 0.1, 0.1].iter()
+            .enumerate()
+        {
+            let step_widths = sgd.coordinate_step_widths(2, iteration as u64);
+
+            for step_width in step_widths {
+                assert!((step_width - expected).abs() < 1.0e-15, "{} is not {} in iteration {}",
+                    step_width, expected, iteration);
+            }
+        }
+
+        // per-coordinate step widths are ramped up as well
+        sgd.step_widths(vec![0.2, 0.04]);
+
+        assert_eq!(sgd.coordinate_step_widths(2, 2), vec![0.125, 0.025]);
+
+        // the schedule starts with its maximal rate after the warmup
+        let schedule = CosineAnnealing::new(3, 1, 0.0, 0.4);
+
+        sgd.warm_restarts(schedule);
+
+        assert_eq!(sgd.coordinate_step_widths(2, 0), vec![0.05, 0.01]);
+
+        for iteration in 0..10 {
+            let scale = schedule.rate(iteration) / 0.4;
+
+            assert_eq!(sgd.coordinate_step_widths(2, iteration + 4),
+                vec![0.2 * scale, 0.04 * scale]);
+        }
+    }
+
+    #[test]
+    fn test_convergence() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let capped = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10))
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(capped.convergence, Convergence::MaxIterations);
+        assert_eq!(capped.epochs, 10);
+
+        let plateau = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10000))
+            .value_tolerance(1.0e-6)
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(plateau.convergence, Convergence::ValueTolerance);
+        assert!(plateau.epochs < 10000);
+
+        let target = StochasticGradientDescent::new()
+            .seed(42)
+            .target_value(Some(10.0))
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(target.convergence, Convergence::TargetValue);
+        assert!(target.value <= 10.0);
+    }
+
+    // adds uniform noise to the value of each term, while the gradients remain exact
+    struct NoisyTerms {
+        problem: LinearRegression,
+        rng: RefCell<Pcg64Mcg>,
+        noise: f64
+    }
+
+    impl Summation for NoisyTerms {
+        fn terms(&self) -> usize {
+            self.problem.terms()
+        }
+
+        fn term_value(&self, w: &[f64], term: usize) -> f64 {
+            let noise = self.rng.borrow_mut().gen_range(-self.noise, self.noise);
+
+            self.problem.term_value(w, term) + noise
+        }
+    }
+
+    impl Summation1 for NoisyTerms {
+        fn term_gradient(&self, w: &[f64], term: usize) -> Vec<f64> {
+            self.problem.term_gradient(w, term)
+        }
+    }
+
+    #[test]
+    fn test_smoothing_window() {
+        let problem = NoisyTerms {
+            problem: LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42),
+            rng: RefCell::new(Pcg64Mcg::seed_from_u64(42)),
+            noise: 1.0
+        };
+
+        let minimize = |smoothing_window: usize| {
+            StochasticGradientDescent::new()
+                .seed(42)
+                .max_iterations(Some(10000))
+                .step_width(0.001)
+                .value_tolerance(0.2)
+                .smoothing_window(smoothing_window)
+                .minimize(&problem, vec![1.0; 3])
+        };
+
+        // single noisy values occasionally coincide by chance long before the plateau
+        let noisy = minimize(1);
+        let smoothed = minimize(100);
+
+        assert_eq!(smoothed.convergence, Convergence::ValueTolerance);
+        assert!(smoothed.epochs > noisy.epochs, "{} vs. {}", smoothed.epochs, noisy.epochs);
+
+        let noisy_value = problem.problem.value(&noisy.position);
+        let smoothed_value = problem.problem.value(&smoothed.position);
+
+        assert!(smoothed_value < noisy_value, "{} vs. {}", smoothed_value, noisy_value);
+    }
+
+    #[test]
+    fn test_iterate_averaging() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        // variance of the final positions across differently seeded runs
+        let variance = |iterate_averaging: bool| {
+            let positions: Vec<_> = (0..20).map(|seed| {
+                StochasticGradientDescent::new()
+                    .seed(seed)
+                    .max_iterations(Some(500))
+                    .step_width(0.05)
+                    .iterate_averaging(iterate_averaging)
+                    .minimize(&problem, vec![13.37, -4.2, 2.5])
+                    .position
+            }).collect();
+
+            (0..3).map(|i| {
+                let mean = positions.iter().map(|x| x[i]).sum::<f64>() / positions.len() as f64;
+                positions.iter().map(|x| (x[i] - mean).powi(2)).sum::<f64>()
+            }).sum::<f64>() / positions.len() as f64
+        };
+
+        let last = variance(false);
+        let averaged = variance(true);
+
+        assert!(averaged < last, "{} is not less than {}", averaged, last);
+    }
+
+    #[test]
+    fn test_ema() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let report = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(10))
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert!(report.ema.is_none());
+
+        let reports: Vec<_> = (0..20).map(|seed| {
+            StochasticGradientDescent::new()
+                .seed(seed)
+                .max_iterations(Some(500))
+                .step_width(0.05)
+                .ema_decay(Some(0.99))
+                .minimize(&problem, vec![13.37, -4.2, 2.5])
+        }).collect();
+
+        // variance of the positions across differently seeded runs
+        let variance = |positions: &[&[f64]]| {
+            (0..3).map(|i| {
+                let mean = positions.iter().map(|x| x[i]).sum::<f64>() / positions.len() as f64;
+                positions.iter().map(|x| (x[i] - mean).powi(2)).sum::<f64>()
+            }).sum::<f64>() / positions.len() as f64
+        };
+
+        let raw: Vec<_> = reports.iter().map(|report| &report.position[..]).collect();
+        let ema: Vec<_> = reports.iter().map(|report| &report.ema.as_ref().unwrap()[..]).collect();
+
+        assert!(variance(&ema) < variance(&raw), "{} is not less than {}", variance(&ema),
+            variance(&raw));
+    }
+
+    #[test]
+    fn test_value_sample() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let report = StochasticGradientDescent::new()
+            .seed(42)
+            .max_iterations(Some(50))
+            .value_sample(Some(0.1))
+            .minimize(&problem, vec![1.0; 3]);
+
+        // the value of the returned position is computed exactly
+        assert_eq!(report.epochs, 50);
+        assert_eq!(report.value, problem.value(&report.position));
+    }
+
+    #[test]
+    fn test_line_search() {
+        let coefficients = [13.37, -4.2, 2.5];
+        let problem = LinearRegression::new(&coefficients, 1000, 42);
+
+        // the gradient is summed over the mini batch, thus the default step width diverges
+        // for large mini batches
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.seed(42).mini_batch(250).max_iterations(Some(50));
+
+        let fixed = sgd.minimize(&problem, vec![1.0; 3]);
+
+        assert!(fixed.value > problem.value(&[1.0; 3]), "{} did not diverge", fixed.value);
+
+        let searched = sgd.line_search(ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0))
+            .minimize(&problem, vec![1.0; 3]);
+
+        for (w, expected) in searched.position.iter().zip(&coefficients) {
+            assert!((w - expected).abs() < 0.1, "{:?} is not close to {:?}", searched.position,
+                coefficients);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel() {
+        let coefficients = [13.37, -4.2, 2.5];
+        let problem = LinearRegression::new(&coefficients, 100, 42);
+
+        let mut sgd = StochasticGradientDescent::new();
+        sgd.seed(42).max_iterations(Some(100)).mini_batch(2);
+
+        let serial = sgd.minimize(&problem, vec![1.0; 3]);
+        let single = sgd.parallel(1).minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(serial.position, single.position);
+        assert_eq!(serial.value, single.value);
+
+        let parallel = sgd.parallel(4).minimize(&problem, vec![1.0; 3]);
+
+        for (w, c) in parallel.position.iter().zip(&coefficients) {
+            assert!((w - c).abs() < 0.5, "{:?} differs from {:?}", parallel.position, coefficients);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut sgd = StochasticGradientDescent::new();
+
+        assert_eq!(sgd.validate(), Ok(()));
+        assert_eq!(sgd.clone().step_widths(vec![0.1, 0.0]).validate(),
+            Err(ConfigError::NonPositiveStepWidth { name: "step_widths", value: 0.0 }));
+        assert_eq!(sgd.shard(2, 2).validate(),
+            Err(ConfigError::OutOfRange { name: "index", value: 2.0, range: "[0, shards)" }));
+        assert_eq!(sgd.shard(2, 1).line_search(ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0))
+            .growth_factor(0.5)
+            .validate(),
+            Err(ConfigError::OutOfRange { name: "growth_factor", value: 0.5, range: "[1, ∞)" }));
+        assert_eq!(CosineAnnealing::try_new(2, 2, 0.5, 0.1).err(),
+            Some(ConfigError::OutOfRange { name: "eta_min", value: 0.5, range: "[0, eta_max]" }));
+    }
+
+    #[test]
+    #[should_panic(expected = "mini_batch = 0 must be in range [1, ∞)")]
+    fn test_invalid_configuration() {
+        let problem = LinearRegression::new(&[1.0, 2.0], 10, 42);
+
+        StochasticGradientDescent::new().mini_batch(0).minimize(&problem, vec![0.0; 2]);
+    }
 }
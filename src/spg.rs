@@ -0,0 +1,375 @@
+use std::collections::VecDeque;
+
+use types::{Function1, Minimizer, Convergence, Report, ConfigError, check_range, check_positive,
+    check_count};
+use utils::dot;
+
+
+/// A Spectral Projected Gradient (SPG) optimizer for functions restricted to a box, i.e.,
+/// a lower and an upper bound for each coordinate.
+///
+/// In each iteration, the position is moved along the projected gradient direction
+/// `d = P(x - α ∇f(x)) - x` using the spectral (Barzilai-Borwein) step width `α = sᵀs / sᵀy`,
+/// where `s` and `y` are the changes of the position and the gradient in the previous
+/// iteration, respectively. Along `d`, a nonmonotone backtracking line search is performed,
+/// i.e., the Armijo rule is evaluated against the maximal value of the last `memory`
+/// iterations instead of the current value. This allows the occasional increase of the value
+/// that makes spectral steps efficient on ill-conditioned problems.
+///
+/// The function is never evaluated outside of the box, assuming that the bounds are the
+/// closed domain of the function.
+#[derive(Debug, Clone)]
+pub struct SpectralProjectedGradient {
+    bounds: Vec<(f64, f64)>,
+    memory: usize,
+    control_parameter: f64,
+    step_width_bounds: (f64, f64),
+    gradient_tolerance: f64,
+    max_iterations: Option<u64>
+}
+
+impl SpectralProjectedGradient {
+    /// Creates a new `SpectralProjectedGradient` optimizer given the `bounds`, i.e., the
+    /// lower and upper bound for each dimension, using the following defaults:
+    ///
+    /// - **`memory`** = `10`
+    /// - **`control_parameter`** = `1e-4`
+    /// - **`step_width_bounds`** = `(1e-10, 1e10)`
+    /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`max_iterations`** = `None`
+    pub fn new(bounds: Vec<(f64, f64)>) -> SpectralProjectedGradient {
+        SpectralProjectedGradient::try_new(bounds).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new `SpectralProjectedGradient` optimizer like `new()`, but returns an error
+    /// instead of panicking if a lower bound is larger than its upper bound.
+    pub fn try_new(bounds: Vec<(f64, f64)>) -> Result<SpectralProjectedGradient, ConfigError> {
+        let invalid = bounds.iter().find(|&&(lower, upper)| {
+            lower > upper || lower.is_nan() || upper.is_nan()
+        });
+
+        if let Some(&(lower, _)) = invalid {
+            return Err(ConfigError::OutOfRange {
+                name: "bounds",
+                value: lower,
+                range: "[-∞, upper bound]"
+            });
+        }
+
+        Ok(SpectralProjectedGradient {
+            bounds,
+            memory: 10,
+            control_parameter: 1.0e-4,
+            step_width_bounds: (1.0e-10, 1.0e10),
+            gradient_tolerance: 1.0e-4,
+            max_iterations: None
+        })
+    }
+
+    /// Adjusts the number of previous values whose maximum serves as reference of the
+    /// nonmonotone line search. A value of `1` results in a monotone line search.
+    pub fn memory(mut self, memory: usize) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    /// Adjusts the control parameter ∈ (0, 1) of the Armijo rule.
+    pub fn control_parameter(mut self, control_parameter: f64) -> Self {
+        self.control_parameter = control_parameter;
+        self
+    }
+
+    /// Adjusts the minimal and maximal spectral step width, which safeguard against
+    /// vanishing or negative curvature.
+    pub fn step_width_bounds(mut self, min_step_width: f64, max_step_width: f64) -> Self {
+        self.step_width_bounds = (min_step_width, max_step_width);
+        self
+    }
+
+    /// Adjusts the gradient tolerance which is used as abort criterion to decide whether
+    /// we reached a stationary point, i.e., a plateau of the projected gradient.
+    pub fn gradient_tolerance(mut self, gradient_tolerance: f64) -> Self {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("memory", self.memory as u64)?;
+        check_range("control_parameter", self.control_parameter, "(0, 1)",
+            self.control_parameter > 0.0 && self.control_parameter < 1.0)?;
+        let (min_step_width, max_step_width) = self.step_width_bounds;
+        check_range("min_step_width", min_step_width, "(0, max_step_width]",
+            min_step_width > 0.0 && min_step_width <= max_step_width)?;
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))
+    }
+
+    // projects the position `x - α g` onto the box
+    fn project(&self, position: &[f64], gradient: &[f64], step_width: f64) -> Vec<f64> {
+        position.iter().zip(gradient).zip(&self.bounds)
+            .map(|((x, g), &(lower, upper))| (x - step_width * g).clamp(lower, upper))
+            .collect()
+    }
+}
+
+impl<F: Function1 + ?Sized> Minimizer<F> for SpectralProjectedGradient {
+    type Solution = Report;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Report {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "SpectralProjectedGradient",
+            memory = self.memory, control_parameter = self.control_parameter,
+            step_width_bounds = ?self.step_width_bounds,
+            gradient_tolerance = self.gradient_tolerance,
+            max_iterations = ?self.max_iterations).entered();
+
+        assert_eq!(initial_position.len(), self.bounds.len(),
+            "number of bounds must match the dimensionality of the position");
+
+        let (min_step_width, max_step_width) = self.step_width_bounds;
+
+        let mut position: Vec<_> = initial_position.iter().zip(&self.bounds)
+            .map(|(x, &(lower, upper))| x.clamp(lower, upper))
+            .collect();
+        let (mut value, mut gradient) = function.value_and_gradient(&position);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut values = VecDeque::with_capacity(self.memory);
+        values.push_back(value);
+
+        let mut step_width = (1.0 / max_norm(&position, &self.project(&position, &gradient, 1.0)))
+            .clamp(min_step_width, max_step_width);
+
+        let mut iteration = 0;
+
+        let convergence = loop {
+            // the projected gradient measures stationarity within the box
+            if max_norm(&position, &self.project(&position, &gradient, 1.0)) <=
+                self.gradient_tolerance
+            {
+                info!("Projected gradient to small, stopping optimization");
+
+                break Convergence::GradientTolerance;
+            }
+
+            let direction: Vec<_> = self.project(&position, &gradient, step_width).iter()
+                .zip(&position)
+                .map(|(p, x)| p - x)
+                .collect();
+
+            let slope = dot(&gradient, &direction);
+            let reference = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+            // nonmonotone backtracking, the box is convex and thus each trial position
+            // remains feasible
+            let mut lambda = 1.0;
+
+            let (next_position, next_value, next_gradient) = loop {
+                let trial: Vec<_> = position.iter().zip(&direction)
+                    .map(|(x, d)| x + lambda * d)
+                    .collect();
+                let trial_value = function.value(&trial);
+
+                if trial_value <= reference + self.control_parameter * lambda * slope ||
+                    lambda < f64::EPSILON
+                {
+                    let trial_gradient = function.gradient(&trial);
+                    break (trial, trial_value, trial_gradient);
+                }
+
+                // minimum of the quadratic interpolation, safeguarded to shrink reasonably
+                let interpolated = -0.5 * lambda * lambda * slope /
+                    (trial_value - value - lambda * slope);
+
+                lambda = if interpolated >= 0.1 * lambda && interpolated <= 0.9 * lambda {
+                    interpolated
+                } else {
+                    0.5 * lambda
+                };
+            };
+
+            let s: Vec<_> = next_position.iter().zip(&position).map(|(a, b)| a - b).collect();
+            let y: Vec<_> = next_gradient.iter().zip(&gradient).map(|(a, b)| a - b).collect();
+
+            let curvature = dot(&s, &y);
+
+            step_width = if curvature > 0.0 {
+                (dot(&s, &s) / curvature).clamp(min_step_width, max_step_width)
+            } else {
+                max_step_width
+            };
+
+            position = next_position;
+            value = next_value;
+            gradient = next_gradient;
+
+            if values.len() == self.memory {
+                values.pop_front();
+            }
+            values.push_back(value);
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}, step_width = {:?}", iteration, value,
+                    position, step_width);
+            } else {
+                debug!("Iteration {:6}: y = {:?}, step_width = {:?}", iteration, value,
+                    step_width);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        Report::new(position, value, iteration, convergence)
+    }
+}
+
+
+// the maximum norm of the difference of `a` and `b`
+fn max_norm(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::{Problem, Sphere, Rosenbrock};
+    use types::{Func1, Minimizer, Convergence, ConfigError};
+    use gd::GradientDescent;
+    use line_search::ArmijoLineSearch;
+
+    use super::SpectralProjectedGradient;
+
+    #[test]
+    fn test_try_new() {
+        assert!(SpectralProjectedGradient::try_new(vec![(0.0, 1.0), (2.0, 2.0)]).is_ok());
+        assert_eq!(SpectralProjectedGradient::try_new(vec![(0.0, 1.0), (3.0, 2.0)]).err(),
+            Some(ConfigError::OutOfRange {
+                name: "bounds",
+                value: 3.0,
+                range: "[-∞, upper bound]"
+            }));
+    }
+
+    mod unbounded {
+        use problems::{Sphere, Rosenbrock};
+
+        use super::SpectralProjectedGradient;
+
+        test_minimizer!{SpectralProjectedGradient::new(vec![(-10.0, 10.0); 2]),
+            sphere => Sphere::default(),
+            rosenbrock => Rosenbrock::default()}
+    }
+
+    mod bounded {
+        use problems::{Problem, Sphere};
+
+        use super::SpectralProjectedGradient;
+
+        test_minimizer!{SpectralProjectedGradient::new(Sphere::bounded(3, 1.0, 3.0).domain()),
+            sphere => Sphere::bounded(3, 1.0, 3.0)}
+    }
+
+    #[test]
+    fn test_bounded_sphere() {
+        // evaluating an illegal position of a problem panics
+        let problem = Sphere::bounded(2, 1.0, 3.0);
+
+        let report = SpectralProjectedGradient::new(problem.domain())
+            .minimize(&problem, vec![2.9, 1.5]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+        assert_eq!(report.position, problem.minimum().0);
+        assert_eq!(report.value, problem.minimum().1);
+
+        // an infeasible initial position is projected onto the box first
+        let report = SpectralProjectedGradient::new(problem.domain())
+            .minimize(&problem, vec![-5.0, 5.0]);
+
+        assert_eq!(report.position, problem.minimum().0);
+    }
+
+    #[test]
+    fn test_ill_conditioned() {
+        // f(x) = ∑ 10ⁱ (xᵢ - cᵢ)² restricted to [-2, 0.5]ⁿ, i.e., the minimum is located on
+        // the boundary for the first and the third coordinate only
+        let center = [1.0, 0.3, 1.0, 0.1];
+
+        let quadratic = Func1(|x: &[f64]| {
+            x.iter().zip(&center).enumerate()
+                .map(|(i, (x, c))| 10.0f64.powi(i as i32) * (x - c).powi(2))
+                .sum()
+        }, |x: &[f64]| {
+            x.iter().zip(&center).enumerate()
+                .map(|(i, (x, c))| 2.0 * 10.0f64.powi(i as i32) * (x - c))
+                .collect()
+        });
+
+        let bounds = vec![(-2.0, 0.5); 4];
+        let start = vec![-1.0, 0.0, -2.0, 0.2];
+
+        let spg = SpectralProjectedGradient::new(bounds.clone())
+            .minimize(&quadratic, start.clone());
+
+        assert_eq!(spg.convergence, Convergence::GradientTolerance);
+
+        for (x, expected) in spg.position.iter().zip(&[0.5, 0.3, 0.5, 0.1]) {
+            assert!((x - expected).abs() < 1.0e-4, "{:?} is not the minimum", spg.position);
+        }
+
+        // the gradient never vanishes at the boundary, thus stop after the same iterations
+        let projected = GradientDescent::new()
+            .line_search(ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0).with_bounds(bounds))
+            .max_iterations(Some(spg.iterations))
+            .minimize(&quadratic, start);
+
+        assert!(spg.value < projected.value, "{} vs. {}", spg.value, projected.value);
+    }
+
+    #[test]
+    fn test_rosenbrock_with_active_bound() {
+        // the unconstrained minimum at [1, 1] is cut off by the upper bound of y
+        let bounds = vec![(-2.0, 2.0), (-2.0, 0.5)];
+
+        let report = SpectralProjectedGradient::new(bounds)
+            .gradient_tolerance(1.0e-8)
+            .minimize(&Rosenbrock::default(), vec![-1.2, 0.0]);
+
+        assert!((report.position[1] - 0.5).abs() < 1.0e-9, "{:?}", report.position);
+        assert!(report.position[0] > 0.7 && report.position[0] < 0.71, "{:?}", report.position);
+    }
+
+    #[test]
+    fn test_validate() {
+        let spg = SpectralProjectedGradient::new(vec![(0.0, 1.0)]);
+
+        assert_eq!(spg.clone().validate(), Ok(()));
+        assert_eq!(spg.step_width_bounds(1.0, 0.5).validate(),
+            Err(ConfigError::OutOfRange {
+                name: "min_step_width", value: 1.0, range: "(0, max_step_width]"
+            }));
+    }
+}
@@ -0,0 +1,222 @@
+use types::{Function1, Minimizer, Convergence, Report, ConfigError, check_range, check_positive,
+    check_count};
+use line_search::{LineSearch, SearchHistory, ArmijoLineSearch};
+use utils::{is_saddle_point, dot};
+
+
+/// A quasi-Newton optimizer using the symmetric rank-one (SR1) update of the inverse
+/// Hessian approximation `H`.
+///
+/// In contrast to BFGS, the update does not enforce positive definiteness and thus often
+/// approximates the true Hessian better, but the resulting direction `-H ∇f(x)` is not
+/// necessarily a descent direction. In that case, the steepest descent direction is used
+/// for the current iteration.
+///
+/// Given the step `s` and the change of the gradient `y`, the update
+/// `H ← H + (s - Hy)(s - Hy)ᵀ / yᵀ(s - Hy)` is skipped unless
+/// `|yᵀ(s - Hy)| ≥ r ‖y‖ ‖s - Hy‖`, which is the standard skip condition with the roles of
+/// `s` and `y` swapped for the inverse update.
+#[derive(Debug, Clone)]
+pub struct SR1<T> {
+    line_search: T,
+    skip_threshold: f64,
+    gradient_tolerance: f64,
+    max_iterations: Option<u64>
+}
+
+impl SR1<ArmijoLineSearch> {
+    /// Creates a new `SR1` optimizer using the following defaults:
+    ///
+    /// - **`line_search`** = `ArmijoLineSearch(0.5, 1.0, 0.5, 0.0)`
+    /// - **`skip_threshold`** = `1e-8`
+    /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`max_iterations`** = `None`
+    pub fn new() -> SR1<ArmijoLineSearch> {
+        SR1 {
+            line_search: ArmijoLineSearch::new(0.5, 1.0, 0.5, 0.0),
+            skip_threshold: 1.0e-8,
+            gradient_tolerance: 1.0e-4,
+            max_iterations: None
+        }
+    }
+}
+
+impl Default for SR1<ArmijoLineSearch> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: LineSearch> SR1<T> {
+    /// Specifies the line search method to use.
+    pub fn line_search<S: LineSearch>(self, line_search: S) -> SR1<S> {
+        SR1 {
+            line_search,
+            skip_threshold: self.skip_threshold,
+            gradient_tolerance: self.gradient_tolerance,
+            max_iterations: self.max_iterations
+        }
+    }
+
+    /// Adjusts the threshold `r` ∈ (0, 1) of the skip condition, below which an update is
+    /// considered as numerically unstable and thus skipped.
+    pub fn skip_threshold(mut self, skip_threshold: f64) -> Self {
+        self.skip_threshold = skip_threshold;
+        self
+    }
+
+    /// Adjusts the gradient tolerance which is used as abort criterion to decide
+    /// whether we reached a plateau.
+    pub fn gradient_tolerance(mut self, gradient_tolerance: f64) -> Self {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_range("skip_threshold", self.skip_threshold, "(0, 1)",
+            self.skip_threshold > 0.0 && self.skip_threshold < 1.0)?;
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))
+    }
+}
+
+impl<F: Function1 + ?Sized, S: LineSearch> Minimizer<F> for SR1<S> {
+    type Solution = Report;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Report {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "SR1",
+            skip_threshold = self.skip_threshold, gradient_tolerance = self.gradient_tolerance,
+            max_iterations = ?self.max_iterations, line_search = ?self.line_search).entered();
+
+        let n = initial_position.len();
+
+        let mut position = initial_position;
+        let (mut value, mut gradient) = function.value_and_gradient(&position);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        // the inverse Hessian approximation stored row by row, starting with the identity
+        let mut inverse_hessian: Vec<_> = (0..n * n)
+            .map(|k| if k / n == k % n { 1.0 } else { 0.0 })
+            .collect();
+
+        let mut iteration = 0;
+        let mut history = SearchHistory::new();
+
+        let convergence = loop {
+            if is_saddle_point(&gradient, self.gradient_tolerance) {
+                info!("Gradient to small, stopping optimization");
+
+                break Convergence::GradientTolerance;
+            }
+
+            let mut direction: Vec<_> = multiply(&inverse_hessian, &gradient).into_iter()
+                .map(|d| -d)
+                .collect();
+
+            // the approximation is indefinite, fall back to the steepest descent
+            if dot(&direction, &gradient) >= 0.0 {
+                direction = gradient.iter().map(|g| -g).collect();
+            }
+
+            let next_position = self.line_search
+                .search_in(&mut history, function, &position, &direction, None)
+                .position;
+            let (next_value, next_gradient) = function.value_and_gradient(&next_position);
+
+            let s: Vec<_> = next_position.iter().zip(&position).map(|(a, b)| a - b).collect();
+            let y: Vec<_> = next_gradient.iter().zip(&gradient).map(|(a, b)| a - b).collect();
+
+            // s - H y
+            let v: Vec<_> = s.iter().zip(multiply(&inverse_hessian, &y))
+                .map(|(s, hy)| s - hy)
+                .collect();
+
+            let denominator = dot(&y, &v);
+            let threshold = self.skip_threshold * dot(&y, &y).sqrt() * dot(&v, &v).sqrt();
+
+            if denominator.abs() >= threshold && denominator != 0.0 {
+                for (k, h) in inverse_hessian.iter_mut().enumerate() {
+                    *h += v[k / n] * v[k % n] / denominator;
+                }
+            }
+
+            position = next_position;
+            value = next_value;
+            gradient = next_gradient;
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        Report::new(position, value, iteration, convergence)
+    }
+}
+
+
+// multiplies the square matrix stored row by row with the vector
+fn multiply(matrix: &[f64], vector: &[f64]) -> Vec<f64> {
+    matrix.chunks(vector.len()).map(|row| dot(row, vector)).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::{Sphere, Rosenbrock};
+    use types::{Minimizer, ConfigError};
+    use gd::GradientDescent;
+
+    use super::SR1;
+
+    test_minimizer!{SR1::new(),
+        sphere => Sphere::default(),
+        rosenbrock => Rosenbrock::default()}
+
+    #[test]
+    fn test_rosenbrock() {
+        let problem = Rosenbrock::default();
+
+        let sr1 = SR1::new().minimize(&problem, vec![-1.2, 1.0]);
+        let gd = GradientDescent::new().minimize(&problem, vec![-1.2, 1.0]);
+
+        assert!((sr1.position[0] - 1.0).abs() < 1.0e-3 && (sr1.position[1] - 1.0).abs() < 1.0e-3,
+            "{:?} is not close to the minimum", sr1.position);
+        assert!(sr1.iterations < gd.iterations, "{} vs. {}", sr1.iterations, gd.iterations);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(SR1::new().validate(), Ok(()));
+        assert_eq!(SR1::new().skip_threshold(1.0).validate(),
+            Err(ConfigError::OutOfRange { name: "skip_threshold", value: 1.0, range: "(0, 1)" }));
+    }
+}
@@ -0,0 +1,161 @@
+use types::{Function1, Minimizer, Convergence, Report, ConfigError, check_step_width, check_count};
+
+
+/// A Subgradient Descent optimizer for non-smooth convex functions, e.g., `f(x) = |x|`.
+///
+/// The `gradient` of the function may return any subgradient at positions where the function
+/// is not differentiable. Since a subgradient step is not necessarily a descent step, no line
+/// search is performed but a diminishing step width `γₖ = a / (b + k)` is used instead.
+/// Consequently, the best position seen so far is returned rather than the last one.
+#[derive(Debug, Copy, Clone)]
+pub struct SubgradientDescent {
+    step_scale: f64,
+    step_offset: f64,
+    max_iterations: Option<u64>
+}
+
+impl SubgradientDescent {
+    /// Creates a new `SubgradientDescent` optimizer using the following defaults:
+    ///
+    /// - **`step_scale`** = `1.0`
+    /// - **`step_offset`** = `1.0`
+    /// - **`max_iterations`** = `1000`
+    pub fn new() -> SubgradientDescent {
+        SubgradientDescent {
+            step_scale: 1.0,
+            step_offset: 1.0,
+            max_iterations: Some(1000)
+        }
+    }
+
+    /// Adjusts the scale `a` of the diminishing step width `γₖ = a / (b + k)`.
+    pub fn step_scale(mut self, step_scale: f64) -> Self {
+        self.step_scale = step_scale;
+        self
+    }
+
+    /// Adjusts the offset `b` of the diminishing step width `γₖ = a / (b + k)`.
+    pub fn step_offset(mut self, step_offset: f64) -> Self {
+        self.step_offset = step_offset;
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_step_width("step_scale", self.step_scale)?;
+        check_step_width("step_offset", self.step_offset)?;
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))
+    }
+}
+
+impl Default for SubgradientDescent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Function1 + ?Sized> Minimizer<F> for SubgradientDescent {
+    type Solution = Report;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Report {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        info!("Starting subgradient descent minimization: step_scale = {:?},
+            step_offset = {:?}, max_iterations = {:?}",
+            self.step_scale, self.step_offset, self.max_iterations);
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "SubgradientDescent",
+            step_scale = self.step_scale, step_offset = self.step_offset,
+            max_iterations = ?self.max_iterations).entered();
+
+        let mut position = initial_position;
+        let mut value = function.value(&position);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut best_position = position.clone();
+        let mut best_value = value;
+
+        let mut iteration = 0;
+
+        let convergence = loop {
+            let subgradient = function.gradient(&position);
+
+            // a vanishing subgradient proves optimality
+            if subgradient.iter().all(|&g| g == 0.0) {
+                info!("Subgradient vanished, stopping optimization");
+
+                break Convergence::GradientTolerance;
+            }
+
+            let step_width = self.step_scale / (self.step_offset + iteration as f64);
+
+            for (x, g) in position.iter_mut().zip(subgradient) {
+                *x -= step_width * g;
+            }
+
+            value = function.value(&position);
+
+            if value < best_value {
+                best_position.clone_from(&position);
+                best_value = value;
+            }
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        Report::new(best_position, best_value, iteration, convergence)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use types::{Func1, Minimizer, ConfigError};
+
+    use super::SubgradientDescent;
+
+    #[test]
+    fn test_absolute_value() {
+        let function = Func1(|x: &[f64]| x[0].abs(), |x: &[f64]| vec![x[0].signum()]);
+
+        let report = SubgradientDescent::new().minimize(&function, vec![3.0]);
+
+        assert!(report.value < 1.0e-2, "{} is not close to zero", report.value);
+        assert_eq!(report.value, report.position[0].abs());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(SubgradientDescent::new().validate(), Ok(()));
+        assert_eq!(SubgradientDescent::new().step_offset(0.0).validate(),
+            Err(ConfigError::NonPositiveStepWidth { name: "step_offset", value: 0.0 }));
+    }
+}
@@ -0,0 +1,317 @@
+use rand::{Rng, SeedableRng, random};
+use rand_pcg::Pcg64Mcg;
+
+use types::{Minimizer, Summation1, SummationAccuracy, Convergence, SgdReport, ConfigError,
+    check_positive, check_step_width, check_count};
+use utils::is_saddle_point;
+
+
+/// Provides _stochastic variance reduced gradient_ (SVRG) optimization.
+///
+/// Periodically, the full gradient `μ = ∇f(x̃)` is computed at a snapshot `x̃` of the
+/// position. In between, each step uses the gradient of a random mini batch `B`, corrected by
+/// its gradient at the snapshot, i.e., `∑ᵢ (∇fᵢ(x) - ∇fᵢ(x̃)) + |B| / n μ` over `i ∈ B`.
+/// This estimate of the (scaled) full gradient is unbiased as for plain stochastic Gradient
+/// Descent, but its variance vanishes as both `x` and `x̃` approach the minimum. Thus, a
+/// constant step width suffices to converge linearly on strongly convex problems, at the cost
+/// of two gradients per term and step as well as an occasional full pass over all terms.
+#[derive(Debug, Clone)]
+pub struct Svrg {
+    rng: Pcg64Mcg,
+    max_iterations: Option<u64>,
+    gradient_tolerance: f64,
+    mini_batch: usize,
+    summation_accuracy: SummationAccuracy,
+    step_width: f64,
+    inner_iterations: Option<usize>,
+    snapshot_frequency: u64
+}
+
+impl Svrg {
+    /// Creates a new `Svrg` optimizer using the following defaults:
+    ///
+    /// - **`step_width`** = `0.01`
+    /// - **`inner_iterations`** = `None`
+    /// - **`snapshot_frequency`** = `1`
+    /// - **`mini_batch`** = `1`
+    /// - **`summation_accuracy`** = `SummationAccuracy::Sequential`
+    /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`max_iterations`** = `1000`
+    ///
+    /// The used random number generator is randomly seeded.
+    pub fn new() -> Svrg {
+        Svrg {
+            rng: Pcg64Mcg::new(random()),
+            max_iterations: Some(1000),
+            gradient_tolerance: 1.0e-4,
+            mini_batch: 1,
+            summation_accuracy: SummationAccuracy::Sequential,
+            step_width: 0.01,
+            inner_iterations: None,
+            snapshot_frequency: 1
+        }
+    }
+
+    /// Seeds the random number generator using the supplied `seed`.
+    ///
+    /// This is useful to create re-producable results.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = Pcg64Mcg::seed_from_u64(seed);
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations, i.e., inner loops. A value of `None`
+    /// instructs the optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(&mut self, max_iterations: Option<u64>) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Adjusts the gradient tolerance which is used as abort criterion to decide whether we
+    /// reached a plateau, evaluated using the full gradient at each snapshot.
+    pub fn gradient_tolerance(&mut self, gradient_tolerance: f64) -> &mut Self {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Adjusts the mini batch size, i.e., how many terms are considered in one step at most.
+    pub fn mini_batch(&mut self, mini_batch: usize) -> &mut Self {
+        self.mini_batch = mini_batch;
+        self
+    }
+
+    /// Adjusts how the terms are summed up when evaluating the objective function, see
+    /// `SummationAccuracy`.
+    pub fn summation_accuracy(&mut self, summation_accuracy: SummationAccuracy) -> &mut Self {
+        self.summation_accuracy = summation_accuracy;
+        self
+    }
+
+    /// Adjusts the step width of the steps within the inner loop.
+    pub fn step_width(&mut self, step_width: f64) -> &mut Self {
+        self.step_width = step_width;
+        self
+    }
+
+    /// Adjusts the number of steps within one inner loop, i.e., one iteration. A value of
+    /// `None` performs as many steps as there are mini batches, i.e., a single pass over the
+    /// terms on average.
+    pub fn inner_iterations(&mut self, inner_iterations: Option<usize>) -> &mut Self {
+        self.inner_iterations = inner_iterations;
+        self
+    }
+
+    /// Adjusts after how many iterations, i.e., inner loops, a new snapshot along with its
+    /// full gradient is taken. Less frequent snapshots save full passes over the terms, but
+    /// correct the stochastic gradients less effectively as the position moves away from
+    /// the snapshot.
+    pub fn snapshot_frequency(&mut self, snapshot_frequency: u64) -> &mut Self {
+        self.snapshot_frequency = snapshot_frequency;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        check_count("mini_batch", self.mini_batch as u64)?;
+        check_step_width("step_width", self.step_width)?;
+        check_count("inner_iterations", self.inner_iterations.unwrap_or(1) as u64)?;
+        check_count("snapshot_frequency", self.snapshot_frequency)
+    }
+}
+
+impl Default for Svrg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Summation1> Minimizer<F> for Svrg {
+    type Solution = SgdReport;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> SgdReport {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "Svrg",
+            max_iterations = ?self.max_iterations, gradient_tolerance = self.gradient_tolerance,
+            mini_batch = self.mini_batch, summation_accuracy = ?self.summation_accuracy,
+            step_width = self.step_width, inner_iterations = ?self.inner_iterations,
+            snapshot_frequency = self.snapshot_frequency).entered();
+
+        let terms = function.terms();
+
+        let mut position = initial_position;
+        let mut value = function.value_with(&position, self.summation_accuracy);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let inner_iterations = self.inner_iterations
+            .unwrap_or_else(|| terms.div_ceil(self.mini_batch));
+
+        let mut iteration = 0;
+        let mut rng = self.rng.clone();
+        let mut batch = Vec::with_capacity(self.mini_batch);
+
+        let mut snapshot = position.clone();
+        let mut full_gradient = Vec::new();
+
+        let convergence = loop {
+            if iteration % self.snapshot_frequency == 0 {
+                snapshot.clone_from(&position);
+                full_gradient = function.gradient(&snapshot);
+
+                if is_saddle_point(&full_gradient, self.gradient_tolerance) {
+                    info!("Gradient to small, stopping optimization");
+
+                    break Convergence::GradientTolerance;
+                }
+            }
+
+            for _ in 0..inner_iterations {
+                batch.clear();
+                batch.extend((0..self.mini_batch).map(|_| rng.gen_range(0, terms)));
+
+                let gradient = function.partial_gradient(&position, &batch);
+                let correction = function.partial_gradient(&snapshot, &batch);
+
+                // the full gradient scaled down to the size of the mini batch
+                let scale = batch.len() as f64 / terms as f64;
+
+                for (((x, g), c), m) in position.iter_mut().zip(gradient).zip(correction)
+                    .zip(&full_gradient)
+                {
+                    *x -= self.step_width * (g - c + scale * m);
+                }
+            }
+
+            value = function.value_with(&position, self.summation_accuracy);
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}", iteration, value, position);
+            } else {
+                debug!("Iteration {:6}: y = {:?}", iteration, value);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        SgdReport::new(position, value, iteration, convergence)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use problems::LinearRegression;
+    use types::{Minimizer, Convergence, Summation, Summation1, ConfigError};
+    use sgd::StochasticGradientDescent;
+
+    use super::Svrg;
+
+    // counts the evaluated term gradients
+    struct CountingTerms {
+        problem: LinearRegression,
+        gradients: Cell<u64>
+    }
+
+    impl Summation for CountingTerms {
+        fn terms(&self) -> usize {
+            self.problem.terms()
+        }
+
+        fn term_value(&self, w: &[f64], term: usize) -> f64 {
+            self.problem.term_value(w, term)
+        }
+    }
+
+    impl Summation1 for CountingTerms {
+        fn term_gradient(&self, w: &[f64], term: usize) -> Vec<f64> {
+            self.gradients.set(self.gradients.get() + 1);
+
+            self.problem.term_gradient(w, term)
+        }
+    }
+
+    #[test]
+    fn test_faster_than_sgd() {
+        let problem = CountingTerms {
+            problem: LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42),
+            gradients: Cell::new(0)
+        };
+
+        let svrg = Svrg::new()
+            .seed(42)
+            .step_width(0.1)
+            .max_iterations(Some(50))
+            .minimize(&problem, vec![1.0; 3]);
+
+        let svrg_gradients = problem.gradients.replace(0);
+
+        // a snapshot and two gradients per step amount to three passes per iteration, while
+        // the constant step width of plain SGD stalls in the noise for any choice
+        for &step_width in &[0.01, 0.03, 0.1] {
+            let sgd = StochasticGradientDescent::new()
+                .seed(42)
+                .step_width(step_width)
+                .max_iterations(Some(150))
+                .minimize(&problem, vec![1.0; 3]);
+
+            let sgd_gradients = problem.gradients.replace(0);
+
+            assert!(svrg_gradients <= sgd_gradients, "{} vs. {}", svrg_gradients, sgd_gradients);
+            assert!(svrg.value < sgd.value, "{} vs. {}", svrg.value, sgd.value);
+        }
+    }
+
+    #[test]
+    fn test_convergence() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 100, 42);
+
+        let report = Svrg::new()
+            .seed(42)
+            .gradient_tolerance(1.0e-6)
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(report.convergence, Convergence::GradientTolerance);
+
+        // less frequent snapshots still converge to the same minimum
+        let infrequent = Svrg::new()
+            .seed(42)
+            .gradient_tolerance(1.0e-6)
+            .snapshot_frequency(3)
+            .inner_iterations(Some(50))
+            .minimize(&problem, vec![1.0; 3]);
+
+        assert_eq!(infrequent.convergence, Convergence::GradientTolerance);
+
+        for (a, b) in report.position.iter().zip(&infrequent.position) {
+            assert!((a - b).abs() < 1.0e-4, "{:?} vs. {:?}", report.position, infrequent.position);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Svrg::new().validate(), Ok(()));
+        assert_eq!(Svrg::new().inner_iterations(Some(0)).validate(),
+            Err(ConfigError::OutOfRange { name: "inner_iterations", value: 0.0, range: "[1, ∞)" }));
+        assert_eq!(Svrg::new().step_width(-0.1).validate(),
+            Err(ConfigError::NonPositiveStepWidth { name: "step_width", value: -0.1 }));
+    }
+}
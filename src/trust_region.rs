@@ -0,0 +1,424 @@
+use std::fmt::Debug;
+
+use types::{HessianProduct, Minimizer, Convergence, TrustRegionReport, ConfigError, check_range,
+    check_positive, check_count};
+use utils::{is_saddle_point, dot, norm};
+
+
+/// Defines a method to approximately solve the trust-region subproblem, i.e., to find a
+/// step `p` minimizing the quadratic model `m(p) = gᵀp + ½ pᵀHp` subject to `‖p‖ ≤ Δ`.
+pub trait StepSolver: Debug {
+    /// Computes the step given the current `position`, the `gradient` at it and the
+    /// trust-region `radius` `Δ`.
+    fn solve<F>(&self, function: &F, position: &[f64], gradient: &[f64], radius: f64) -> Vec<f64>
+        where F: HessianProduct + ?Sized;
+
+    /// Checks the configuration of the solver, which is part of `TrustRegion::validate()`.
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+
+/// Solves the trust-region subproblem using the truncated conjugate gradient method by
+/// Steihaug, requiring Hessian-vector products only.
+///
+/// The conjugate gradient iteration is stopped as soon as it leaves the trust region or
+/// encounters a direction of non-positive curvature, in both cases stepping onto the
+/// boundary. Otherwise, it runs until the residual is sufficiently small, i.e., below
+/// `min(0.5, √‖g‖) ‖g‖`, or `max_iterations` are reached.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SteihaugCG {
+    max_iterations: Option<u64>
+}
+
+impl SteihaugCG {
+    /// Creates a new `SteihaugCG` solver, which runs at most as many iterations as there
+    /// are dimensions, after which the exact solution is found in exact arithmetic.
+    pub fn new() -> SteihaugCG {
+        SteihaugCG {
+            max_iterations: None
+        }
+    }
+
+    /// Adjusts the number of maximally run conjugate gradient iterations. A value of `None`
+    /// uses the number of dimensions.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+}
+
+impl StepSolver for SteihaugCG {
+    fn solve<F>(&self, function: &F, position: &[f64], gradient: &[f64], radius: f64) -> Vec<f64>
+        where F: HessianProduct + ?Sized
+    {
+        let tolerance = norm(gradient).sqrt().min(0.5) * norm(gradient);
+        let max_iterations = self.max_iterations.unwrap_or(position.len() as u64);
+
+        let mut step = vec![0.0; position.len()];
+        let mut residual = gradient.to_vec();
+        let mut direction: Vec<_> = gradient.iter().map(|g| -g).collect();
+
+        if norm(&residual) <= tolerance {
+            return step;
+        }
+
+        for _ in 0..max_iterations {
+            let product = function.hessian_product(position, &direction);
+            let curvature = dot(&direction, &product);
+
+            // the model is unbounded along the direction, follow it up to the boundary
+            if curvature <= 0.0 {
+                return to_boundary(&step, &direction, radius);
+            }
+
+            let alpha = dot(&residual, &residual) / curvature;
+
+            let next_step: Vec<_> = step.iter().zip(&direction)
+                .map(|(p, d)| p + alpha * d)
+                .collect();
+
+            if norm(&next_step) >= radius {
+                return to_boundary(&step, &direction, radius);
+            }
+
+            let next_residual: Vec<_> = residual.iter().zip(&product)
+                .map(|(r, hd)| r + alpha * hd)
+                .collect();
+
+            step = next_step;
+
+            if norm(&next_residual) <= tolerance {
+                break;
+            }
+
+            let beta = dot(&next_residual, &next_residual) / dot(&residual, &residual);
+
+            for (d, r) in direction.iter_mut().zip(&next_residual) {
+                *d = -r + beta * *d;
+            }
+
+            residual = next_residual;
+        }
+
+        step
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))
+    }
+}
+
+
+/// A Trust-Region optimizer, which minimizes a quadratic model of the function within a
+/// region around the current position in each iteration.
+///
+/// The region is enlarged if the model predicts the actual decrease well and shrunk
+/// otherwise. Steps that do not decrease the function sufficiently are rejected.
+#[derive(Debug, Clone)]
+pub struct TrustRegion<S> {
+    step_solver: S,
+    initial_radius: f64,
+    max_radius: f64,
+    acceptance: f64,
+    gradient_tolerance: f64,
+    max_iterations: Option<u64>
+}
+
+impl TrustRegion<SteihaugCG> {
+    /// Creates a new `TrustRegion` optimizer using the following defaults:
+    ///
+    /// - **`step_solver`** = `SteihaugCG::new()`
+    /// - **`initial_radius`** = `1.0`
+    /// - **`max_radius`** = `100.0`
+    /// - **`acceptance`** = `0.1`
+    /// - **`gradient_tolerance`** = `1e-4`
+    /// - **`max_iterations`** = `None`
+    pub fn new() -> TrustRegion<SteihaugCG> {
+        TrustRegion {
+            step_solver: SteihaugCG::new(),
+            initial_radius: 1.0,
+            max_radius: 100.0,
+            acceptance: 0.1,
+            gradient_tolerance: 1.0e-4,
+            max_iterations: None
+        }
+    }
+}
+
+impl Default for TrustRegion<SteihaugCG> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: StepSolver> TrustRegion<S> {
+    /// Specifies the method to solve the trust-region subproblem.
+    pub fn step_solver<T: StepSolver>(self, step_solver: T) -> TrustRegion<T> {
+        TrustRegion {
+            step_solver,
+            initial_radius: self.initial_radius,
+            max_radius: self.max_radius,
+            acceptance: self.acceptance,
+            gradient_tolerance: self.gradient_tolerance,
+            max_iterations: self.max_iterations
+        }
+    }
+
+    /// Adjusts the initial and the maximal radius of the trust region.
+    pub fn radius(mut self, initial_radius: f64, max_radius: f64) -> Self {
+        self.initial_radius = initial_radius;
+        self.max_radius = max_radius;
+        self
+    }
+
+    /// Adjusts the minimal ratio of actual to predicted decrease required to accept a step.
+    pub fn acceptance(mut self, acceptance: f64) -> Self {
+        self.acceptance = acceptance;
+        self
+    }
+
+    /// Adjusts the gradient tolerance which is used as abort criterion to decide
+    /// whether we reached a plateau.
+    pub fn gradient_tolerance(mut self, gradient_tolerance: f64) -> Self {
+        self.gradient_tolerance = gradient_tolerance;
+        self
+    }
+
+    /// Adjusts the number of maximally run iterations. A value of `None` instructs the
+    /// optimizer to ignore the nubmer of iterations.
+    pub fn max_iterations(mut self, max_iterations: Option<u64>) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks the configuration, which the minimization requires to be valid, returning the
+    /// first invalid parameter as error.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_range("initial_radius", self.initial_radius, "(0, max_radius]",
+            self.initial_radius > 0.0 && self.initial_radius <= self.max_radius)?;
+        check_range("acceptance", self.acceptance, "[0, 0.25)",
+            (0.0..0.25).contains(&self.acceptance))?;
+        check_positive("gradient_tolerance", self.gradient_tolerance)?;
+        check_count("max_iterations", self.max_iterations.unwrap_or(1))?;
+        self.step_solver.validate()
+    }
+}
+
+impl<F: HessianProduct + ?Sized, S: StepSolver> Minimizer<F> for TrustRegion<S> {
+    type Solution = TrustRegionReport;
+
+    fn minimize(&self, function: &F, initial_position: Vec<f64>) -> TrustRegionReport {
+        self.validate().unwrap_or_else(|error| panic!("{}", error));
+
+        #[cfg(feature = "tracing")]
+        let _span = info_span!("minimize", algorithm = "TrustRegion",
+            step_solver = ?self.step_solver, initial_radius = self.initial_radius,
+            max_radius = self.max_radius, acceptance = self.acceptance,
+            gradient_tolerance = self.gradient_tolerance,
+            max_iterations = ?self.max_iterations).entered();
+
+        let mut position = initial_position;
+        let (mut value, mut gradient) = function.value_and_gradient(&position);
+
+        if trace_enabled!() {
+            info!("Starting with y = {:?} for x = {:?}", value, position);
+        } else {
+            info!("Starting with y = {:?}", value);
+        }
+
+        let mut iteration = 0;
+        let mut radius = self.initial_radius;
+        let mut radii = Vec::new();
+        let mut rejections = 0;
+
+        let convergence = loop {
+            if is_saddle_point(&gradient, self.gradient_tolerance) {
+                info!("Gradient to small, stopping optimization");
+
+                break Convergence::GradientTolerance;
+            }
+
+            if radius < f64::EPSILON {
+                info!("Trust region to small, stopping optimization");
+
+                break Convergence::SmallStep;
+            }
+
+            let step = self.step_solver.solve(function, &position, &gradient, radius);
+
+            let product = function.hessian_product(&position, &step);
+            let predicted = -(dot(&gradient, &step) + 0.5 * dot(&step, &product));
+
+            let candidate: Vec<_> = position.iter().zip(&step).map(|(x, p)| x + p).collect();
+            let (candidate_value, candidate_gradient) = function.value_and_gradient(&candidate);
+
+            let ratio = if predicted > 0.0 {
+                (value - candidate_value) / predicted
+            } else {
+                -1.0
+            };
+
+            if ratio < 0.25 {
+                radius *= 0.25;
+            } else if ratio > 0.75 && norm(&step) >= 0.99 * radius {
+                radius = (2.0 * radius).min(self.max_radius);
+            }
+
+            if ratio > self.acceptance {
+                position = candidate;
+                value = candidate_value;
+                gradient = candidate_gradient;
+            } else {
+                rejections += 1;
+
+                debug!("Rejected step with ratio = {:?}, shrinking radius to {:?}", ratio,
+                    radius);
+            }
+
+            radii.push(radius);
+
+            iteration += 1;
+
+            if trace_enabled!() {
+                debug!("Iteration {:6}: y = {:?}, x = {:?}, radius = {:?}", iteration, value,
+                    position, radius);
+            } else {
+                debug!("Iteration {:6}: y = {:?}, radius = {:?}", iteration, value, radius);
+            }
+
+            let reached_max_iterations = self.max_iterations == Some(iteration);
+
+            if reached_max_iterations {
+                info!("Reached maximal number of iterations, stopping optimization");
+
+                break Convergence::MaxIterations;
+            }
+        };
+
+        TrustRegionReport::new(position, value, iteration, convergence, radii, rejections)
+    }
+}
+
+
+// follows the `direction` from the `step` inside the trust region onto its boundary
+fn to_boundary(step: &[f64], direction: &[f64], radius: f64) -> Vec<f64> {
+    let a = dot(direction, direction);
+    let b = 2.0 * dot(step, direction);
+    let c = dot(step, step) - radius * radius;
+
+    let tau = (-b + (b * b - 4.0 * a * c).sqrt()) / (2.0 * a);
+
+    step.iter().zip(direction).map(|(p, d)| p + tau * d).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use problems::{Sphere, Rosenbrock, RosenbrockN};
+    use types::{Function, Function1, HessianProduct, Minimizer, ConfigError};
+
+    use super::{TrustRegion, SteihaugCG, StepSolver, norm};
+
+    test_minimizer!{TrustRegion::new(),
+        sphere => Sphere::default(),
+        rosenbrock => Rosenbrock::default(),
+        rosenbrock_n => RosenbrockN::new(3)}
+
+    // nonconvex quadratic f(x, y) = ½ (x² - y²) with a saddle point at the origin
+    struct Saddle;
+
+    impl Function for Saddle {
+        fn value(&self, x: &[f64]) -> f64 {
+            0.5 * (x[0].powi(2) - x[1].powi(2))
+        }
+    }
+
+    impl Function1 for Saddle {
+        fn gradient(&self, x: &[f64]) -> Vec<f64> {
+            vec![x[0], -x[1]]
+        }
+    }
+
+    impl HessianProduct for Saddle {
+        fn hessian_product(&self, _x: &[f64], v: &[f64]) -> Vec<f64> {
+            vec![v[0], -v[1]]
+        }
+    }
+
+    #[test]
+    fn test_negative_curvature() {
+        let position = [0.1, 1.0];
+        let gradient = Saddle.gradient(&position);
+
+        for &radius in &[0.5, 2.0, 10.0] {
+            let step = SteihaugCG::new().solve(&Saddle, &position, &gradient, radius);
+
+            // the model is unbounded, thus the step has to end on the boundary
+            assert!((norm(&step) - radius).abs() < 1.0e-9, "{:?} is not on the boundary", step);
+
+            let next: Vec<_> = position.iter().zip(&step).map(|(x, p)| x + p).collect();
+
+            assert!(Saddle.value(&next) < Saddle.value(&position));
+        }
+    }
+
+    #[test]
+    fn test_newton_step() {
+        let sphere = Sphere::new(3);
+        let position = [1.0, -2.0, 0.5];
+        let gradient = sphere.gradient(&position);
+
+        // a sufficiently large region contains the minimum of the exact quadratic model
+        let step = SteihaugCG::new().solve(&sphere, &position, &gradient, 10.0);
+
+        for (x, p) in position.iter().zip(&step) {
+            assert!((x + p).abs() < 1.0e-12);
+        }
+
+        // otherwise, the step is truncated at the boundary
+        let step = SteihaugCG::new().solve(&sphere, &position, &gradient, 1.0);
+
+        assert!((norm(&step) - 1.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn test_report() {
+        // the quadratic model is poor far away from the minimum, thus the large initial
+        // region causes rejections
+        let report = TrustRegion::new()
+            .radius(10.0, 100.0)
+            .minimize(&Rosenbrock::default(), vec![-1.2, 1.0]);
+
+        assert_eq!(report.radii.len() as u64, report.iterations);
+        assert!(report.rejections > 0);
+        assert!(report.radii[report.radii.len() - 1] < 1.0, "{:?}", report.radii);
+
+        // each rejection shrinks the region
+        let mut previous = 10.0;
+        let mut shrunk = 0;
+
+        for &radius in &report.radii {
+            if radius < previous {
+                shrunk += 1;
+            }
+
+            previous = radius;
+        }
+
+        assert!(shrunk >= report.rejections, "{} vs. {}", shrunk, report.rejections);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(TrustRegion::new().validate(), Ok(()));
+        assert_eq!(TrustRegion::new().radius(2.0, 1.0).validate(),
+            Err(ConfigError::OutOfRange {
+                name: "initial_radius", value: 2.0, range: "(0, max_radius]"
+            }));
+        assert_eq!(TrustRegion::new().step_solver(SteihaugCG::new().max_iterations(Some(0)))
+            .validate(),
+            Err(ConfigError::OutOfRange { name: "max_iterations", value: 0.0, range: "[1, ∞)" }));
+    }
+}
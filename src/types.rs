@@ -1,4 +1,14 @@
 use std::borrow::Borrow;
+use std::cmp::min;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use rand::Rng;
+use rand::seq::index::sample;
+
+use problems::Problem;
+use adapters::{Tracking, Negated};
 
 
 /// Defines an objective function `f` that is subject to minimization.
@@ -8,6 +18,14 @@ use std::borrow::Borrow;
 pub trait Function {
     /// Computes the objective function at a given `position` `x`, i.e., `f(x) = y`.
     fn value(&self, position: &[f64]) -> f64;
+
+    /// Returns whether the function is known to be convex, e.g., to select an appropriate
+    /// algorithm or to warn about applying a method that requires convexity.
+    ///
+    /// By default, the property is unknown, i.e., `None`.
+    fn is_convex(&self) -> Option<bool> {
+        None
+    }
 }
 
 
@@ -21,6 +39,8 @@ impl<F: Fn(&[f64]) -> f64> Function for Func<F> {
     }
 }
 
+impl_add!{[F: Fn(&[f64]) -> f64,] Func<F>}
+
 
 /// Defines an objective function `f` that is able to compute the first derivative
 /// `f'(x)`.
@@ -28,6 +48,130 @@ pub trait Function1: Function {
     /// Computes the gradient of the objective function at a given `position` `x`,
     /// i.e., `∀ᵢ ∂/∂xᵢ f(x) = ∇f(x)`.
     fn gradient(&self, position: &[f64]) -> Vec<f64>;
+
+    /// Computes both the value and the gradient of the objective function at a given
+    /// `position` `x`, i.e., `(f(x), ∇f(x))`.
+    ///
+    /// By default, `value()` and `gradient()` are called one after another. Implementors
+    /// should override this method if both can be computed cheaper at once, e.g., by sharing
+    /// intermediate results.
+    fn value_and_gradient(&self, position: &[f64]) -> (f64, Vec<f64>) {
+        (self.value(position), self.gradient(position))
+    }
+}
+
+
+/// New-type to support optimization of arbitrary functions along with their first
+/// derivative without requiring to implement a trait.
+pub struct Func1<F: Fn(&[f64]) -> f64, G: Fn(&[f64]) -> Vec<f64>>(pub F, pub G);
+
+impl<F: Fn(&[f64]) -> f64, G: Fn(&[f64]) -> Vec<f64>> Function for Func1<F, G> {
+    fn value(&self, position: &[f64]) -> f64 {
+        self.0(position)
+    }
+}
+
+impl<F: Fn(&[f64]) -> f64, G: Fn(&[f64]) -> Vec<f64>> Function1 for Func1<F, G> {
+    fn gradient(&self, position: &[f64]) -> Vec<f64> {
+        self.1(position)
+    }
+}
+
+impl_add!{[F: Fn(&[f64]) -> f64, G: Fn(&[f64]) -> Vec<f64>,] Func1<F, G>}
+
+
+/// Defines an objective function `f` that is able to compute the second derivative, i.e.,
+/// the Hessian `H(x)`.
+pub trait Function2: Function1 {
+    /// Computes the `n × n` Hessian of the objective function at a given `position` `x`,
+    /// i.e., `Hᵢⱼ = ∂²/∂xᵢ∂xⱼ f(x)`, stored row by row, i.e., `Hᵢⱼ` is located at index
+    /// `i · n + j`.
+    fn hessian(&self, position: &[f64]) -> Vec<f64>;
+}
+
+
+/// Defines an objective function `f` whose evaluation mutates internal state, e.g., caches
+/// of an incremental simulation, without resorting to interior mutability.
+///
+/// Such functions are minimized through dedicated methods, e.g.,
+/// `GradientDescent::minimize_mut()`.
+pub trait MutFunction {
+    /// Computes the objective function at a given `position` `x`, i.e., `f(x) = y`.
+    fn value(&mut self, position: &[f64]) -> f64;
+}
+
+
+/// Defines a stateful objective function `f` that is able to compute the first derivative
+/// `f'(x)`, see `MutFunction`.
+pub trait MutFunction1: MutFunction {
+    /// Computes the gradient of the objective function at a given `position` `x`,
+    /// i.e., `∀ᵢ ∂/∂xᵢ f(x) = ∇f(x)`.
+    fn gradient(&mut self, position: &[f64]) -> Vec<f64>;
+
+    /// Computes both the value and the gradient of the objective function at a given
+    /// `position` `x`, i.e., `(f(x), ∇f(x))`.
+    ///
+    /// By default, `value()` and `gradient()` are called one after another.
+    fn value_and_gradient(&mut self, position: &[f64]) -> (f64, Vec<f64>) {
+        (self.value(position), self.gradient(position))
+    }
+}
+
+
+/// Defines an objective function `f` that is able to compute products of its second
+/// derivative, the Hessian `H(x)`, with arbitrary vectors.
+///
+/// In contrast to the Hessian itself, such products can be computed in linear time and
+/// space in many cases, which allows second-order methods in high dimensions.
+pub trait HessianProduct: Function1 {
+    /// Computes the product of the Hessian at a given `position` `x` and a `direction`
+    /// `v`, i.e., `H(x) v`.
+    fn hessian_product(&self, position: &[f64], direction: &[f64]) -> Vec<f64>;
+}
+
+
+/// Defines a vector-valued function `g: Rⁿ → Rᵐ`, e.g., an inner function of a composition.
+pub trait VectorFunction {
+    /// Computes the vector-valued function at a given `position` `x`, i.e., `g(x) = y`.
+    fn value(&self, position: &[f64]) -> Vec<f64>;
+}
+
+
+/// Defines a vector-valued function `g` that is able to compute its Jacobian `J`.
+pub trait VectorFunction1: VectorFunction {
+    /// Computes the `m × n` Jacobian of the function at a given `position` `x`, i.e.,
+    /// `Jᵢⱼ = ∂/∂xⱼ gᵢ(x)`, stored row by row, i.e., `Jᵢⱼ` is located at index `i · n + j`.
+    fn jacobian(&self, position: &[f64]) -> Vec<f64>;
+
+    /// Computes the product of the Jacobian at a given `position` `x` and a `direction` `v`,
+    /// i.e., the directional derivative `J(x) v`.
+    ///
+    /// By default, the Jacobian is computed explicitly, which should be avoided for large
+    /// functions, e.g., using forward-mode differentiation.
+    fn jacobian_product(&self, position: &[f64], direction: &[f64]) -> Vec<f64> {
+        let n = position.len();
+
+        self.jacobian(position).chunks(n)
+            .map(|row| row.iter().zip(direction).map(|(j, v)| j * v).sum())
+            .collect()
+    }
+
+    /// Computes the product of the transposed Jacobian at a given `position` `x` and a
+    /// `vector` `u` of the dimensionality of the codomain, i.e., `J(x)ᵀ u`.
+    ///
+    /// By default, the Jacobian is computed explicitly, which should be avoided for large
+    /// functions, e.g., using reverse-mode differentiation.
+    fn jacobian_transpose_product(&self, position: &[f64], vector: &[f64]) -> Vec<f64> {
+        let mut product = vec![0.0; position.len()];
+
+        for (row, u) in self.jacobian(position).chunks(position.len()).zip(vector) {
+            for (p, j) in product.iter_mut().zip(row) {
+                *p += j * u;
+            }
+        }
+
+        product
+    }
 }
 
 
@@ -40,6 +184,23 @@ pub trait Summation: Function {
     /// given the `position` `x`.
     fn term_value(&self, position: &[f64], term: usize) -> f64;
 
+    /// Partitions the terms into `shards` contiguous ranges of balanced size, e.g., to
+    /// distribute the terms across several workers, and returns the range of the shard with
+    /// the given `index`.
+    fn shard(&self, shards: usize, index: usize) -> Range<usize> {
+        assert!(shards > 0, "shards must be greater than 0");
+        assert!(index < shards, "index must be less than shards");
+
+        let size = self.terms() / shards;
+        let remainder = self.terms() % shards;
+
+        // the first shards take one of the remaining terms each
+        let start = index * size + min(index, remainder);
+        let end = start + size + if index < remainder { 1 } else { 0 };
+
+        start..end
+    }
+
     /// Computes the partial sum over a set of individual functions identified by `terms`.
     fn partial_value<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, position: &[f64], terms: T) -> f64 {
         let mut value = 0.0;
@@ -50,12 +211,120 @@ pub trait Summation: Function {
 
         value
     }
+
+    /// Computes the partial sum over a set of individual functions identified by `terms`
+    /// using the supplied summation `accuracy`.
+    fn partial_value_with<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, position: &[f64], terms: T,
+        accuracy: SummationAccuracy) -> f64
+    {
+        match accuracy {
+            SummationAccuracy::Sequential => self.partial_value(position, terms),
+            _ => accuracy.sum(terms.into_iter().map(|term| self.term_value(position, *term.borrow())))
+        }
+    }
+
+    /// Computes the sum over all individual functions using the supplied summation `accuracy`.
+    fn value_with(&self, position: &[f64], accuracy: SummationAccuracy) -> f64 {
+        self.partial_value_with(position, 0..self.terms(), accuracy)
+    }
+
+    /// Estimates the sum over all individual functions from a random subset of them, i.e.,
+    /// the partial sum over `k = ⌈fraction · n⌉` terms drawn without replacement, scaled by
+    /// `n / k`, e.g., to monitor the progress of a stochastic minimization cheaply.
+    ///
+    /// The estimate is unbiased and its standard deviation shrinks with `√k`, at least as
+    /// long as `k` is small compared to `n`. A `fraction` of `1` sums up all terms, albeit in
+    /// random order.
+    fn value_sample<R: Rng + ?Sized>(&self, position: &[f64], fraction: f64, rng: &mut R) -> f64 {
+        self.partial_value_sample(position, 0..self.terms(), fraction,
+            SummationAccuracy::Sequential, rng)
+    }
+
+    /// Estimates the partial sum over the contiguous range of `terms`, e.g., a `shard()`, like
+    /// `value_sample()` from a random subset of them, using the supplied summation `accuracy`.
+    fn partial_value_sample<R: Rng + ?Sized>(&self, position: &[f64], terms: Range<usize>,
+        fraction: f64, accuracy: SummationAccuracy, rng: &mut R) -> f64
+    {
+        assert!(fraction > 0.0 && fraction <= 1.0, "fraction must be in range (0, 1]");
+
+        let samples = ((fraction * terms.len() as f64).ceil() as usize).min(terms.len());
+
+        if samples == 0 {
+            return 0.0;
+        }
+
+        let sampled = sample(rng, terms.len(), samples).into_iter().map(|i| terms.start + i);
+
+        self.partial_value_with(position, sampled, accuracy) * terms.len() as f64 /
+            samples as f64
+    }
+
+    /// Returns whether the sum is known to be convex, which is reported by its
+    /// `Function::is_convex()`.
+    ///
+    /// By default, the property is unknown, i.e., `None`.
+    fn is_convex_sum(&self) -> Option<bool> {
+        None
+    }
 }
 
 impl<S: Summation> Function for S {
     fn value(&self, position: &[f64]) -> f64 {
         self.partial_value(position, 0..self.terms())
     }
+
+    fn is_convex(&self) -> Option<bool> {
+        self.is_convex_sum()
+    }
+}
+
+
+/// Specifies how the individual values of a `Summation` are summed up, trading speed for
+/// a lower floating point error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SummationAccuracy {
+    /// Sums the values from left to right, whose error grows linearly with the number of terms.
+    Sequential,
+    /// Compensates the lost low-order bits of each addition, also known as Kahan summation,
+    /// whose error is independent of the number of terms.
+    Kahan,
+    /// Recursively sums up both halves of the values, whose error grows logarithmically with
+    /// the number of terms.
+    Pairwise
+}
+
+impl SummationAccuracy {
+    /// Sums up the supplied `values`.
+    pub fn sum<I: IntoIterator<Item=f64>>(self, values: I) -> f64 {
+        match self {
+            SummationAccuracy::Sequential => values.into_iter().fold(0.0, |sum, value| sum + value),
+            SummationAccuracy::Kahan => {
+                let mut sum = 0.0;
+                let mut compensation = 0.0;
+
+                for value in values {
+                    let y = value - compensation;
+                    let t = sum + y;
+
+                    compensation = (t - sum) - y;
+                    sum = t;
+                }
+
+                sum
+            },
+            SummationAccuracy::Pairwise => pairwise_sum(&values.into_iter().collect::<Vec<_>>())
+        }
+    }
+}
+
+fn pairwise_sum(values: &[f64]) -> f64 {
+    if values.len() <= 8 {
+        values.iter().fold(0.0, |sum, value| sum + value)
+    } else {
+        let (first, second) = values.split_at(values.len() / 2);
+
+        pairwise_sum(first) + pairwise_sum(second)
+    }
 }
 
 
@@ -66,6 +335,28 @@ pub trait Summation1: Summation + Function1 {
     /// `position`.
     fn term_gradient(&self, position: &[f64], term: usize) -> Vec<f64>;
 
+    /// Computes the gradient of one individual function identified by `term` at the given
+    /// `position` as sparse vector, i.e., as pairs of coordinate and non-zero component.
+    ///
+    /// By default, the non-zero components of `term_gradient()` are collected. Implementors
+    /// should override this method along with `has_sparse_gradients()` if each term only
+    /// depends on a few coordinates, which allows minimizers like `StochasticGradientDescent`
+    /// to update just these.
+    fn term_gradient_sparse(&self, position: &[f64], term: usize) -> Vec<(usize, f64)> {
+        self.term_gradient(position, term).into_iter()
+            .enumerate()
+            .filter(|&(_, g)| g != 0.0)
+            .collect()
+    }
+
+    /// Returns whether `term_gradient_sparse()` is overridden to provide sparse gradients,
+    /// which `StochasticGradientDescent` then prefers over `partial_gradient()`.
+    ///
+    /// By default, `false` is returned.
+    fn has_sparse_gradients(&self) -> bool {
+        false
+    }
+
     /// Computes the partial gradient over a set of `terms` at the given `position`.
     fn partial_gradient<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, position: &[f64], terms: T) -> Vec<f64> {
         let mut gradient = vec![0.0; position.len()];
@@ -87,6 +378,36 @@ impl<S: Summation1> Function1 for S {
 }
 
 
+/// Defines a summation of individual functions `fᵢ(x)`, assuming that each function has a
+/// second derivative, e.g., for stochastic Newton methods.
+pub trait Summation2: Summation1 + Function2 {
+    /// Computes the Hessian of one individual function identified by `term` at the given
+    /// `position`, stored row by row as for `Function2::hessian()`.
+    fn term_hessian(&self, position: &[f64], term: usize) -> Vec<f64>;
+
+    /// Computes the partial Hessian over a set of `terms` at the given `position`.
+    fn partial_hessian<T: IntoIterator<Item=I>, I: Borrow<usize>>(&self, position: &[f64],
+        terms: T) -> Vec<f64>
+    {
+        let mut hessian = vec![0.0; position.len() * position.len()];
+
+        for term in terms {
+            for (h, hi) in hessian.iter_mut().zip(self.term_hessian(position, *term.borrow())) {
+                *h += hi;
+            }
+        }
+
+        hessian
+    }
+}
+
+impl<S: Summation2> Function2 for S {
+    fn hessian(&self, position: &[f64]) -> Vec<f64> {
+        self.partial_hessian(position, 0..self.terms())
+    }
+}
+
+
 /// Defines an optimizer that is able to minimize a given objective function `F`.
 pub trait Minimizer<F: ?Sized> {
     /// Type of the solution the `Minimizer` returns.
@@ -95,6 +416,79 @@ pub trait Minimizer<F: ?Sized> {
     /// Performs the actual minimization and returns a solution that
     /// might be better than the initially provided one.
     fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Self::Solution;
+
+    /// Performs a minimization from each of the supplied `starts` and returns the
+    /// solution with the lowest value, where a NaN value is considered the worst one.
+    fn minimize_from_best_of(&self, function: &F, starts: Vec<Vec<f64>>) -> Self::Solution {
+        assert!(!starts.is_empty(), "starts must not be empty");
+
+        let mut best: Option<Self::Solution> = None;
+
+        for start in starts {
+            let solution = self.minimize(function, start);
+
+            if best.as_ref().is_none_or(|best| best.value().is_nan() ||
+                solution.value() < best.value())
+            {
+                best = Some(solution);
+            }
+        }
+
+        best.unwrap()
+    }
+
+    /// Repairs the `initial_position` using `Problem::repair` such that it becomes legal
+    /// before performing the actual minimization.
+    fn minimize_repaired(&self, function: &F, mut initial_position: Vec<f64>) -> Self::Solution
+        where F: Problem
+    {
+        if !function.is_legal_position(&initial_position) {
+            function.repair(&mut initial_position);
+
+            info!("Repaired illegal initial position");
+        }
+
+        self.minimize(function, initial_position)
+    }
+
+    /// Performs the actual minimization, but catches a panic of the function or the minimizer.
+    ///
+    /// Returns the solution along with a flag indicating whether a panic has been caught.
+    /// In that case, the best position evaluated so far is returned, which falls back to
+    /// the initial position if no evaluation succeeded.
+    fn minimize_catching(&self, function: &F, initial_position: Vec<f64>) -> (Solution, bool)
+        where F: Function, Self: for<'a> Minimizer<Tracking<'a, F>>
+    {
+        let tracking = Tracking::new(function);
+
+        // after a panic only the best evaluation is read, which is consistent at any time
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            self.minimize(&tracking, initial_position.clone())
+        }));
+
+        match result {
+            Ok(solution) => (Solution::new(solution.position().to_vec(), solution.value()), false),
+            Err(_) => {
+                warn!("Caught a panic during the minimization, returning the best evaluation");
+
+                let (position, value) = tracking.best().unwrap_or((initial_position, f64::NAN));
+
+                (Solution::new(position, value), true)
+            }
+        }
+    }
+
+    /// Maximizes the function instead by minimizing its negation, see `Negated`.
+    ///
+    /// The returned solution carries the maximal value `f(x)` found, i.e., the value of the
+    /// negated function is negated back.
+    fn maximize(&self, function: &F, initial_position: Vec<f64>) -> Solution
+        where F: Function, Self: for<'a> Minimizer<Negated<'a, F>>
+    {
+        let solution = self.minimize(&Negated::new(function), initial_position);
+
+        Solution::new(solution.position().to_vec(), -solution.value())
+    }
 }
 
 
@@ -105,6 +499,57 @@ pub trait Evaluation {
 
     /// The actual value `f(x)`.
     fn value(&self) -> f64;
+
+    /// The number of iterations it took to find the position, i.e., the number of epochs for
+    /// stochastic minimizers, if reported by the solution.
+    fn iterations(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<E: Evaluation + ?Sized> Evaluation for &E {
+    fn position(&self) -> &[f64] {
+        (**self).position()
+    }
+
+    fn value(&self) -> f64 {
+        (**self).value()
+    }
+
+    fn iterations(&self) -> Option<u64> {
+        (**self).iterations()
+    }
+}
+
+
+/// Extends iterators over `Evaluation`s, e.g., the solutions of several minimizations.
+pub trait MinByValue: Iterator {
+    /// Returns the evaluation with the lowest value, ignoring evaluations whose value is NaN.
+    /// If several evaluations share the lowest value, the first one is returned.
+    fn min_by_value(self) -> Option<Self::Item>;
+}
+
+impl<I: Iterator> MinByValue for I where I::Item: Evaluation {
+    fn min_by_value(self) -> Option<Self::Item> {
+        let mut best: Option<Self::Item> = None;
+
+        for evaluation in self {
+            if !evaluation.value().is_nan() &&
+                best.as_ref().is_none_or(|best| evaluation.value() < best.value())
+            {
+                best = Some(evaluation);
+            }
+        }
+
+        best
+    }
+}
+
+
+/// Returns the evaluation with the lowest value among the `evaluations`, ignoring evaluations
+/// whose value is NaN.
+pub fn best<E: Evaluation>(evaluations: &[E]) -> Option<&E> {
+    evaluations.iter().min_by_value()
 }
 
 
@@ -139,3 +584,560 @@ impl Evaluation for Solution {
         self.value
     }
 }
+
+
+/// Describes an invalid configuration, as returned by the fallible constructors, e.g.,
+/// `ArmijoLineSearch::try_new()`, whereas the plain constructors panic with its description.
+///
+/// The builder methods of the minimizers accept any value, which their `validate()` methods
+/// check instead, e.g., `GradientDescent::validate()`. The minimization panics with the
+/// description of the first invalid parameter.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The step width `name` is not greater than `0` or not finite.
+    NonPositiveStepWidth {
+        /// The name of the offending parameter.
+        name: &'static str,
+        /// The supplied value.
+        value: f64
+    },
+    /// The parameter `name` lies outside of its valid range.
+    OutOfRange {
+        /// The name of the offending parameter.
+        name: &'static str,
+        /// The supplied value.
+        value: f64,
+        /// The valid range, e.g., `(0, 1)`.
+        range: &'static str
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::NonPositiveStepWidth { name, value } =>
+                write!(f, "{} = {:?} must be greater than 0 and finite", name, value),
+            ConfigError::OutOfRange { name, value, range } =>
+                write!(f, "{} = {} must be in range {}", name, value, range)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Ensures that the step width `name` is greater than `0` and finite.
+pub fn check_step_width(name: &'static str, value: f64) -> Result<(), ConfigError> {
+    if value > 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(ConfigError::NonPositiveStepWidth { name, value })
+    }
+}
+
+/// Ensures that the parameter `name` is `valid`, i.e., lies within the `range`.
+pub fn check_range(name: &'static str, value: f64, range: &'static str, valid: bool)
+    -> Result<(), ConfigError>
+{
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::OutOfRange { name, value, range })
+    }
+}
+
+/// Ensures that the parameter `name` is greater than `0`, e.g., a tolerance.
+pub fn check_positive(name: &'static str, value: f64) -> Result<(), ConfigError> {
+    check_range(name, value, "(0, ∞]", value > 0.0)
+}
+
+/// Ensures that the count `name` is at least `1`, e.g., a number of iterations.
+pub fn check_count(name: &'static str, count: u64) -> Result<(), ConfigError> {
+    check_range(name, count as f64, "[1, ∞)", count > 0)
+}
+
+
+/// Specifies the reason why a `Minimizer` stopped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Convergence {
+    /// All absolute gradient components fell below the gradient tolerance.
+    GradientTolerance,
+    /// The maximal number of iterations has been reached.
+    MaxIterations,
+    /// The accepted steps have been smaller than the minimal step for several iterations.
+    SmallStep,
+    /// The value dropped to or below the target value.
+    TargetValue,
+    /// The value changed less than the value tolerance between two consecutive iterations,
+    /// i.e., the minimization reached a plateau.
+    ValueTolerance,
+    /// The position changed less than the position tolerance relative to its norm between
+    /// two consecutive iterations.
+    PositionTolerance
+}
+
+
+/// A solution of a minimization run that additionally reports how the run went.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Position `x` of the lowest corresponding value `f(x)` that has been found.
+    pub position: Vec<f64>,
+    /// The actual value `f(x)`.
+    pub value: f64,
+    /// The number of performed iterations.
+    pub iterations: u64,
+    /// The reason why the minimization stopped.
+    pub convergence: Convergence,
+    /// The accepted iterates `(x, f(x))` of each iteration, if recorded.
+    pub trajectory: Option<Vec<(Vec<f64>, f64)>>,
+    /// The step widths chosen by the line search in each iteration, if recorded.
+    pub step_widths: Option<Vec<f64>>
+}
+
+impl Report {
+    /// Creates a new `Report` given the `position`, the corresponding `value`, the number of
+    /// `iterations` as well as the `convergence` reason, without a recorded trajectory and
+    /// step widths.
+    pub fn new(position: Vec<f64>, value: f64, iterations: u64, convergence: Convergence) -> Report {
+        Report {
+            position,
+            value,
+            iterations,
+            convergence,
+            trajectory: None,
+            step_widths: None
+        }
+    }
+}
+
+impl Evaluation for Report {
+    fn position(&self) -> &[f64] {
+        &self.position
+    }
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn iterations(&self) -> Option<u64> {
+        Some(self.iterations)
+    }
+}
+
+
+/// A solution of a stochastic minimization run that additionally reports how the run went,
+/// analogous to `Report`.
+#[derive(Debug, Clone)]
+pub struct SgdReport {
+    /// Position `x` of the returned solution.
+    pub position: Vec<f64>,
+    /// The actual value `f(x)`.
+    pub value: f64,
+    /// The number of performed epochs, i.e., passes over all terms.
+    pub epochs: u64,
+    /// The reason why the minimization stopped.
+    pub convergence: Convergence,
+    /// The exponential moving average of the positions, if maintained by the minimizer,
+    /// see `StochasticGradientDescent::ema_decay()`.
+    pub ema: Option<Vec<f64>>
+}
+
+impl SgdReport {
+    /// Creates a new `SgdReport` given the `position`, the corresponding `value`, the number
+    /// of `epochs` as well as the `convergence` reason.
+    pub fn new(position: Vec<f64>, value: f64, epochs: u64, convergence: Convergence) -> SgdReport {
+        SgdReport {
+            position,
+            value,
+            epochs,
+            convergence,
+            ema: None
+        }
+    }
+}
+
+impl Evaluation for SgdReport {
+    fn position(&self) -> &[f64] {
+        &self.position
+    }
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn iterations(&self) -> Option<u64> {
+        Some(self.epochs)
+    }
+}
+
+
+/// A solution of a trust-region minimization run that additionally reports how the trust
+/// region evolved, e.g., to diagnose why it is shrinking, analogous to `Report`.
+#[derive(Debug, Clone)]
+pub struct TrustRegionReport {
+    /// Position `x` of the lowest corresponding value `f(x)` that has been found.
+    pub position: Vec<f64>,
+    /// The actual value `f(x)`.
+    pub value: f64,
+    /// The number of performed iterations.
+    pub iterations: u64,
+    /// The reason why the minimization stopped.
+    pub convergence: Convergence,
+    /// The radius of the trust region after the adaptation in each iteration.
+    pub radii: Vec<f64>,
+    /// The number of rejected steps.
+    pub rejections: u64
+}
+
+impl TrustRegionReport {
+    /// Creates a new `TrustRegionReport` given the `position`, the corresponding `value`, the
+    /// number of `iterations`, the `convergence` reason as well as the `radii` and the number
+    /// of `rejections`.
+    pub fn new(position: Vec<f64>, value: f64, iterations: u64, convergence: Convergence,
+               radii: Vec<f64>, rejections: u64) -> TrustRegionReport {
+        TrustRegionReport {
+            position,
+            value,
+            iterations,
+            convergence,
+            radii,
+            rejections
+        }
+    }
+}
+
+impl Evaluation for TrustRegionReport {
+    fn position(&self) -> &[f64] {
+        &self.position
+    }
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn iterations(&self) -> Option<u64> {
+        Some(self.iterations)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    use problems::{Problem, Rosenbrock, Sphere, LinearRegression};
+    use gd::GradientDescent;
+    use adapters::Affine;
+
+    use super::{Func, Function, Function1, Function2, Minimizer, Summation, Summation1,
+        Summation2, SummationAccuracy, Solution, VectorFunction, VectorFunction1, MinByValue, best};
+
+    #[test]
+    fn test_minimize_from_best_of() {
+        let minimizer = GradientDescent::new().max_iterations(Some(10));
+        let problem = Rosenbrock::default();
+
+        let worse = minimizer.minimize(&problem, vec![-1.5, 2.0]);
+        let better = minimizer.minimize(&problem, vec![1.0, 1.0]);
+
+        assert!(better.value < worse.value);
+
+        let best = minimizer.minimize_from_best_of(&problem,
+            vec![vec![-1.5, 2.0], vec![1.0, 1.0]]);
+
+        assert_eq!(best.position, better.position);
+        assert_eq!(best.value, better.value);
+
+        // a NaN solution is replaced by any other
+        struct Evaluate;
+
+        impl<F: Function> Minimizer<F> for Evaluate {
+            type Solution = Solution;
+
+            fn minimize(&self, function: &F, initial_position: Vec<f64>) -> Solution {
+                let value = function.value(&initial_position);
+
+                Solution::new(initial_position, value)
+            }
+        }
+
+        let sqrt = Func(|x: &[f64]| x[0].sqrt());
+
+        let best = Evaluate.minimize_from_best_of(&sqrt, vec![vec![-1.0], vec![4.0], vec![9.0]]);
+
+        assert_eq!(best.position, vec![4.0]);
+        assert_eq!(best.value, 2.0);
+    }
+
+    #[test]
+    fn test_minimize_repaired() {
+        let problem = Sphere::bounded(2, -1.0, 1.0);
+
+        let mut start = vec![5.0, -7.0];
+
+        assert!(!problem.is_legal_position(&start));
+
+        problem.repair(&mut start);
+
+        assert_eq!(start, vec![1.0, -1.0]);
+
+        // evaluating the illegal start would panic
+        let solution = GradientDescent::new().max_iterations(Some(1))
+            .minimize_repaired(&problem, vec![5.0, -7.0]);
+
+        assert!(problem.is_legal_position(&solution.position));
+        assert!(solution.value < problem.value(&start));
+    }
+
+    // Rosenbrock function panicking after a fixed number of evaluations
+    struct Flaky {
+        evaluations: Cell<u32>
+    }
+
+    impl Function for Flaky {
+        fn value(&self, position: &[f64]) -> f64 {
+            self.evaluations.set(self.evaluations.get() + 1);
+
+            if self.evaluations.get() > 50 {
+                panic!("flaky function failed");
+            }
+
+            Rosenbrock::default().value(position)
+        }
+    }
+
+    impl Function1 for Flaky {
+        fn gradient(&self, position: &[f64]) -> Vec<f64> {
+            Rosenbrock::default().gradient(position)
+        }
+    }
+
+    #[test]
+    fn test_minimize_catching() {
+        let minimizer = GradientDescent::new();
+
+        let (solution, panicked) = minimizer.minimize_catching(&Rosenbrock::default(),
+            vec![-1.5, 2.0]);
+
+        assert!(!panicked);
+        assert!(solution.value < 1.0e-6);
+
+        let flaky = Flaky { evaluations: Cell::new(0) };
+
+        let (solution, panicked) = minimizer.minimize_catching(&flaky, vec![-1.5, 2.0]);
+
+        assert!(panicked);
+        assert!(solution.value < Rosenbrock::default().value(&[-1.5, 2.0]));
+        assert_eq!(solution.value, Rosenbrock::default().value(&solution.position));
+    }
+
+    #[test]
+    fn test_maximize() {
+        // -‖x‖² + 3 attains its maximum of 3 at the origin
+        let function = Affine::new(Sphere::default(), -1.0, 3.0);
+
+        let solution = GradientDescent::new().maximize(&function, vec![1.0, -2.0]);
+
+        assert!(solution.position.iter().all(|x| x.abs() < 1.0e-3), "{:?} is not the origin",
+            solution.position);
+        assert!((solution.value - 3.0).abs() < 1.0e-6, "{} is not the maximum", solution.value);
+        assert_eq!(solution.value, function.value(&solution.position));
+    }
+
+    // one followed by many tiny values, which are lost when summed up sequentially
+    struct Tiny(usize);
+
+    impl Summation for Tiny {
+        fn terms(&self) -> usize {
+            self.0
+        }
+
+        fn term_value(&self, _position: &[f64], term: usize) -> f64 {
+            if term == 0 { 1.0 } else { 1.0e-16 }
+        }
+    }
+
+    #[test]
+    fn test_shard() {
+        for &terms in &[0, 1, 7, 10, 100, 1001] {
+            let summation = Tiny(terms);
+
+            for shards in 1..13 {
+                let ranges: Vec<_> = (0..shards).map(|index| summation.shard(shards, index)).collect();
+
+                assert_eq!(ranges.first().unwrap().start, 0);
+                assert_eq!(ranges.last().unwrap().end, terms);
+
+                for pair in ranges.windows(2) {
+                    assert_eq!(pair[0].end, pair[1].start);
+                    assert!(pair[0].len() == pair[1].len() || pair[0].len() == pair[1].len() + 1);
+                }
+            }
+        }
+    }
+
+    // g(x, y) = (x y, x + 2y, y²) with J = [[y, x], [1, 2], [0, 2y]]
+    struct Polynomial;
+
+    impl VectorFunction for Polynomial {
+        fn value(&self, x: &[f64]) -> Vec<f64> {
+            vec![x[0] * x[1], x[0] + 2.0 * x[1], x[1] * x[1]]
+        }
+    }
+
+    impl VectorFunction1 for Polynomial {
+        fn jacobian(&self, x: &[f64]) -> Vec<f64> {
+            vec![x[1], x[0], 1.0, 2.0, 0.0, 2.0 * x[1]]
+        }
+    }
+
+    #[test]
+    fn test_jacobian_products() {
+        let position = [3.0, -2.0];
+
+        assert_eq!(Polynomial.jacobian_product(&position, &[1.0, 0.5]), vec![-0.5, 2.0, -2.0]);
+        assert_eq!(Polynomial.jacobian_transpose_product(&position, &[1.0, -1.0, 0.5]),
+            vec![-3.0, -1.0]);
+    }
+
+    #[test]
+    fn test_summation_accuracy() {
+        let tiny = Tiny(1_000_001);
+        let total = 1.0 + 1.0e-10;
+
+        let error = |accuracy| {
+            (tiny.partial_value_with(&[], 0..tiny.terms(), accuracy) - total).abs()
+        };
+
+        assert_eq!(tiny.partial_value_with(&[], 0..tiny.terms(), SummationAccuracy::Sequential),
+            tiny.value(&[]));
+
+        assert!(error(SummationAccuracy::Kahan) < error(SummationAccuracy::Sequential));
+        assert!(error(SummationAccuracy::Pairwise) < error(SummationAccuracy::Sequential));
+        assert!(error(SummationAccuracy::Kahan) < 1.0e-15);
+    }
+
+    // f(x) = ∑ᵢ ½ (aᵢᵀx - bᵢ)² with the Hessians Hᵢ = aᵢaᵢᵀ
+    struct Quadratic(Vec<(Vec<f64>, f64)>);
+
+    impl Summation for Quadratic {
+        fn terms(&self) -> usize {
+            self.0.len()
+        }
+
+        fn term_value(&self, x: &[f64], term: usize) -> f64 {
+            let (ref a, b) = self.0[term];
+
+            0.5 * (a.iter().zip(x).map(|(a, x)| a * x).sum::<f64>() - b).powi(2)
+        }
+    }
+
+    impl Summation1 for Quadratic {
+        fn term_gradient(&self, x: &[f64], term: usize) -> Vec<f64> {
+            let (ref a, b) = self.0[term];
+            let residual = a.iter().zip(x).map(|(a, x)| a * x).sum::<f64>() - b;
+
+            a.iter().map(|a| a * residual).collect()
+        }
+    }
+
+    impl Summation2 for Quadratic {
+        fn term_hessian(&self, _x: &[f64], term: usize) -> Vec<f64> {
+            let a = &self.0[term].0;
+
+            a.iter().flat_map(|ai| a.iter().map(move |aj| ai * aj)).collect()
+        }
+    }
+
+    #[test]
+    fn test_partial_hessian() {
+        let quadratic = Quadratic(vec![
+            (vec![1.0, 2.0, 0.0], 1.0),
+            (vec![0.0, -1.0, 3.0], 2.0),
+            (vec![4.0, 0.0, 1.0], -1.0),
+            (vec![1.0, 1.0, 1.0], 0.5)
+        ]);
+
+        let position = [0.5, -1.0, 2.0];
+
+        // the full Hessian AᵀA of the stacked coefficients A
+        let full: Vec<_> = (0..9).map(|k| {
+            quadratic.0.iter().map(|(a, _)| a[k / 3] * a[k % 3]).sum::<f64>()
+        }).collect();
+
+        assert_eq!(quadratic.partial_hessian(&position, 0..quadratic.terms()), full);
+        assert_eq!(quadratic.hessian(&position), full);
+
+        // the partial Hessians of a partition of the terms add up to the full Hessian
+        let first = quadratic.partial_hessian(&position, [0, 2]);
+        let second = quadratic.partial_hessian(&position, vec![1, 3]);
+
+        let sum: Vec<_> = first.iter().zip(&second).map(|(a, b)| a + b).collect();
+
+        assert_eq!(sum, full);
+        assert_eq!(quadratic.partial_hessian(&position, Vec::<usize>::new()), vec![0.0; 9]);
+    }
+
+    #[test]
+    fn test_value_sample() {
+        let problem = LinearRegression::new(&[13.37, -4.2, 2.5], 1000, 42);
+        let position = [12.0, -4.0, 3.0];
+
+        let value = problem.value(&position);
+        let mut rng = Pcg64Mcg::seed_from_u64(42);
+
+        let exact = problem.value_sample(&position, 1.0, &mut rng);
+
+        assert!((exact - value).abs() < 1.0e-9 * value, "{} vs. {}", exact, value);
+
+        // the standard deviation of a single estimate over 100 terms drawn without
+        // replacement out of 1000
+        let n = problem.terms() as f64;
+        let mean = value / n;
+        let variance = (0..problem.terms())
+            .map(|term| (problem.term_value(&position, term) - mean).powi(2))
+            .sum::<f64>() / (n - 1.0);
+        let deviation = n * (variance / 100.0 * (n - 100.0) / (n - 1.0)).sqrt();
+
+        let estimates: Vec<_> = (0..200)
+            .map(|_| problem.value_sample(&position, 0.1, &mut rng))
+            .collect();
+
+        let average = estimates.iter().sum::<f64>() / estimates.len() as f64;
+
+        assert!((average - value).abs() < 4.0 * deviation / 200.0f64.sqrt(),
+            "{} vs. {}", average, value);
+        assert!(estimates.iter().all(|estimate| (estimate - value).abs() < 5.0 * deviation));
+
+        // a shard is estimated from its own terms only
+        let shard = problem.shard(4, 1);
+        let partial = problem.partial_value(&position, shard.clone());
+        let estimate = problem.partial_value_sample(&position, shard, 1.0,
+            SummationAccuracy::Kahan, &mut rng);
+
+        assert!((estimate - partial).abs() < 1.0e-9 * partial, "{} vs. {}", estimate, partial);
+    }
+
+    #[test]
+    fn test_best() {
+        let solutions = vec![
+            Solution::new(vec![0.0], 3.0),
+            Solution::new(vec![1.0], f64::NAN),
+            Solution::new(vec![2.0], 1.0),
+            Solution::new(vec![3.0], 1.0),
+            Solution::new(vec![4.0], 2.0)
+        ];
+
+        assert_eq!(best(&solutions).unwrap().position, vec![2.0]);
+        assert_eq!(solutions.iter().min_by_value().unwrap().position, vec![2.0]);
+        assert_eq!(solutions.into_iter().rev().min_by_value().unwrap().position, vec![3.0]);
+
+        let nan = vec![Solution::new(vec![1.0], f64::NAN), Solution::new(vec![2.0], -1.0)];
+
+        assert_eq!(best(&nan).unwrap().position, vec![2.0]);
+        assert!(best(&nan[..1]).is_none());
+        assert!(best::<Solution>(&[]).is_none());
+    }
+}
@@ -1,5 +1,6 @@
-#[cfg(test)]
-use std::f64::{MIN_POSITIVE, MAX};
+//! Numerical utilities, e.g., for writing custom convergence checks and tests.
+
+use std::ops::Add;
 
 
 /// Tests whether we reached a flat area, i.e., tests if all absolute gradient component
@@ -9,9 +10,39 @@ pub fn is_saddle_point(gradient: &[f64], tolerance: f64) -> bool {
 }
 
 
+/// Computes the dot product `aᵀb` of two vectors.
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).fold(0.0, Add::add)
+}
+
+
+/// Computes the euclidean norm `‖a‖` of a vector.
+pub fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+
 /// Tests whether two floating point numbers are close using the relative error
 /// and handling special cases like infinity etc.
-#[cfg(test)]
+///
+/// The numbers `a` and `b` are considered close if either
+///
+/// - they are identical, which includes infinities of the same sign,
+/// - one of them is zero or their difference is subnormal, in which case the relative
+///   error is meaningless and the absolute difference has to be below `eps` scaled to the
+///   smallest normal number,
+/// - or their relative error `|a - b| / (|a| + |b|)` is below `eps`.
+///
+/// `NaN` is never close to anything, not even to itself.
+///
+/// # Examples
+///
+/// ```
+/// # use optimization::utils::are_close;
+/// assert!(are_close(1.0, 1.0 + 1.0e-10, 1.0e-6));
+/// assert!(!are_close(1.0, 1.1, 1.0e-6));
+/// assert!(!are_close(1.0e-40, 0.0, 1.0e-6));
+/// ```
 #[allow(clippy::float_cmp)]
 pub fn are_close(a: f64, b: f64, eps: f64) -> bool {
     assert!(eps.is_finite());
@@ -23,19 +54,78 @@ pub fn are_close(a: f64, b: f64, eps: f64) -> bool {
 
     // a or b is zero or both are extremely close to it
     // relative error is less meaningful here
-    || ((a == 0.0 || b == 0.0 || d < MIN_POSITIVE) &&
-        d < eps * MIN_POSITIVE)
+    || ((a == 0.0 || b == 0.0 || d < f64::MIN_POSITIVE) &&
+        d < eps * f64::MIN_POSITIVE)
 
     // finally, use the relative error
-    || d / (a + b).min(MAX) < eps
+    || d / (a.abs() + b.abs()).min(f64::MAX) < eps
+}
+
+
+/// Solves the linear system `A x = b` for a square matrix `A` using Gaussian elimination
+/// with partial pivoting.
+///
+/// The `n × n` matrix is stored row by row, i.e., `Aᵢⱼ` is located at index `i · n + j`.
+/// Returns `None` if the matrix is singular.
+///
+/// # Examples
+///
+/// ```
+/// # use optimization::utils::solve_linear_system;
+/// let x = solve_linear_system(&[2.0, 1.0, 1.0, 3.0], &[3.0, 5.0]).unwrap();
+///
+/// assert!((x[0] - 0.8).abs() < 1.0e-12 && (x[1] - 1.4).abs() < 1.0e-12);
+/// ```
+pub fn solve_linear_system(matrix: &[f64], rhs: &[f64]) -> Option<Vec<f64>> {
+    let n = rhs.len();
+
+    assert_eq!(matrix.len(), n * n, "matrix must be square and match the right-hand side");
+
+    let mut a = matrix.to_vec();
+    let mut x = rhs.to_vec();
+
+    // pivots below this threshold are considered as zero
+    let threshold = f64::EPSILON * matrix.iter().fold(0.0, |m: f64, a| m.max(a.abs()));
+
+    for k in 0..n {
+        // choose the row with the largest pivot to reduce rounding errors
+        let pivot = (k..n).max_by(|&i, &j| a[i * n + k].abs().total_cmp(&a[j * n + k].abs()))
+            .unwrap();
+
+        if a[pivot * n + k].abs() <= threshold {
+            return None;
+        }
+
+        if pivot != k {
+            for j in 0..n {
+                a.swap(k * n + j, pivot * n + j);
+            }
+            x.swap(k, pivot);
+        }
+
+        for i in k + 1..n {
+            let factor = a[i * n + k] / a[k * n + k];
+
+            for j in k..n {
+                a[i * n + j] -= factor * a[k * n + j];
+            }
+            x[i] -= factor * x[k];
+        }
+    }
+
+    for k in (0..n).rev() {
+        let sum = (k + 1..n).map(|j| a[k * n + j] * x[j]).sum::<f64>();
+
+        x[k] = (x[k] - sum) / a[k * n + k];
+    }
+
+    Some(x)
 }
 
 
 #[cfg(test)]
 mod tests {
-    use std::f64::{INFINITY, NAN};
-
-    use super::{is_saddle_point, are_close};
+    use super::{is_saddle_point, are_close, solve_linear_system};
 
     #[test]
     fn test_is_saddle_point() {
@@ -48,10 +138,42 @@ mod tests {
     #[test]
     fn test_are_close() {
         assert!(are_close(1.0, 1.0, 0.00001));
-        assert!(are_close(INFINITY, INFINITY, 0.00001));
+        assert!(are_close(f64::INFINITY, f64::INFINITY, 0.00001));
         assert!(are_close(1.0e-1000, 0.0, 0.1));
         assert!(!are_close(1.0e-40, 0.0, 0.000_001));
         assert!(!are_close(2.0, 1.0, 0.00001));
-        assert!(!are_close(NAN, NAN, 0.00001));
+        assert!(!are_close(f64::NAN, f64::NAN, 0.00001));
+    }
+
+    #[test]
+    fn test_are_close_nan() {
+        assert!(!are_close(f64::NAN, 1.0, 0.1));
+        assert!(!are_close(1.0, f64::NAN, 0.1));
+        assert!(!are_close(f64::NAN, f64::INFINITY, 0.1));
+        assert!(!are_close(0.0, f64::NAN, 0.1));
+    }
+
+    #[test]
+    fn test_are_close_opposite_signs() {
+        assert!(!are_close(1.0, -1.0, 0.1));
+        assert!(!are_close(1.0, -1.000_000_1, 0.1));
+        assert!(!are_close(-1.0e-3, 1.0e-3, 0.1));
+        assert!(!are_close(f64::INFINITY, -f64::INFINITY, 0.1));
+        assert!(are_close(-1.0, -1.000_000_1, 0.00001));
+    }
+
+    #[test]
+    fn test_solve_linear_system() {
+        // requires pivoting due to the leading zero
+        let a = [0.0, 2.0, 1.0,
+                 1.0, 1.0, 1.0,
+                 2.0, 1.0, 3.0];
+        let x = solve_linear_system(&a, &[7.0, 6.0, 13.0]).unwrap();
+
+        for (x, expected) in x.iter().zip(&[1.0, 2.0, 3.0]) {
+            assert!(are_close(*x, *expected, 1.0e-12));
+        }
+
+        assert_eq!(solve_linear_system(&[1.0, 2.0, 2.0, 4.0], &[1.0, 2.0]), None);
     }
 }